@@ -113,6 +113,26 @@ mod trie {
         );
     }
 
+    pub fn contains(_: &mut Criterion) {
+        let times = 100;
+
+        super::c().bench_function(
+            &format!("[{}] Trie::contains() {} times", super::git_hash(), times),
+            move |b| {
+                b.iter_batched(
+                    || &TRIE_EDICT,
+                    |trie| {
+                        // Unlike exact_match(), contains() does not allocate.
+                        for _ in 0..times {
+                            assert!(trie.contains("すしをにぎる"));
+                        }
+                    },
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+    }
+
     pub fn predictive_search(_: &mut Criterion) {
         let times = 100;
 
@@ -272,6 +292,7 @@ criterion_group!(
     benches,
     trie::build,
     trie::exact_match,
+    trie::contains,
     trie::predictive_search,
     trie::predictive_search_big_output,
     trie::predictive_search_limited_big_output,
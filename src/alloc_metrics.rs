@@ -0,0 +1,188 @@
+//! Allocation counts for searches, under the crate feature "alloc-metrics".
+//!
+//! Counting needs [CountingAllocator] installed as the global allocator.
+//! Search iterators then report through [Stats::stats] the allocations made
+//! while advancing them, and for predictive searches also while descending to
+//! the query. Only allocations on the iterating thread are counted.
+//!
+//! ```rust
+//! use trie_rs::alloc_metrics::{CountingAllocator, Stats};
+//! use trie_rs::Trie;
+//!
+//! #[global_allocator]
+//! static ALLOCATOR: CountingAllocator = CountingAllocator;
+//!
+//! let trie = Trie::from_iter(["a", "app", "apple"]);
+//! let mut results = trie.predictive_search::<String, _>("app");
+//! while results.next().is_some() {}
+//! assert!(results.stats().allocations > 0);
+//! ```
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::ops::AddAssign;
+
+thread_local! {
+    static ALLOCATIONS: Cell<u64> = const { Cell::new(0) };
+    static BYTES: Cell<u64> = const { Cell::new(0) };
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// Allocations made by a search.
+pub struct SearchStats {
+    /// Number of allocations and reallocations.
+    pub allocations: u64,
+    /// Bytes requested by those allocations.
+    pub bytes: u64,
+}
+
+impl AddAssign for SearchStats {
+    fn add_assign(&mut self, other: Self) {
+        self.allocations += other.allocations;
+        self.bytes += other.bytes;
+    }
+}
+
+/// Search iterators that report their allocations.
+pub trait Stats {
+    /// Return the allocations made so far by this search.
+    fn stats(&self) -> SearchStats;
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+/// A global allocator that forwards to [System] and counts allocations per
+/// thread.
+pub struct CountingAllocator;
+
+fn count(bytes: usize) {
+    // The thread locals may already be gone while a thread shuts down.
+    let _ = ALLOCATIONS.try_with(|a| a.set(a.get() + 1));
+    let _ = BYTES.try_with(|b| b.set(b.get() + bytes as u64));
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        count(layout.size());
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        count(new_size);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+fn snapshot() -> SearchStats {
+    SearchStats {
+        allocations: ALLOCATIONS.with(Cell::get),
+        bytes: BYTES.with(Cell::get),
+    }
+}
+
+/// Measures the allocations between [Probe::start] and [Probe::finish].
+pub(crate) struct Probe(SearchStats);
+
+impl Probe {
+    pub(crate) fn start() -> Self {
+        Probe(snapshot())
+    }
+
+    pub(crate) fn finish(self, stats: &mut SearchStats) {
+        let now = snapshot();
+        *stats += SearchStats {
+            allocations: now.allocations - self.0.allocations,
+            bytes: now.bytes - self.0.bytes,
+        };
+    }
+}
+
+#[cfg(test)]
+mod alloc_metrics_tests {
+    use super::{CountingAllocator, Probe, SearchStats, Stats};
+    use crate::map::Trie;
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    fn build_trie() -> Trie<u8, u8> {
+        Trie::from_iter([("a", 0), ("app", 1), ("apple", 2), ("better", 3)])
+    }
+
+    #[test]
+    fn predictive_search() {
+        let trie = build_trie();
+        let mut iter = trie.predictive_search::<Vec<u8>, _>("app");
+        let created = iter.stats();
+        assert!(created.allocations > 0);
+        assert_eq!(iter.by_ref().count(), 2);
+        assert!(iter.stats().allocations > created.allocations);
+    }
+
+    #[test]
+    fn contains_key_does_not_allocate() {
+        use crate::map::{MultiTrie, UpdatableTrie};
+        let trie = build_trie();
+        let set = crate::Trie::from_iter(["a", "app"]);
+        let mut builder = crate::map::CaseInsensitiveTrieBuilder::new();
+        builder.push("App", 0);
+        let folded = builder.build();
+        let remapped = build_trie().remap_alphabet();
+        let mut updatable = UpdatableTrie::from(build_trie());
+        updatable.insert("apply", 4);
+        let multi = MultiTrie::new([&trie]);
+        let snapshots = crate::map::SnapshotTrie::from(build_trie());
+        snapshots.remove("a");
+        let start = std::time::Instant::now();
+        let later = start + std::time::Duration::from_secs(1);
+        let mut builder = crate::map::TrieBuilder::new();
+        builder.push("app", (later, 0));
+        let expiring = crate::map::ExpiringTrie::from(builder.build());
+
+        let probe = Probe::start();
+        for query in ["", "a", "ap", "apple", "applex", "better", "z"] {
+            let expected = trie.exact_match(query).is_some();
+            assert_eq!(trie.contains_key(query), expected);
+            assert_eq!(remapped.contains_key(query), expected);
+            assert_eq!(multi.contains_key(query), expected);
+        }
+        assert!(set.contains("app"));
+        assert!(folded.contains_key("aPP"));
+        assert!(updatable.contains_key("apply"));
+        assert!(!snapshots.contains_key("a"));
+        assert!(snapshots.contains_key("app"));
+        assert!(expiring.contains_key("app", start));
+        assert!(!expiring.contains_key("app", later));
+        let mut stats = SearchStats::default();
+        probe.finish(&mut stats);
+        assert_eq!(stats, SearchStats::default());
+    }
+
+    #[test]
+    fn deep_keys_do_not_regrow_buffers() {
+        // As deep as the label buffer is preallocated for.
+        let trie = Trie::from_iter((1..=256).map(|len| (vec![b'a'; len], ())));
+        let mut iter = trie.postfix_search::<Vec<u8>, _>("a");
+        let created = iter.stats();
+        assert_eq!(iter.by_ref().count(), 255);
+        // One allocation per collected key and none for the label buffer.
+        assert_eq!(iter.stats().allocations - created.allocations, 255);
+    }
+
+    #[test]
+    fn miss_allocates_little() {
+        let trie = build_trie();
+        let mut iter = trie.common_prefix_search::<Vec<u8>, _>("zzz");
+        assert!(iter.next().is_none());
+        let mut postfix = trie.postfix_search::<Vec<u8>, _>("zzz");
+        assert!(postfix.next().is_none());
+        assert_eq!(postfix.stats(), SearchStats::default());
+    }
+}
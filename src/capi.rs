@@ -0,0 +1,300 @@
+//! A C API over byte tries, under the crate feature "capi", for embedding a
+//! dictionary in apps written in other languages.
+//!
+//! Everything goes through handles: a trie built from a buffer of keys, and
+//! incremental searches on it that are stepped one byte at a time and copy
+//! their completions into caller buffers. Handles are freed with their
+//! `_free` function, and a search must be freed before its trie.
+//!
+//! Build a C library with e.g. `cargo rustc --release --features capi
+//! --crate-type cdylib`, and declare the functions as:
+//!
+//! ```c
+//! typedef struct TrieRsTrie TrieRsTrie;
+//! typedef struct TrieRsSearch TrieRsSearch;
+//!
+//! TrieRsTrie *trie_rs_new(const uint8_t *keys, size_t len);
+//! void trie_rs_free(TrieRsTrie *trie);
+//! TrieRsSearch *trie_rs_search_new(const TrieRsTrie *trie);
+//! void trie_rs_search_free(TrieRsSearch *search);
+//! void trie_rs_search_reset(TrieRsSearch *search);
+//! int32_t trie_rs_search_push(TrieRsSearch *search, uint8_t label);
+//! size_t trie_rs_search_completions(TrieRsSearch *search, size_t skip,
+//!                                   uint8_t *buf, size_t cap, size_t *written);
+//! ```
+use crate::inc_search::{Answer, IncSearch, Position};
+use crate::map::TrieBuilder;
+use crate::Trie;
+use std::ptr;
+
+/// [trie_rs_search_push] result: the query is in no key.
+pub const TRIE_RS_NONE: i32 = 0;
+/// [trie_rs_search_push] bit: the query is a proper prefix of some key.
+pub const TRIE_RS_PREFIX: i32 = 1;
+/// [trie_rs_search_push] bit: the query is a key.
+pub const TRIE_RS_MATCH: i32 = 2;
+
+/// An opaque trie of byte keys.
+pub type TrieRsTrie = Trie<u8>;
+
+/// An opaque incremental search on a [TrieRsTrie].
+#[derive(Debug)]
+pub struct TrieRsSearch {
+    trie: *const TrieRsTrie,
+    position: Position,
+    /// Where the last page of completions stopped: the search position, the
+    /// keys paged through and the next key.
+    cursor: Option<(Position, usize, Vec<u8>)>,
+}
+
+impl TrieRsSearch {
+    /// # Safety
+    /// The trie must still be alive.
+    unsafe fn inc_search(&self) -> IncSearch<'_, u8, ()> {
+        IncSearch::resume(&(*self.trie).0, self.position)
+    }
+}
+
+/// Build a trie from `len` bytes of keys separated by `'\n'`. Empty keys are
+/// skipped. Returns null if `keys` is null.
+///
+/// # Safety
+/// `keys` must point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn trie_rs_new(keys: *const u8, len: usize) -> *mut TrieRsTrie {
+    if keys.is_null() {
+        return ptr::null_mut();
+    }
+    let keys = std::slice::from_raw_parts(keys, len);
+    let mut builder = TrieBuilder::new();
+    for key in keys.split(|b| *b == b'\n').filter(|key| !key.is_empty()) {
+        builder.push(key, ());
+    }
+    Box::into_raw(Box::new(Trie(builder.build())))
+}
+
+/// Free a trie from [trie_rs_new]. Null is ignored.
+///
+/// # Safety
+/// `trie` must come from [trie_rs_new], not be freed yet, and have no
+/// searches left.
+#[no_mangle]
+pub unsafe extern "C" fn trie_rs_free(trie: *mut TrieRsTrie) {
+    if !trie.is_null() {
+        drop(Box::from_raw(trie));
+    }
+}
+
+/// Start an incremental search at the root of `trie`. Returns null if `trie`
+/// is null.
+///
+/// # Safety
+/// `trie` must be a live trie, which outlives the search.
+#[no_mangle]
+pub unsafe extern "C" fn trie_rs_search_new(trie: *const TrieRsTrie) -> *mut TrieRsSearch {
+    if trie.is_null() {
+        return ptr::null_mut();
+    }
+    let position = IncSearch::new(&(*trie).0).into();
+    Box::into_raw(Box::new(TrieRsSearch {
+        trie,
+        position,
+        cursor: None,
+    }))
+}
+
+/// Free a search from [trie_rs_search_new]. Null is ignored.
+///
+/// # Safety
+/// `search` must come from [trie_rs_search_new] and not be freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn trie_rs_search_free(search: *mut TrieRsSearch) {
+    if !search.is_null() {
+        drop(Box::from_raw(search));
+    }
+}
+
+/// Go back to the root, i.e. the empty query.
+///
+/// # Safety
+/// `search` must be a live search of a live trie.
+#[no_mangle]
+pub unsafe extern "C" fn trie_rs_search_reset(search: *mut TrieRsSearch) {
+    let search = &mut *search;
+    let mut inc_search = search.inc_search();
+    inc_search.reset();
+    search.position = inc_search.into();
+}
+
+/// Append `label` to the query. Returns [TRIE_RS_NONE] and leaves the search
+/// where it was if no key continues with it, otherwise [TRIE_RS_PREFIX],
+/// [TRIE_RS_MATCH] or both.
+///
+/// # Safety
+/// `search` must be a live search of a live trie.
+#[no_mangle]
+pub unsafe extern "C" fn trie_rs_search_push(search: *mut TrieRsSearch, label: u8) -> i32 {
+    let search = &mut *search;
+    let mut inc_search = search.inc_search();
+    let answer = inc_search.query(&label);
+    search.position = inc_search.into();
+    match answer {
+        None => TRIE_RS_NONE,
+        Some(Answer::Prefix) => TRIE_RS_PREFIX,
+        Some(Answer::Match) => TRIE_RS_MATCH,
+        Some(Answer::PrefixAndMatch) => TRIE_RS_PREFIX | TRIE_RS_MATCH,
+    }
+}
+
+/// Copy the keys starting with the query, in lexicographic order and after
+/// skipping the first `skip`, into `buf`, each followed by `'\n'`. Stops
+/// before the first key that does not fit in `cap` bytes.
+///
+/// Returns the number of keys copied and stores the bytes used in `written`
+/// if it is not null. Call again with `skip` increased by the result to page
+/// through the rest; the search remembers where the last page stopped, so
+/// the next one starts there instead of skipping through the keys again. If
+/// not even the first key fits, nothing is copied, 0 is returned and
+/// `written` holds the bytes it needs, so the caller may grow `buf` and call
+/// again. At the end of the keys, 0 is returned with `written` 0.
+///
+/// # Safety
+/// `search` must be a live search of a live trie, and `buf` must point to
+/// `cap` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn trie_rs_search_completions(
+    search: *mut TrieRsSearch,
+    skip: usize,
+    buf: *mut u8,
+    cap: usize,
+    written: *mut usize,
+) -> usize {
+    let search = &mut *search;
+    let trie = &(*search.trie).0;
+    let prefix: Vec<u8> = search.inc_search().prefix();
+    let mut keys = trie.predictive_search::<Vec<u8>, _>(prefix);
+    match &search.cursor {
+        Some((position, paged, next)) if *position == search.position && *paged == skip => {
+            keys.skip_to(next);
+        }
+        _ => {
+            if let Some(last) = skip.checked_sub(1) {
+                keys.nth(last);
+            }
+        }
+    }
+    let mut used = 0;
+    let mut count = 0;
+    let mut needed = 0;
+    if !buf.is_null() {
+        let buf = std::slice::from_raw_parts_mut(buf, cap);
+        for (key, _) in keys {
+            if used + key.len() + 1 > cap {
+                if count == 0 {
+                    needed = key.len() + 1;
+                }
+                search.cursor = Some((search.position, skip + count, key));
+                break;
+            }
+            buf[used..used + key.len()].copy_from_slice(&key);
+            buf[used + key.len()] = b'\n';
+            used += key.len() + 1;
+            count += 1;
+        }
+    }
+    if !written.is_null() {
+        *written = used.max(needed);
+    }
+    count
+}
+
+#[cfg(test)]
+mod capi_tests {
+    use super::*;
+
+    #[test]
+    fn search_and_completions() {
+        let keys = b"apple\napp\n\nbanana\napplication";
+        unsafe {
+            let trie = trie_rs_new(keys.as_ptr(), keys.len());
+            let search = trie_rs_search_new(trie);
+            assert_eq!(trie_rs_search_push(search, b'a'), TRIE_RS_PREFIX);
+            assert_eq!(trie_rs_search_push(search, b'p'), TRIE_RS_PREFIX);
+            assert_eq!(
+                trie_rs_search_push(search, b'p'),
+                TRIE_RS_PREFIX | TRIE_RS_MATCH
+            );
+            assert_eq!(trie_rs_search_push(search, b'x'), TRIE_RS_NONE);
+
+            let mut buf = [0u8; 16];
+            let mut written = 0;
+            let count =
+                trie_rs_search_completions(search, 0, buf.as_mut_ptr(), buf.len(), &mut written);
+            assert_eq!(count, 2);
+            assert_eq!(&buf[..written], b"app\napple\n");
+            let count =
+                trie_rs_search_completions(search, 2, buf.as_mut_ptr(), buf.len(), &mut written);
+            assert_eq!(count, 1);
+            assert_eq!(&buf[..written], b"application\n");
+            let count =
+                trie_rs_search_completions(search, 3, buf.as_mut_ptr(), buf.len(), &mut written);
+            assert_eq!((count, written), (0, 0));
+
+            trie_rs_search_reset(search);
+            assert_eq!(trie_rs_search_push(search, b'b'), TRIE_RS_PREFIX);
+            trie_rs_search_free(search);
+            trie_rs_free(trie);
+            assert!(trie_rs_new(ptr::null(), 0).is_null());
+        }
+    }
+
+    #[test]
+    fn paging() {
+        let keys: Vec<u8> = (0..1000)
+            .flat_map(|i| format!("key{:04}\n", i).into_bytes())
+            .collect();
+        unsafe {
+            let trie = trie_rs_new(keys.as_ptr(), keys.len());
+            let search = trie_rs_search_new(trie);
+            let mut buf = [0u8; 32];
+            let mut written = 0;
+            let mut skip = 0;
+            let mut paged = Vec::new();
+            loop {
+                let count = trie_rs_search_completions(
+                    search,
+                    skip,
+                    buf.as_mut_ptr(),
+                    buf.len(),
+                    &mut written,
+                );
+                if count == 0 {
+                    break;
+                }
+                skip += count;
+                paged.extend_from_slice(&buf[..written]);
+                // Started where the last page stopped.
+                assert!(matches!(&(*search).cursor, Some((_, n, _)) if *n == skip) || skip == 1000);
+            }
+            assert_eq!(paged, keys);
+
+            // Paging from elsewhere, or after the search moved, skips anew.
+            let count =
+                trie_rs_search_completions(search, 998, buf.as_mut_ptr(), buf.len(), &mut written);
+            assert_eq!(count, 2);
+            assert_eq!(&buf[..written], b"key0998\nkey0999\n");
+            trie_rs_search_push(search, b'k');
+            let count =
+                trie_rs_search_completions(search, 3, buf.as_mut_ptr(), buf.len(), &mut written);
+            assert_eq!(count, 4);
+            assert_eq!(&buf[..8], b"key0003\n");
+
+            // Too small for the next key: learn the size it needs.
+            let count = trie_rs_search_completions(search, 0, buf.as_mut_ptr(), 5, &mut written);
+            assert_eq!((count, written), (0, 8));
+
+            trie_rs_search_free(search);
+            trie_rs_free(trie);
+        }
+    }
+}
@@ -39,7 +39,7 @@
 //! This means the above code restores the time complexity of _O(m log n)_ for
 //! the loop.
 use crate::{
-    map::Trie,
+    map::{NodeRef, Trie},
     try_collect::{TryCollect, TryFromIterator},
 };
 use louds_rs::LoudsNodeNum;
@@ -89,7 +89,7 @@ impl Answer {
         matches!(self, Answer::Match | Answer::PrefixAndMatch)
     }
 
-    fn new(is_prefix: bool, is_match: bool) -> Option<Self> {
+    pub(crate) fn new(is_prefix: bool, is_match: bool) -> Option<Self> {
         match (is_prefix, is_match) {
             (true, false) => Some(Answer::Prefix),
             (false, true) => Some(Answer::Match),
@@ -134,36 +134,18 @@ impl<'a, Label: Ord, Value> IncSearch<'a, Label, Value> {
 
     /// Query but do not change the node we're looking at on the trie.
     pub fn peek(&self, chr: &Label) -> Option<Answer> {
-        let children_node_nums: Vec<_> = self.trie.children_node_nums(self.node).collect();
-        let res = self
-            .trie
-            .bin_search_by_children_labels(chr, &children_node_nums[..]);
-        match res {
-            Ok(j) => {
-                let node = children_node_nums[j];
-                let is_prefix = self.trie.has_children_node_nums(node);
-                let is_match = self.trie.value(node).is_some();
-                Answer::new(is_prefix, is_match)
-            }
-            Err(_) => None,
-        }
+        let node = self.trie.child(self.node, chr)?;
+        let is_prefix = self.trie.has_children_node_nums(node);
+        let is_match = self.trie.value(node).is_some();
+        Answer::new(is_prefix, is_match)
     }
 
     /// Query the trie and go to node if there is a match.
     pub fn query(&mut self, chr: &Label) -> Option<Answer> {
-        let children_node_nums: Vec<_> = self.trie.children_node_nums(self.node).collect();
-        let res = self
-            .trie
-            .bin_search_by_children_labels(chr, &children_node_nums[..]);
-        match res {
-            Ok(j) => {
-                self.node = children_node_nums[j];
-                let is_prefix = self.trie.has_children_node_nums(self.node);
-                let is_match = self.trie.value(self.node).is_some();
-                Answer::new(is_prefix, is_match)
-            }
-            Err(_) => None,
-        }
+        self.node = self.trie.child(self.node, chr)?;
+        let is_prefix = self.trie.has_children_node_nums(self.node);
+        let is_match = self.trie.value(self.node).is_some();
+        Answer::new(is_prefix, is_match)
     }
 
     /// Query the trie with a sequence. Will return `Err(index of query)` on
@@ -181,6 +163,44 @@ impl<'a, Label: Ord, Value> IncSearch<'a, Label, Value> {
         result.ok_or(i)
     }
 
+    /// Reset and query the trie with `query` in one call. Like
+    /// [IncSearch::query_until], return `Err(index of query)` on the first
+    /// failure to match, leaving the search on the longest matched prefix.
+    ///
+    /// ```
+    /// use trie_rs::{Trie, inc_search::Answer};
+    ///
+    /// let trie: Trie<u8> = ["hello", "help"].into_iter().collect();
+    /// let mut inc_search = trie.inc_search();
+    /// assert_eq!(inc_search.goto("help"), Ok(Answer::Match));
+    /// assert_eq!(inc_search.goto("hex"), Err(2));
+    /// assert_eq!(inc_search.prefix::<String, _>(), "he");
+    /// ```
+    pub fn goto(&mut self, query: impl AsRef<[Label]>) -> Result<Answer, usize> {
+        self.reset();
+        self.query_until(query)
+    }
+
+    /// Go to `node`, e.g. one found with [Trie::node] or [Trie::children].
+    /// Return the [Answer] for it, which is `None` only for an empty trie's
+    /// root.
+    ///
+    /// # Panics
+    /// If `node` belongs to another trie.
+    pub fn goto_node(&mut self, node: NodeRef<'a, Label, Value>) -> Option<Answer> {
+        assert!(
+            std::ptr::eq(node.trie(), self.trie),
+            "node belongs to another trie"
+        );
+        self.node = node.louds_node_num();
+        node.answer()
+    }
+
+    /// Return the node the search is on.
+    pub fn node(&self) -> NodeRef<'a, Label, Value> {
+        NodeRef::new(self.trie, self.node)
+    }
+
     /// Return the value at current node. There should be one for any node where
     /// `answer.is_match()` is true.
     pub fn value(&self) -> Option<&'a Value> {
@@ -261,6 +281,135 @@ impl<'a, Label: Ord, Value> IncSearch<'a, Label, Value> {
     }
 }
 
+#[derive(Debug, Clone)]
+/// An [IncSearch] fed raw tokens that a normalizer turns into labels first,
+/// e.g. characters that are case folded or decomposed into several labels.
+/// Obtained from [IncSearch::with_normalizer].
+///
+/// It keeps both the raw and the normalized query, and remembers where each
+/// raw token started, so [NormalizedIncSearch::pop] takes back one raw token
+/// however many labels it became.
+///
+/// ```
+/// use trie_rs::Trie;
+///
+/// let trie: Trie<char> = ["strasse", "stra\u{df}e"].map(|s| s.chars().collect::<Vec<_>>()).into_iter().collect();
+/// // Lowercase, and spell 'ß' as "ss".
+/// let mut search = trie.inc_search().with_normalizer(|raw: &char, out: &mut Vec<char>| {
+///     match raw.to_lowercase().next().unwrap() {
+///         'ß' => out.extend(['s', 's']),
+///         c => out.push(c),
+///     }
+/// });
+/// for c in "STRA\u{df}".chars() {
+///     assert!(search.push(c).is_some());
+/// }
+/// assert_eq!(search.raw(), ['S', 'T', 'R', 'A', '\u{df}']);
+/// assert_eq!(search.normalized(), ['s', 't', 'r', 'a', 's', 's']);
+/// // Backspace removes both labels of 'ß'.
+/// assert_eq!(search.pop(), Some('\u{df}'));
+/// assert_eq!(search.normalized(), ['s', 't', 'r', 'a']);
+/// ```
+pub struct NormalizedIncSearch<'a, Raw, Label, Value, N> {
+    search: IncSearch<'a, Label, Value>,
+    normalizer: N,
+    raw: Vec<Raw>,
+    normalized: Vec<Label>,
+    /// For each raw token, where the search was and how many labels were
+    /// normalized before it.
+    starts: Vec<(Position, usize)>,
+    /// Scratch space for the labels of one raw token.
+    buffer: Vec<Label>,
+}
+
+impl<'a, Label: Ord, Value> IncSearch<'a, Label, Value> {
+    /// Feed this search raw tokens through `normalizer`, which appends the
+    /// labels each one stands for to its second argument. See
+    /// [NormalizedIncSearch].
+    pub fn with_normalizer<Raw, N>(
+        self,
+        normalizer: N,
+    ) -> NormalizedIncSearch<'a, Raw, Label, Value, N>
+    where
+        N: FnMut(&Raw, &mut Vec<Label>),
+    {
+        NormalizedIncSearch {
+            search: self,
+            normalizer,
+            raw: Vec::new(),
+            normalized: Vec::new(),
+            starts: Vec::new(),
+            buffer: Vec::new(),
+        }
+    }
+}
+
+impl<'a, Raw, Label: Ord + Clone, Value, N> NormalizedIncSearch<'a, Raw, Label, Value, N>
+where
+    N: FnMut(&Raw, &mut Vec<Label>),
+{
+    /// Normalize `raw` and query the trie with its labels. If they all match,
+    /// go there and return the [Answer]; otherwise stay put and return
+    /// `None`. A token normalized to no labels matches where the search is,
+    /// so it is pushed unless the trie has no keys at all.
+    pub fn push(&mut self, raw: Raw) -> Option<Answer> {
+        self.buffer.clear();
+        (self.normalizer)(&raw, &mut self.buffer);
+        let start = self.search.node;
+        let answer = if self.buffer.is_empty() {
+            self.search.node().answer()
+        } else {
+            self.search.query_until(&self.buffer).ok()
+        };
+        if answer.is_none() {
+            self.search.node = start;
+            return None;
+        }
+        self.starts.push((start, self.normalized.len()));
+        self.raw.push(raw);
+        self.normalized.append(&mut self.buffer);
+        answer
+    }
+
+    /// Take back the last raw token and all the labels it was normalized to.
+    pub fn pop(&mut self) -> Option<Raw> {
+        let (start, len) = self.starts.pop()?;
+        self.normalized.truncate(len);
+        self.search.node = start;
+        self.raw.pop()
+    }
+
+    /// Return the raw tokens pushed so far.
+    pub fn raw(&self) -> &[Raw] {
+        &self.raw
+    }
+
+    /// Return the labels the raw tokens were normalized to.
+    pub fn normalized(&self) -> &[Label] {
+        &self.normalized
+    }
+
+    /// Return the search underneath.
+    pub fn inc_search(&self) -> &IncSearch<'a, Label, Value> {
+        &self.search
+    }
+
+    /// Return the value at the current node.
+    pub fn value(&self) -> Option<&'a Value> {
+        self.search.value()
+    }
+
+    /// Go back to where the search started.
+    pub fn reset(&mut self) {
+        if let Some((start, _)) = self.starts.first() {
+            self.search.node = *start;
+        }
+        self.starts.clear();
+        self.raw.clear();
+        self.normalized.clear();
+    }
+}
+
 #[cfg(test)]
 mod search_tests {
     use super::*;
@@ -362,6 +511,82 @@ mod search_tests {
         assert_eq!(Some(&2), search.value());
     }
 
+    #[test]
+    fn inc_search_goto() {
+        let trie = build_trie();
+        let mut search = trie.inc_search();
+        assert_eq!(Ok(Answer::PrefixAndMatch), search.goto("app"));
+        assert_eq!(Ok(Answer::Match), search.goto("better"));
+        assert_eq!(Err(2), search.goto("apz"));
+        assert_eq!("ap", search.prefix::<String, _>());
+
+        let node = trie.children("appl").next().unwrap();
+        assert_eq!(Some(Answer::Match), search.goto_node(node));
+        assert_eq!("apple", search.prefix::<String, _>());
+        assert_eq!(Some(&2), search.value());
+        assert_eq!(search.node(), node);
+        assert_eq!(None, search.query(&b'x'));
+        assert_eq!(search.node().parent().unwrap().label(), Some(&b'l'));
+    }
+
+    #[test]
+    #[should_panic(expected = "node belongs to another trie")]
+    fn inc_search_goto_node_other_trie() {
+        let trie = build_trie();
+        let other = build_trie();
+        let mut search = trie.inc_search();
+        search.goto_node(other.node("a").unwrap());
+    }
+
+    #[test]
+    fn inc_search_normalized() {
+        let trie = build_trie();
+        let mut search = trie
+            .inc_search()
+            .with_normalizer(|c: &char, out: &mut Vec<u8>| {
+                let mut buf = [0; 4];
+                out.extend(c.to_ascii_lowercase().encode_utf8(&mut buf).bytes());
+            });
+        assert_eq!(search.push('A'), Some(Answer::PrefixAndMatch));
+        assert_eq!(search.push('z'), None);
+        assert_eq!(search.raw(), ['A']);
+        search.reset();
+        for c in "アップ".chars() {
+            assert_eq!(search.push(c), Some(Answer::Prefix));
+        }
+        assert_eq!(search.normalized().len(), 9);
+        assert_eq!(search.push('ル'), Some(Answer::Prefix));
+        assert_eq!(search.push('🍎'), Some(Answer::Match));
+        assert_eq!(search.value(), Some(&5));
+        assert_eq!(search.pop(), Some('🍎'));
+        assert_eq!(search.pop(), Some('ル'));
+        assert_eq!(search.normalized(), "アップ".as_bytes());
+        assert_eq!(search.inc_search().prefix::<String, _>(), "アップ");
+        search.reset();
+        assert_eq!(search.pop(), None);
+        assert_eq!(search.inc_search().prefix_len(), 0);
+    }
+
+    #[test]
+    fn inc_search_normalized_to_nothing() {
+        let skip_spaces = |c: &char, out: &mut Vec<u8>| {
+            if *c != ' ' {
+                out.push(*c as u8);
+            }
+        };
+        let trie = build_trie();
+        let mut search = trie.inc_search().with_normalizer(skip_spaces);
+        assert_eq!(search.push(' '), Some(Answer::Prefix));
+        assert_eq!(search.push('a'), Some(Answer::PrefixAndMatch));
+        assert_eq!(search.push(' '), Some(Answer::PrefixAndMatch));
+        assert_eq!(search.raw(), [' ', 'a', ' ']);
+
+        let empty: Trie<u8, u8> = TrieBuilder::new().build();
+        let mut search = empty.inc_search().with_normalizer(skip_spaces);
+        assert_eq!(search.push(' '), None);
+        assert!(search.raw().is_empty());
+    }
+
     // #[test]
     // fn inc_serach_value_mut() {
     //     let trie = build_trie();
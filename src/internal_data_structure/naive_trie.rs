@@ -84,6 +84,12 @@ pub enum NaiveTrie<Label, Value> {
 pub struct NaiveTrieRoot<Label, Value> {
     /// Sorted by Label's order.
     children: Vec<NaiveTrie<Label, Value>>,
+    /// Number of pushes so far.
+    pushes: usize,
+    /// Child indices from the root along the last pushed word. Sorted input
+    /// shares long prefixes between pushes, which can follow these instead
+    /// of searching.
+    last_path: Vec<usize>,
 }
 
 #[derive(Debug, Clone)]
@@ -94,4 +100,6 @@ pub struct NaiveTrieIntermOrLeaf<Label, Value> {
     children: Vec<NaiveTrie<Label, Value>>,
     pub(crate) label: Label,
     pub(crate) value: Option<Value>,
+    /// Index of the last push that ended here. Meaningful if `value` is some.
+    pub(crate) seq: usize,
 }
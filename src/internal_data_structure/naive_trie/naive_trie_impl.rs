@@ -4,7 +4,11 @@ use std::vec::Drain;
 
 impl<'trie, Label: Ord, Value> NaiveTrie<Label, Value> {
     pub fn make_root() -> Self {
-        NaiveTrie::Root(NaiveTrieRoot { children: vec![] })
+        NaiveTrie::Root(NaiveTrieRoot {
+            children: vec![],
+            pushes: 0,
+            last_path: vec![],
+        })
     }
 
     pub fn make_interm_or_leaf(label: Label, terminal: Option<Value>) -> Self {
@@ -12,17 +16,51 @@ impl<'trie, Label: Ord, Value> NaiveTrie<Label, Value> {
             children: vec![],
             label,
             value: terminal,
+            seq: 0,
         })
     }
 
+    /// Return the number of pushes into this root.
+    pub fn pushes(&self) -> usize {
+        match self {
+            NaiveTrie::Root(node) => node.pushes,
+            _ => panic!("Unexpected type"),
+        }
+    }
+
     pub fn push<Arr: Iterator<Item = Label>>(&'trie mut self, word: Arr, value: Value) {
-        let mut trie = self;
+        let (seq, last_path) = match self {
+            NaiveTrie::Root(node) => {
+                node.pushes += 1;
+                (node.pushes - 1, std::mem::take(&mut node.last_path))
+            }
+            _ => panic!("Unexpected type"),
+        };
+        let mut path = Vec::with_capacity(last_path.len());
+        // Whether this word so far follows the last one.
+        let mut on_last_path = true;
+        let mut trie = &mut *self;
         for chr in word {
-            let res = trie
-                .children()
-                .binary_search_by(|child| child.label().cmp(&chr));
+            let children = trie.children();
+            let cached = last_path
+                .get(path.len())
+                .filter(|&&j| on_last_path && children.get(j).map(Self::label) == Some(&chr));
+            let res = match cached {
+                Some(&j) => Ok(j),
+                None => {
+                    on_last_path = false;
+                    match children.last() {
+                        // Sorted input appends after the last child.
+                        Some(last) if last.label() >= &chr => {
+                            children.binary_search_by(|child| child.label().cmp(&chr))
+                        }
+                        _ => Err(children.len()),
+                    }
+                }
+            };
             match res {
                 Ok(j) => {
+                    path.push(j);
                     trie = match trie {
                         NaiveTrie::Root(node) => &mut node.children[j],
                         NaiveTrie::IntermOrLeaf(node) => &mut node.children[j],
@@ -30,6 +68,7 @@ impl<'trie, Label: Ord, Value> NaiveTrie<Label, Value> {
                     };
                 }
                 Err(j) => {
+                    path.push(j);
                     let child_trie = Self::make_interm_or_leaf(chr, None);
                     trie = match trie {
                         NaiveTrie::Root(node) => {
@@ -46,8 +85,14 @@ impl<'trie, Label: Ord, Value> NaiveTrie<Label, Value> {
             };
         }
         match trie {
-            NaiveTrie::IntermOrLeaf(node) => node.value = Some(value),
-            _ => panic!("Unexpected type"),
+            NaiveTrie::IntermOrLeaf(node) => {
+                node.value = Some(value);
+                node.seq = seq;
+            }
+            _ => panic!("Cannot push an empty key: the root holds no value"),
+        }
+        if let NaiveTrie::Root(node) = self {
+            node.last_path = path;
         }
     }
 
@@ -0,0 +1,83 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+
+#[derive(Debug, Clone)]
+/// Yields only the first item of each canonical form. See
+/// [DedupCanonicalExt::dedup_canonical].
+pub struct DedupCanonical<I, F, K> {
+    iter: I,
+    canonical: F,
+    seen: HashSet<K>,
+}
+
+impl<I, F, K> Iterator for DedupCanonical<I, F, K>
+where
+    I: Iterator,
+    F: FnMut(&I::Item) -> K,
+    K: Hash + Eq,
+{
+    type Item = I::Item;
+    fn next(&mut self) -> Option<Self::Item> {
+        let Self {
+            iter,
+            canonical,
+            seen,
+        } = self;
+        iter.find(|item| seen.insert(canonical(item)))
+    }
+}
+
+/// Collapse results whose keys are aliases of one another.
+pub trait DedupCanonicalExt: Iterator {
+    /// Yield only the first item of each canonical form returned by
+    /// `canonical`, e.g. when keys were stored in several normalized forms.
+    /// Items stream through as they are found; only the canonical forms seen
+    /// so far are kept.
+    ///
+    /// ```rust
+    /// use trie_rs::iter::DedupCanonicalExt;
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("Colour", 0), ("color", 1), ("colour", 2), ("column", 3)]);
+    /// let results: Vec<(String, &u8)> = trie
+    ///     .iter()
+    ///     .dedup_canonical(|(key, _): &(String, _)| key.to_lowercase().replace("our", "or"))
+    ///     .collect();
+    /// assert_eq!(results, [("Colour".to_string(), &0), ("column".to_string(), &3)]);
+    /// ```
+    fn dedup_canonical<K, F>(self, canonical: F) -> DedupCanonical<Self, F, K>
+    where
+        Self: Sized,
+        F: FnMut(&Self::Item) -> K,
+        K: Hash + Eq,
+    {
+        DedupCanonical {
+            iter: self,
+            canonical,
+            seen: HashSet::new(),
+        }
+    }
+}
+
+impl<T> DedupCanonicalExt for T where T: Iterator + ?Sized {}
+
+#[cfg(test)]
+mod dedup_canonical_tests {
+    use crate::iter::DedupCanonicalExt;
+    use crate::Trie;
+
+    #[test]
+    fn first_of_each_form() {
+        let trie = Trie::from_iter(["cafe", "café", "CAFE", "cab"]);
+        let fold = |key: &String| -> String {
+            key.to_lowercase()
+                .chars()
+                .map(|c| if c == 'é' { 'e' } else { c })
+                .collect()
+        };
+        let results: Vec<String> = trie.predictive_search("").dedup_canonical(fold).collect();
+        assert_eq!(results, ["CAFE", "cab"]);
+        let results: Vec<String> = trie.predictive_search("ca").dedup_canonical(fold).collect();
+        assert_eq!(results, ["cab", "cafe"]);
+    }
+}
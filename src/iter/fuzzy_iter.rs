@@ -0,0 +1,195 @@
+use crate::iter::{StepBudget, StepLimit};
+use crate::map::Trie;
+use crate::try_collect::{TryCollect, TryFromIterator};
+use louds_rs::LoudsNodeNum;
+use std::marker::PhantomData;
+
+#[derive(Debug, Clone)]
+/// Iterates through the entries within an edit distance of a query, along
+/// with their distance.
+///
+/// Each step down the trie computes one row of the edit distance matrix, and
+/// subtrees whose row is already over the limit are skipped.
+pub struct FuzzyIter<'a, Label, Value, C, M> {
+    trie: &'a Trie<Label, Value>,
+    query: Vec<Label>,
+    max_edits: usize,
+    transpositions: bool,
+    stack: Vec<(usize, LoudsNodeNum)>,
+    buffer: Vec<&'a Label>,
+    /// `rows[d]`: distances from the first `d` labels of `buffer` to each
+    /// prefix of `query`.
+    rows: Vec<Vec<usize>>,
+    budget: StepBudget,
+    col: PhantomData<(C, M)>,
+}
+
+impl<'a, Label: Ord + Clone, Value, C, M> FuzzyIter<'a, Label, Value, C, M> {
+    #[inline]
+    pub(crate) fn new(
+        trie: &'a Trie<Label, Value>,
+        query: impl AsRef<[Label]>,
+        max_edits: usize,
+    ) -> Self {
+        let query = query.as_ref().to_vec();
+        let mut stack: Vec<_> = trie
+            .children_node_nums(LoudsNodeNum(1))
+            .map(|n| (1, n))
+            .collect();
+        stack.reverse();
+        Self {
+            trie,
+            rows: vec![(0..=query.len()).collect()],
+            query,
+            max_edits,
+            transpositions: false,
+            stack,
+            buffer: Vec::new(),
+            budget: StepBudget::default(),
+            col: PhantomData,
+        }
+    }
+
+    /// Count swapping two adjacent labels as one edit instead of two, i.e.
+    /// use the Damerau-Levenshtein (optimal string alignment) distance.
+    ///
+    /// Call it before iterating.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("the", 0), ("tea", 1)]);
+    /// let results: Vec<(String, usize, &u8)> = trie.fuzzy_search("teh", 1).collect();
+    /// assert_eq!(results, [("tea".to_string(), 1, &1)]);
+    /// let results: Vec<(String, usize, &u8)> = trie.fuzzy_search("teh", 1).transpositions().collect();
+    /// assert_eq!(results, [("tea".to_string(), 1, &1), ("the".to_string(), 1, &0)]);
+    /// ```
+    pub fn transpositions(mut self) -> Self {
+        self.transpositions = true;
+        self
+    }
+
+    /// Compute the row for `label` at `depth` from the rows above it.
+    fn row(&self, depth: usize, label: &Label) -> Vec<usize> {
+        let prev = &self.rows[depth - 1];
+        let mut row = Vec::with_capacity(prev.len());
+        row.push(depth);
+        for i in 1..=self.query.len() {
+            let cost = usize::from(self.query[i - 1] != *label);
+            let mut d = (prev[i - 1] + cost).min(prev[i] + 1).min(row[i - 1] + 1);
+            if self.transpositions
+                && depth >= 2
+                && i >= 2
+                && self.query[i - 1] == *self.buffer[depth - 2]
+                && self.query[i - 2] == *label
+            {
+                d = d.min(self.rows[depth - 2][i - 2] + 1);
+            }
+            row.push(d);
+        }
+        row
+    }
+}
+
+impl<'a, Label: Ord + Clone, Value, C, M> Iterator for FuzzyIter<'a, Label, Value, C, M>
+where
+    C: TryFromIterator<Label, M>,
+{
+    type Item = (C, usize, &'a Value);
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((depth, node)) = self.stack.pop() {
+            if !self.budget.spend() {
+                break;
+            }
+            self.buffer.truncate(depth - 1);
+            self.rows.truncate(depth);
+            let label = self.trie.label(node);
+            let row = self.row(depth, label);
+            self.buffer.push(label);
+            // Distances never shrink further down, so the row minimum bounds
+            // every key below.
+            if row.iter().any(|d| *d <= self.max_edits) {
+                let children = self.trie.children_node_nums(node);
+                self.stack
+                    .extend(children.rev().map(|child| (depth + 1, child)));
+            }
+            let distance = row[self.query.len()];
+            self.rows.push(row);
+            if distance <= self.max_edits {
+                if let Some(value) = self.trie.value(node) {
+                    return Some((
+                        self.buffer
+                            .iter()
+                            .cloned()
+                            .cloned()
+                            .try_collect()
+                            .expect("Could not collect"),
+                        distance,
+                        value,
+                    ));
+                }
+            }
+        }
+        None
+    }
+}
+
+impl<'a, Label: Ord + Clone, Value, C, M> StepLimit for FuzzyIter<'a, Label, Value, C, M>
+where
+    C: TryFromIterator<Label, M>,
+{
+    fn budget(&self) -> &StepBudget {
+        &self.budget
+    }
+
+    fn budget_mut(&mut self) -> &mut StepBudget {
+        &mut self.budget
+    }
+}
+
+#[cfg(test)]
+mod fuzzy_iter_tests {
+    use crate::map::Trie;
+
+    fn build_trie() -> Trie<u8, u8> {
+        Trie::from_iter([
+            ("a", 0),
+            ("app", 1),
+            ("apple", 2),
+            ("better", 3),
+            ("application", 4),
+            ("apply", 5),
+        ])
+    }
+
+    macro_rules! parameterized_tests {
+        ($($name:ident: $value:expr,)*) => {
+        $(
+            #[test]
+            fn $name() {
+                let (query, max_edits, transpositions, expected) = $value;
+                let trie = build_trie();
+                let iter = trie.fuzzy_search(query, max_edits);
+                let iter = if transpositions { iter.transpositions() } else { iter };
+                let results: Vec<(String, usize)> = iter.map(|(k, d, _)| (k, d)).collect();
+                let expected: Vec<(String, usize)> = expected
+                    .iter()
+                    .map(|(k, d): &(&str, usize)| (k.to_string(), *d))
+                    .collect();
+                assert_eq!(results, expected);
+            }
+        )*
+        }
+    }
+
+    parameterized_tests! {
+        t1: ("apple", 0, false, [("apple", 0)]),
+        t2: ("appel", 1, false, []),
+        t3: ("appel", 2, false, [("app", 2), ("apple", 2), ("apply", 2)]),
+        t4: ("appel", 1, true, [("apple", 1)]),
+        t5: ("bettre", 1, true, [("better", 1)]),
+        t6: ("", 1, false, [("a", 1)]),
+        t7: ("x", 1, false, [("a", 1)]),
+        t8: ("z", 0, true, []),
+    }
+}
@@ -0,0 +1,268 @@
+use crate::map::Trie;
+use crate::try_collect::{TryCollect, TryFromIterator};
+use louds_rs::LoudsNodeNum;
+use std::marker::PhantomData;
+
+#[derive(Debug, Clone)]
+/// Iterates through the entries that start with a query's labels in order,
+/// with up to a number of other labels skipped before each of them.
+///
+/// Each step down the trie computes, for every prefix of the query, the
+/// fewest labels skipped since its last label matched, and subtrees where no
+/// prefix is still within the limit are skipped.
+pub struct GapIter<'a, Label, Value, C, M> {
+    trie: &'a Trie<Label, Value>,
+    query: Vec<Label>,
+    max_gap: usize,
+    /// `gaps[i]`: whether labels may be skipped before `query[i]`.
+    gaps: Vec<bool>,
+    /// Whether a label may be matched right after a skip, given the label
+    /// before it. See [GapIter::word_boundaries].
+    after_gap: Option<fn(&Label, &Label) -> bool>,
+    stack: Vec<(usize, LoudsNodeNum)>,
+    buffer: Vec<&'a Label>,
+    /// `rows[d][i]`: after the first `d` labels of `buffer`, the fewest
+    /// labels skipped since the first `i` labels of `query` matched, or
+    /// `None` if they cannot have.
+    rows: Vec<Vec<Option<usize>>>,
+    col: PhantomData<(C, M)>,
+}
+
+impl<'a, Label: Ord + Clone, Value, C, M> GapIter<'a, Label, Value, C, M> {
+    #[inline]
+    pub(crate) fn new(
+        trie: &'a Trie<Label, Value>,
+        query: impl AsRef<[Label]>,
+        max_gap: usize,
+    ) -> Self {
+        let query = query.as_ref().to_vec();
+        let mut stack: Vec<_> = trie
+            .children_node_nums(LoudsNodeNum(1))
+            .map(|n| (1, n))
+            .collect();
+        stack.reverse();
+        let mut root = vec![None; query.len() + 1];
+        root[0] = Some(0);
+        Self {
+            trie,
+            gaps: (0..query.len()).map(|i| i > 0).collect(),
+            after_gap: None,
+            query,
+            max_gap,
+            stack,
+            buffer: Vec::new(),
+            rows: vec![root],
+            col: PhantomData,
+        }
+    }
+
+    /// Only skip labels before the query labels at `positions`, instead of
+    /// before every query label but the first.
+    ///
+    /// Call it before iterating.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("git checkout", 0), ("git commit", 1)]);
+    /// // Skip only between the command and the subcommand.
+    /// let results: Vec<(String, &u8)> = trie.gap_search("gico", 10).gaps_at([2]).collect();
+    /// assert_eq!(results, [("git commit".to_string(), &1)]);
+    /// ```
+    pub fn gaps_at(mut self, positions: impl IntoIterator<Item = usize>) -> Self {
+        self.gaps = vec![false; self.query.len()];
+        for i in positions {
+            if let Some(gap) = self.gaps.get_mut(i) {
+                *gap = true;
+            }
+        }
+        self
+    }
+
+    /// Compute the row for `label` at `depth` from the row above it.
+    fn row(&self, depth: usize, label: &Label) -> Vec<Option<usize>> {
+        let prev = &self.rows[depth - 1];
+        let len = self.query.len();
+        let mut row = vec![None; len + 1];
+        for i in 0..len {
+            let Some(gap) = prev[i] else { continue };
+            let may_match = match self.after_gap {
+                Some(after_gap) if gap > 0 => after_gap(self.buffer[depth - 2], label),
+                _ => true,
+            };
+            if self.query[i] == *label && may_match {
+                row[i + 1] = Some(0);
+            }
+            // Skipping a matching label may still line up better later.
+            if self.gaps[i] && gap < self.max_gap {
+                row[i] = Some(row[i].map_or(gap + 1, |g| g.min(gap + 1)));
+            }
+        }
+        // Once the whole query matched, any label may follow.
+        if prev[len].is_some() {
+            row[len] = Some(0);
+        }
+        row
+    }
+}
+
+impl<Label: Ord + Clone + WordBoundary, Value, C, M> GapIter<'_, Label, Value, C, M> {
+    /// Only skip up to the start of a word, i.e. an uppercase letter or a
+    /// label after `_`, `-`, `/`, `.`, or whitespace, for matching
+    /// abbreviations of identifiers and paths.
+    ///
+    /// Call it before iterating.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("foo_bar_baz", 0), ("fooBarBaz", 1), ("fabulous", 2)]);
+    /// let results: Vec<(String, &u8)> = trie.gap_search("fbb", usize::MAX).word_boundaries().collect();
+    /// assert_eq!(results, [("foo_bar_baz".to_string(), &0)]);
+    /// // Labels are compared as they are.
+    /// let results: Vec<(String, &u8)> = trie.gap_search("fBB", usize::MAX).word_boundaries().collect();
+    /// assert_eq!(results, [("fooBarBaz".to_string(), &1)]);
+    /// ```
+    pub fn word_boundaries(mut self) -> Self {
+        self.after_gap = Some(|prev, label| label.starts_word(prev));
+        self
+    }
+}
+
+/// Labels that can tell where a word starts. See [GapIter::word_boundaries].
+pub trait WordBoundary {
+    /// Return true if a word starts at this label, given the label before it.
+    fn starts_word(&self, prev: &Self) -> bool;
+}
+
+impl WordBoundary for u8 {
+    fn starts_word(&self, prev: &Self) -> bool {
+        self.is_ascii_uppercase()
+            || matches!(prev, b'_' | b'-' | b'/' | b'.')
+            || prev.is_ascii_whitespace()
+    }
+}
+
+impl WordBoundary for char {
+    fn starts_word(&self, prev: &Self) -> bool {
+        self.is_uppercase() || matches!(prev, '_' | '-' | '/' | '.') || prev.is_whitespace()
+    }
+}
+
+impl<'a, Label: Ord + Clone, Value, C, M> Iterator for GapIter<'a, Label, Value, C, M>
+where
+    C: TryFromIterator<Label, M>,
+{
+    type Item = (C, &'a Value);
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((depth, node)) = self.stack.pop() {
+            self.buffer.truncate(depth - 1);
+            self.rows.truncate(depth);
+            let label = self.trie.label(node);
+            let row = self.row(depth, label);
+            self.buffer.push(label);
+            if row.iter().any(Option::is_some) {
+                let children = self.trie.children_node_nums(node);
+                self.stack
+                    .extend(children.rev().map(|child| (depth + 1, child)));
+            }
+            let matched = row[self.query.len()].is_some();
+            self.rows.push(row);
+            if matched {
+                if let Some(value) = self.trie.value(node) {
+                    return Some((
+                        self.buffer
+                            .iter()
+                            .cloned()
+                            .cloned()
+                            .try_collect()
+                            .expect("Could not collect"),
+                        value,
+                    ));
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod gap_iter_tests {
+    use crate::map::Trie;
+
+    fn build_trie() -> Trie<u8, u8> {
+        Trie::from_iter([
+            ("git checkout", 0),
+            ("git cherry-pick", 1),
+            ("git commit", 2),
+            ("go", 3),
+            ("gco", 4),
+            ("aaa", 5),
+        ])
+    }
+
+    macro_rules! parameterized_tests {
+        ($($name:ident: $value:expr,)*) => {
+        $(
+            #[test]
+            fn $name() {
+                let (query, max_gap, expected) = $value;
+                let trie = build_trie();
+                let results: Vec<String> = trie.gap_search(query, max_gap).map(|(k, _)| k).collect();
+                let expected: Vec<String> = expected.iter().map(|k: &&str| k.to_string()).collect();
+                assert_eq!(results, expected);
+            }
+        )*
+        }
+    }
+
+    parameterized_tests! {
+        t1: ("gco", 0, ["gco"]),
+        t2: ("gco", 4, ["gco", "git checkout", "git commit"]),
+        t3: ("gco", 3, ["gco", "git commit"]),
+        t4: ("co", 10, []),
+        t5: ("g", 0, ["gco", "git checkout", "git cherry-pick", "git commit", "go"]),
+        t6: ("aa", 1, ["aaa"]),
+        t7: ("", 0, ["aaa", "gco", "git checkout", "git cherry-pick", "git commit", "go"]),
+        t8: ("gcp", 6, ["git cherry-pick"]),
+    }
+
+    #[test]
+    fn word_boundaries() {
+        let trie = Trie::from_iter([
+            ("src/map/trie.rs", 0),
+            ("src/map.rs", 1),
+            ("smart.rs", 2),
+            ("src/main.rs", 3),
+            ("HTTPServer", 4),
+        ]);
+        let search = |query: &str| -> Vec<String> {
+            trie.gap_search(query, usize::MAX)
+                .word_boundaries()
+                .map(|(k, _)| k)
+                .collect()
+        };
+        assert_eq!(search("smt"), ["src/map/trie.rs"]);
+        assert_eq!(
+            search("sm"),
+            ["smart.rs", "src/main.rs", "src/map.rs", "src/map/trie.rs"]
+        );
+        // The "r" of "smart.rs" starts a word after ".".
+        assert_eq!(
+            search("smr"),
+            ["smart.rs", "src/main.rs", "src/map.rs", "src/map/trie.rs"]
+        );
+        assert_eq!(search("smat"), ["src/map/trie.rs"]);
+        assert_eq!(search("HS"), ["HTTPServer"]);
+        assert!(search("sa").is_empty());
+
+        let trie: Trie<char, u8> =
+            Trie::from_iter([("gitCheckout".chars().collect::<Vec<_>>(), 0)]);
+        let results: Vec<(String, &u8)> =
+            trie.gap_search(['g', 'c'], 10).word_boundaries().collect();
+        assert!(results.is_empty());
+        let results: Vec<(String, &u8)> =
+            trie.gap_search(['g', 'C'], 10).word_boundaries().collect();
+        assert_eq!(results.len(), 1);
+    }
+}
@@ -0,0 +1,44 @@
+use crate::map::Trie;
+use crate::try_collect::TryFromIterator;
+use louds_rs::LoudsNodeNum;
+use std::marker::PhantomData;
+
+#[derive(Debug, Clone)]
+/// Iterates through all entries in the order they were pushed to the builder.
+pub struct InsertionOrderIter<'a, Label, Value, C, M> {
+    trie: &'a Trie<Label, Value>,
+    nodes: std::slice::Iter<'a, LoudsNodeNum>,
+    col: PhantomData<(C, M)>,
+}
+
+impl<'a, Label, Value, C, M> InsertionOrderIter<'a, Label, Value, C, M> {
+    #[inline]
+    pub(crate) fn new(trie: &'a Trie<Label, Value>, nodes: &'a [LoudsNodeNum]) -> Self {
+        Self {
+            trie,
+            nodes: nodes.iter(),
+            col: PhantomData,
+        }
+    }
+}
+
+impl<'a, Label: Ord + Clone, Value, C, M> Iterator for InsertionOrderIter<'a, Label, Value, C, M>
+where
+    C: TryFromIterator<Label, M>,
+{
+    type Item = (C, &'a Value);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.nodes.next().map(|node| {
+            (
+                self.trie.prefix_of(*node),
+                self.trie
+                    .value(*node)
+                    .expect("insertion order holds terminals"),
+            )
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.nodes.size_hint()
+    }
+}
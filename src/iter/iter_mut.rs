@@ -0,0 +1,99 @@
+use crate::try_collect::{TryCollect, TryFromIterator};
+use louds_rs::{Louds, LoudsNodeNum};
+use std::marker::PhantomData;
+
+#[derive(Debug)]
+/// Iterates through all entries in lexicographic order, with mutable values.
+/// See [crate::map::Trie::iter_mut].
+pub struct IterMut<'a, Label, Value, C, M> {
+    louds: &'a Louds,
+    labels: &'a [Label],
+    /// (LoudsNodeNum - 2) -> value, taken when yielded.
+    values: Vec<Option<&'a mut Value>>,
+    queue: Vec<(usize, LoudsNodeNum)>,
+    buffer: Vec<&'a Label>,
+    col: PhantomData<(C, M)>,
+}
+
+impl<'a, Label, Value, C, M> IterMut<'a, Label, Value, C, M> {
+    #[inline]
+    pub(crate) fn new(
+        louds: &'a Louds,
+        labels: &'a [Label],
+        values: Vec<Option<&'a mut Value>>,
+    ) -> Self {
+        let mut queue: Vec<_> = louds
+            .parent_to_children_nodes(LoudsNodeNum(1))
+            .map(|node| (0, node))
+            .collect();
+        queue.reverse();
+        Self {
+            louds,
+            labels,
+            values,
+            queue,
+            buffer: Vec::new(),
+            col: PhantomData,
+        }
+    }
+}
+
+impl<'a, Label: Clone, Value, C, M> Iterator for IterMut<'a, Label, Value, C, M>
+where
+    C: TryFromIterator<Label, M>,
+{
+    type Item = (C, &'a mut Value);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((depth, node)) = self.queue.pop() {
+            let children = self.louds.parent_to_children_nodes(node);
+            self.queue
+                .extend(children.rev().map(|child| (depth + 1, child)));
+            let index = (node.0 - 2) as usize;
+            self.buffer.truncate(depth);
+            self.buffer.push(&self.labels[index]);
+            if let Some(value) = self.values[index].take() {
+                let key = self
+                    .buffer
+                    .iter()
+                    .cloned()
+                    .cloned()
+                    .try_collect()
+                    .expect("Could not collect");
+                return Some((key, value));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod iter_mut_tests {
+    use crate::map::Trie;
+
+    #[test]
+    fn updates_in_key_order() {
+        let mut trie = Trie::from_iter([("b", 0), ("a", 1), ("abc", 2), ("ab", 3)]);
+        let mut keys = Vec::new();
+        for (i, (key, value)) in trie.iter_mut::<String, _>().enumerate() {
+            keys.push(key);
+            *value = i * 10;
+        }
+        assert_eq!(keys, ["a", "ab", "abc", "b"]);
+        let values: Vec<(String, &usize)> = trie.iter().collect();
+        assert_eq!(
+            values,
+            [
+                ("a".to_string(), &0),
+                ("ab".to_string(), &10),
+                ("abc".to_string(), &20),
+                ("b".to_string(), &30)
+            ]
+        );
+
+        let mut units = Trie::from_iter([("x", ()), ("xy", ())]);
+        assert_eq!(units.iter_mut::<String, _>().count(), 2);
+        let mut empty = Trie::<u8, u8>::from_iter(Vec::<(&str, u8)>::new());
+        assert_eq!(empty.iter_mut::<String, _>().next(), None);
+    }
+}
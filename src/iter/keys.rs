@@ -1,3 +1,6 @@
+use crate::iter::{PairsWith, PostfixIter, SearchIter, StepBudget, StepLimit};
+use crate::try_collect::TryFromIterator;
+
 #[derive(Debug, Clone)]
 /// Retains keys and strips off `Value`s from a [crate::iter] iterator.
 pub struct Keys<I>(I);
@@ -9,6 +12,65 @@ impl<I> Keys<I> {
     }
 }
 
+impl<Label: Ord, Value, C, M> Keys<PostfixIter<'_, Label, Value, C, M>>
+where
+    C: TryFromIterator<Label, M>,
+{
+    /// Only yield postfixes of at least `len` labels. See
+    /// [PostfixIter::min_len].
+    pub fn min_len(self, len: usize) -> Self {
+        Self(self.0.min_len(len))
+    }
+
+    /// Only yield postfixes of at most `len` labels. See
+    /// [PostfixIter::max_len].
+    pub fn max_len(self, len: usize) -> Self {
+        Self(self.0.max_len(len))
+    }
+}
+
+impl<Label: Ord + Clone, Value, C, M> Keys<SearchIter<'_, Label, Value, C, M>>
+where
+    C: TryFromIterator<Label, M> + Clone,
+{
+    /// Skip ahead to the first key not less than `key`. See
+    /// [SearchIter::skip_to].
+    pub fn skip_to(&mut self, key: impl AsRef<[Label]>) {
+        self.0.skip_to(key)
+    }
+}
+
+impl<Label, I: PairsWith<Label>> PairsWith<Label> for Keys<I> {
+    type With<C, M> = Keys<I::With<C, M>>;
+
+    fn pairs_with<C, M>(self) -> Self::With<C, M>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        Keys(self.0.pairs_with())
+    }
+}
+
+impl<I, C, V> StepLimit for Keys<I>
+where
+    I: StepLimit + Iterator<Item = (C, V)>,
+{
+    fn budget(&self) -> &StepBudget {
+        self.0.budget()
+    }
+
+    fn budget_mut(&mut self) -> &mut StepBudget {
+        self.0.budget_mut()
+    }
+}
+
+#[cfg(feature = "alloc-metrics")]
+impl<I: crate::alloc_metrics::Stats> crate::alloc_metrics::Stats for Keys<I> {
+    fn stats(&self) -> crate::alloc_metrics::SearchStats {
+        self.0.stats()
+    }
+}
+
 // TODO: This is generic for V, which is a stand-in for the Value, but in a
 // `map::Trie<K,V>`, its iterators will actually reurn `(C, &V)`. Hopefully that
 // won't matter.
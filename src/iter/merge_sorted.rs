@@ -0,0 +1,160 @@
+use std::cmp::Ordering;
+use std::iter::{FusedIterator, Peekable};
+
+/// Merge iterators of `(key, value)` sorted by key into one sorted by key,
+/// e.g. the results of the same search on several tries. See [MergeSorted].
+///
+/// ```rust
+/// use trie_rs::iter::merge_sorted;
+/// use trie_rs::map::Trie;
+///
+/// let user = Trie::from_iter([("rust", 0), ("rustup", 0)]);
+/// let system = Trie::from_iter([("rust", 1), ("rusty", 1)]);
+/// let merged: Vec<(String, &u8)> = merge_sorted([
+///     user.predictive_search("rust"),
+///     system.predictive_search("rust"),
+/// ])
+/// .collect();
+/// assert_eq!(
+///     merged,
+///     [
+///         ("rust".to_string(), &0),
+///         ("rust".to_string(), &1),
+///         ("rustup".to_string(), &0),
+///         ("rusty".to_string(), &1),
+///     ]
+/// );
+/// ```
+pub fn merge_sorted<I, K, V>(iters: impl IntoIterator<Item = I>) -> MergeSorted<I>
+where
+    I: Iterator<Item = (K, V)>,
+    K: Ord,
+{
+    MergeSorted {
+        heads: iters.into_iter().map(Iterator::peekable).collect(),
+        first_wins: false,
+    }
+}
+
+/// Iterates through the entries of several iterators sorted by key, in key
+/// order. See [merge_sorted].
+///
+/// Entries with equal keys come in the order of their iterators, and in
+/// their order within one iterator, so the merge is stable. If an iterator is
+/// not sorted, neither is the merge, but every entry still comes once.
+pub struct MergeSorted<I: Iterator> {
+    heads: Vec<Peekable<I>>,
+    first_wins: bool,
+}
+
+impl<I: Iterator> MergeSorted<I> {
+    /// Yield only the first entry of each key, i.e. the one from the earliest
+    /// iterator, like a map layered over the maps after it. See
+    /// [crate::map::MultiTrie].
+    ///
+    /// ```rust
+    /// use trie_rs::iter::merge_sorted;
+    ///
+    /// let merged: Vec<(&str, u8)> = merge_sorted([
+    ///     vec![("a", 0), ("c", 0)].into_iter(),
+    ///     vec![("a", 1), ("b", 1), ("c", 1)].into_iter(),
+    /// ])
+    /// .first_wins()
+    /// .collect();
+    /// assert_eq!(merged, [("a", 0), ("b", 1), ("c", 0)]);
+    /// ```
+    pub fn first_wins(mut self) -> Self {
+        self.first_wins = true;
+        self
+    }
+}
+
+impl<I, K: Ord, V> Iterator for MergeSorted<I>
+where
+    I: Iterator<Item = (K, V)>,
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut first: Option<(usize, &K)> = None;
+        for (i, head) in self.heads.iter_mut().enumerate() {
+            let Some((key, _)) = head.peek() else {
+                continue;
+            };
+            match first {
+                // Only a strictly smaller key displaces an earlier iterator.
+                Some((_, first_key)) if first_key <= key => {}
+                _ => first = Some((i, key)),
+            }
+        }
+        let first = first?.0;
+        let (key, value) = self.heads[first].next()?;
+        if self.first_wins {
+            for head in &mut self.heads {
+                while head
+                    .next_if(|(k, _)| k.cmp(&key) == Ordering::Equal)
+                    .is_some()
+                {}
+            }
+        }
+        Some((key, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (mut low, mut high) = (0usize, Some(0usize));
+        for head in &self.heads {
+            let (l, h) = head.size_hint();
+            low = low.saturating_add(l);
+            high = high.zip(h).and_then(|(a, b)| a.checked_add(b));
+        }
+        if self.first_wins {
+            // Every entry may share its key with another.
+            low = low.min(1);
+        }
+        (low, high)
+    }
+}
+
+impl<I, K: Ord, V> FusedIterator for MergeSorted<I> where I: Iterator<Item = (K, V)> {}
+
+#[cfg(test)]
+mod merge_sorted_tests {
+    use super::merge_sorted;
+
+    #[test]
+    fn stable_on_ties() {
+        let iters = || {
+            [
+                vec![("b", 0), ("b", 1), ("d", 2)],
+                vec![],
+                vec![("a", 3), ("b", 4), ("e", 5)],
+                vec![("b", 6), ("c", 7), ("d", 8)],
+            ]
+            .into_iter()
+            .map(Vec::into_iter)
+        };
+        let merged: Vec<(&str, u8)> = merge_sorted(iters()).collect();
+        assert_eq!(
+            merged,
+            [
+                ("a", 3),
+                ("b", 0),
+                ("b", 1),
+                ("b", 4),
+                ("b", 6),
+                ("c", 7),
+                ("d", 2),
+                ("d", 8),
+                ("e", 5)
+            ]
+        );
+        assert_eq!(merge_sorted(iters()).size_hint(), (9, Some(9)));
+        let first: Vec<(&str, u8)> = merge_sorted(iters()).first_wins().collect();
+        assert_eq!(first, [("a", 3), ("b", 0), ("c", 7), ("d", 2), ("e", 5)]);
+        assert_eq!(merge_sorted(iters()).first_wins().size_hint(), (1, Some(9)));
+
+        let mut none = merge_sorted(Vec::<std::vec::IntoIter<(u8, u8)>>::new());
+        assert_eq!(none.next(), None);
+        assert_eq!(none.size_hint(), (0, Some(0)));
+    }
+}
@@ -1,10 +1,30 @@
 //! Trie iterators
+mod dedup_canonical;
+mod fuzzy_iter;
+mod gap_iter;
+mod insertion_order_iter;
+mod iter_mut;
 mod keys;
+mod merge_sorted;
+mod pairs_with;
 mod postfix_iter;
 mod prefix_iter;
+mod range_iter;
 mod search_iter;
+mod step_limit;
+mod values;
 
+pub use dedup_canonical::{DedupCanonical, DedupCanonicalExt};
+pub use fuzzy_iter::FuzzyIter;
+pub use gap_iter::{GapIter, WordBoundary};
+pub use insertion_order_iter::InsertionOrderIter;
+pub use iter_mut::IterMut;
 pub use keys::{Keys, KeysExt};
+pub use merge_sorted::{merge_sorted, MergeSorted};
+pub use pairs_with::PairsWith;
 pub use postfix_iter::PostfixIter;
-pub use prefix_iter::PrefixIter;
+pub use prefix_iter::{PrefixIter, PrefixOffsetIter};
+pub use range_iter::RangeIter;
 pub use search_iter::SearchIter;
+pub use step_limit::{StepBudget, StepLimit, Truncated, Truncating};
+pub use values::{Values, ValuesExt};
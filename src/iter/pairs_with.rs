@@ -0,0 +1,124 @@
+use crate::try_collect::{LossyIter, ResultCollect, StringCollect, TryFromIterator};
+use std::string::FromUtf8Error;
+
+/// Search iterators whose key collector can be chosen after they are created,
+/// so generic code can take any search and decide how to collect its keys.
+///
+/// ```rust
+/// use trie_rs::iter::PairsWith;
+/// use trie_rs::map::Trie;
+///
+/// use trie_rs::try_collect::StringCollect;
+///
+/// fn first_key<'a, I>(results: I) -> Option<String>
+/// where
+///     I: PairsWith<u8>,
+///     I::With<String, StringCollect>: Iterator<Item = (String, &'a u8)>,
+/// {
+///     results.pairs_with::<String, _>().next().map(|(key, _)| key)
+/// }
+///
+/// let trie = Trie::from_iter([("a", 0), ("app", 1)]);
+/// assert_eq!(first_key(trie.predictive_search::<Vec<u8>, _>("ap")), Some("app".to_string()));
+/// assert_eq!(first_key(trie.common_prefix_search::<Vec<u8>, _>("ap")), Some("a".to_string()));
+/// assert_eq!(first_key(trie.postfix_search::<Vec<u8>, _>("a")), Some("pp".to_string()));
+/// ```
+pub trait PairsWith<Label> {
+    /// This search collecting keys into `C`.
+    type With<C, M>;
+
+    /// Collect keys into `C` instead, without restarting the search.
+    fn pairs_with<C, M>(self) -> Self::With<C, M>
+    where
+        C: TryFromIterator<Label, M>;
+
+    /// Collect the keys of a byte trie into [String]s, replacing invalid
+    /// UTF-8 with U+FFFD instead of panicking, without restarting the search.
+    /// Same as collecting into `Result<String, _>` and calling
+    /// [LossyIterator::lossy](crate::try_collect::LossyIterator::lossy).
+    ///
+    /// ```rust
+    /// use trie_rs::iter::PairsWith;
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([(&b"GET /"[..], 0), (b"GET /\xff", 1), (&[b'G', 0xc3], 2)]);
+    /// let entries: Vec<(String, &u8)> = trie.predictive_search::<Vec<u8>, _>("G").labels_lossy().collect();
+    /// assert_eq!(entries[0], ("GET /".to_string(), &0));
+    /// assert_eq!(entries[2], ("G\u{fffd}".to_string(), &2));
+    ///
+    /// let set = trie_rs::Trie::from_iter([&b"ok"[..], &[0xff]]);
+    /// let keys: Vec<String> = set.iter::<Vec<u8>, _>().labels_lossy().collect();
+    /// assert_eq!(keys, ["ok", "\u{fffd}"]);
+    /// ```
+    fn labels_lossy(
+        self,
+    ) -> LossyIter<Self::With<Result<String, FromUtf8Error>, ResultCollect<StringCollect>>>
+    where
+        Self: Sized,
+        String: TryFromIterator<Label, StringCollect, Error = FromUtf8Error>,
+    {
+        LossyIter(self.pairs_with())
+    }
+}
+
+#[cfg(test)]
+mod pairs_with_tests {
+    use crate::iter::{KeysExt, PairsWith, ValuesExt};
+    use crate::map::Trie;
+    use crate::try_collect::Collect;
+
+    #[test]
+    fn recollect() {
+        let trie = Trie::from_iter([("a", 0), ("app", 1), ("apple", 2)]);
+        let mut search = trie.predictive_search::<String, _>("a");
+        assert_eq!(search.next(), Some(("a".to_string(), &0)));
+        let rest: Vec<(Vec<u8>, &u8)> = search.pairs_with::<_, Collect>().collect();
+        assert_eq!(rest, [(b"app".to_vec(), &1), (b"apple".to_vec(), &2)]);
+
+        let search = trie.predictive_search::<String, _>("app");
+        let all: Vec<(Vec<u8>, &u8)> = search.pairs_with::<_, Collect>().collect();
+        assert_eq!(all.len(), 2);
+
+        let prefixes: Vec<(Vec<u8>, &u8)> = trie
+            .common_prefix_search::<String, _>("apple")
+            .pairs_with::<_, Collect>()
+            .collect();
+        assert_eq!(prefixes[2], (b"apple".to_vec(), &2));
+
+        let values: Vec<&u8> = trie
+            .postfix_search::<String, _>("a")
+            .pairs_with::<Vec<u8>, _>()
+            .values()
+            .collect();
+        assert_eq!(values, [&1, &2]);
+
+        let set = crate::Trie::from_iter(["a", "ab"]);
+        let keys: Vec<Vec<u8>> = set.iter::<String, _>().pairs_with::<_, Collect>().collect();
+        assert_eq!(keys, [b"a".to_vec(), b"ab".to_vec()]);
+    }
+
+    #[test]
+    fn labels_lossy() {
+        let trie = Trie::from_iter([(&b"a"[..], 0), (b"a\xe3\x81", 1), (b"a\xe3\x81\x82", 2)]);
+        let mut search = trie.predictive_search::<Vec<u8>, _>("a");
+        assert_eq!(search.next(), Some((b"a".to_vec(), &0)));
+        let rest: Vec<(String, &u8)> = search.labels_lossy().collect();
+        assert_eq!(
+            rest,
+            [("a\u{fffd}".to_string(), &1), ("aあ".to_string(), &2)]
+        );
+
+        let prefixes: Vec<(String, &u8)> = trie
+            .common_prefix_search::<Vec<u8>, _>(b"a\xe3\x81\x82")
+            .labels_lossy()
+            .collect();
+        assert_eq!(prefixes.len(), 3);
+        assert_eq!(prefixes[1].0, "a\u{fffd}");
+        let postfixes: Vec<String> = trie
+            .postfix_search::<String, _>("a")
+            .labels_lossy()
+            .keys()
+            .collect();
+        assert_eq!(postfixes, ["\u{fffd}", "あ"]);
+    }
+}
@@ -1,15 +1,30 @@
+#[cfg(feature = "alloc-metrics")]
+use crate::alloc_metrics::{Probe, SearchStats, Stats};
+use crate::iter::{PairsWith, StepBudget, StepLimit};
 use crate::map::Trie;
 use crate::try_collect::{TryCollect, TryFromIterator};
 use louds_rs::LoudsNodeNum;
 use std::marker::PhantomData;
 
+/// The most labels a [PostfixIter] reserves room for up front.
+const PREALLOCATED_LABELS: usize = 256;
+
 #[derive(Debug, Clone)]
 /// Iterates through all the postfixes of a matching query.
 pub struct PostfixIter<'a, Label, Value, C, M> {
     trie: &'a Trie<Label, Value>,
+    root: LoudsNodeNum,
     queue: Vec<(usize, LoudsNodeNum)>,
+    /// The query's labels if yielding full keys, then the postfix's.
     buffer: Vec<&'a Label>,
+    /// Length of the query in `buffer`.
+    offset: usize,
     value: Option<&'a Value>,
+    min_len: usize,
+    max_len: usize,
+    budget: StepBudget,
+    #[cfg(feature = "alloc-metrics")]
+    stats: SearchStats,
     col: PhantomData<(C, M)>,
 }
 
@@ -21,11 +36,27 @@ where
     pub(crate) fn new(trie: &'a Trie<Label, Value>, root: LoudsNodeNum) -> Self {
         let mut children: Vec<_> = trie.children_node_nums(root).map(|n| (0, n)).collect();
         children.reverse();
+        // No key under `root` is longer than the longest key of all, query
+        // included, so the buffer rarely grows while deep keys are walked. A
+        // single long key elsewhere must not make every search reserve it,
+        // though: past the cap the buffer grows as usual.
+        let capacity = if children.is_empty() {
+            0
+        } else {
+            trie.max_key_len().unwrap_or(0).min(PREALLOCATED_LABELS)
+        };
         Self {
             trie,
+            root,
             queue: children,
-            buffer: Vec::new(),
+            buffer: Vec::with_capacity(capacity),
+            offset: 0,
             value: None,
+            min_len: 0,
+            max_len: usize::MAX,
+            budget: StepBudget::default(),
+            #[cfg(feature = "alloc-metrics")]
+            stats: SearchStats::default(),
             col: PhantomData,
         }
     }
@@ -34,40 +65,164 @@ where
     pub(crate) fn empty(trie: &'a Trie<Label, Value>) -> Self {
         Self {
             trie,
+            root: LoudsNodeNum(1),
             queue: Vec::new(),
             buffer: Vec::new(),
+            offset: 0,
             value: None,
+            min_len: 0,
+            max_len: usize::MAX,
+            budget: StepBudget::default(),
+            #[cfg(feature = "alloc-metrics")]
+            stats: SearchStats::default(),
             col: PhantomData,
         }
     }
+
+    /// Only yield postfixes of at least `len` labels.
+    ///
+    /// Shorter postfixes are skipped without being collected.
+    pub fn min_len(mut self, len: usize) -> Self {
+        self.min_len = len;
+        self
+    }
+
+    /// Only yield postfixes of at most `len` labels.
+    ///
+    /// Nodes deeper than `len` are not visited at all.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("run", 0), ("runs", 1), ("runner", 2), ("running", 3)]);
+    /// let results: Vec<(String, &u8)> = trie.postfix_search("run").min_len(2).max_len(3).collect();
+    /// assert_eq!(results, [("ner".to_string(), &2)]);
+    /// ```
+    pub fn max_len(mut self, len: usize) -> Self {
+        self.max_len = len;
+        if len == 0 {
+            self.queue.clear();
+        }
+        self
+    }
+
+    /// Yield whole keys, the query included, instead of postfixes. The query
+    /// is kept at the front of the buffer the postfixes are collected from,
+    /// so each key is collected in one go. [PostfixIter::min_len],
+    /// [PostfixIter::max_len] and [PostfixIter::skip_to] still count and
+    /// compare postfixes only.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("app", 0), ("apple", 1), ("apply", 2)]);
+    /// let results: Vec<(String, &u8)> = trie.postfix_search("app").full_keys().collect();
+    /// assert_eq!(results, [("apple".to_string(), &1), ("apply".to_string(), &2)]);
+    /// ```
+    pub fn full_keys(mut self) -> Self {
+        if self.offset == 0 {
+            let mut query = Vec::new();
+            let mut node = self.root;
+            while let Some(parent) = self.trie.parent(node) {
+                query.push(self.trie.label(node));
+                node = parent;
+            }
+            query.reverse();
+            self.offset = query.len();
+            self.buffer.splice(0..0, query);
+        }
+        self
+    }
 }
 
-impl<'a, Label: Ord + Clone, Value, C, M> Iterator for PostfixIter<'a, Label, Value, C, M>
+impl<'a, Label: Ord + Clone, Value, C, M> PostfixIter<'a, Label, Value, C, M>
 where
     C: TryFromIterator<Label, M>,
 {
-    type Item = (C, &'a Value);
-    #[inline]
-    fn next(&mut self) -> Option<Self::Item> {
+    /// Skip ahead to the first postfix not less than `postfix`, without
+    /// collecting the postfixes skipped. Subtrees entirely before it are
+    /// dropped unvisited. Postfixes already yielded stay yielded, so skipping
+    /// backward does nothing.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("ta", 0), ("tab", 1), ("tan", 2), ("tea", 3), ("to", 4)]);
+    /// let mut postfixes = trie.postfix_search::<String, _>("t");
+    /// postfixes.skip_to("ab");
+    /// assert_eq!(postfixes.next(), Some(("ab".to_string(), &1)));
+    /// postfixes.skip_to("b");
+    /// assert_eq!(postfixes.next(), Some(("ea".to_string(), &3)));
+    /// ```
+    pub fn skip_to(&mut self, postfix: impl AsRef<[Label]>) {
+        use std::cmp::Ordering;
+        let target = postfix.as_ref();
+        while let Some(&(depth, node)) = self.queue.last() {
+            let label = self.trie.label(node);
+            let differ = self.buffer[self.offset..self.offset + depth]
+                .iter()
+                .copied()
+                .chain([label])
+                .zip(target)
+                .map(|(a, b)| a.cmp(b))
+                .find(|ord| ord.is_ne());
+            match differ {
+                // The whole subtree comes before the target.
+                Some(Ordering::Less) => {
+                    self.queue.pop();
+                }
+                // The node is a proper prefix of the target: go into it.
+                None if depth + 1 < target.len() => {
+                    self.queue.pop();
+                    if depth + 1 < self.max_len {
+                        let children = self.trie.children_node_nums(node);
+                        self.queue
+                            .extend(children.rev().map(|child| (depth + 1, child)));
+                    }
+                    self.buffer.truncate(self.offset + depth);
+                    self.buffer.push(label);
+                }
+                _ => break,
+            }
+            if !self.budget.spend() {
+                self.queue.clear();
+            }
+        }
+    }
+
+    /// Drop the postfixes not yet yielded.
+    pub(crate) fn clear(&mut self) {
+        self.queue.clear();
+    }
+
+    fn advance(&mut self) -> Option<(C, &'a Value)> {
         use std::cmp::Ordering;
         while self.value.is_none() {
             if let Some((depth, node)) = self.queue.pop() {
-                let children = self.trie.children_node_nums(node);
-                self.queue
-                    .extend(children.rev().map(|child| (depth + 1, child)));
-                match depth.cmp(&self.buffer.len()) {
+                if !self.budget.spend() {
+                    break;
+                }
+                if depth + 1 < self.max_len {
+                    let children = self.trie.children_node_nums(node);
+                    self.queue
+                        .extend(children.rev().map(|child| (depth + 1, child)));
+                }
+                let index = self.offset + depth;
+                match index.cmp(&self.buffer.len()) {
                     Ordering::Equal => {
                         self.buffer.push(self.trie.label(node));
                     }
                     Ordering::Less => {
-                        let _ = self.buffer.drain(depth + 1..);
-                        self.buffer[depth] = self.trie.label(node);
+                        let _ = self.buffer.drain(index + 1..);
+                        self.buffer[index] = self.trie.label(node);
                     }
                     Ordering::Greater => {
                         panic!("depth > buffer.len()");
                     }
                 }
-                self.value = self.trie.value(node);
+                if depth + 1 >= self.min_len {
+                    self.value = self.trie.value(node);
+                }
             } else {
                 break;
             }
@@ -88,6 +243,152 @@ where
     }
 }
 
+impl<'a, Label: Ord + Clone, Value, C, M> Iterator for PostfixIter<'a, Label, Value, C, M>
+where
+    C: TryFromIterator<Label, M>,
+{
+    type Item = (C, &'a Value);
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        #[cfg(feature = "alloc-metrics")]
+        let probe = Probe::start();
+        let item = self.advance();
+        #[cfg(feature = "alloc-metrics")]
+        probe.finish(&mut self.stats);
+        item
+    }
+}
+
+impl<'a, Label, Value, C, M> PairsWith<Label> for PostfixIter<'a, Label, Value, C, M> {
+    type With<C2, M2> = PostfixIter<'a, Label, Value, C2, M2>;
+
+    fn pairs_with<C2, M2>(self) -> Self::With<C2, M2>
+    where
+        C2: TryFromIterator<Label, M2>,
+    {
+        PostfixIter {
+            trie: self.trie,
+            root: self.root,
+            queue: self.queue,
+            buffer: self.buffer,
+            offset: self.offset,
+            value: self.value,
+            min_len: self.min_len,
+            max_len: self.max_len,
+            budget: self.budget,
+            #[cfg(feature = "alloc-metrics")]
+            stats: self.stats,
+            col: PhantomData,
+        }
+    }
+}
+
+impl<'a, Label: Ord + Clone, Value, C, M> StepLimit for PostfixIter<'a, Label, Value, C, M>
+where
+    C: TryFromIterator<Label, M>,
+{
+    fn budget(&self) -> &StepBudget {
+        &self.budget
+    }
+
+    fn budget_mut(&mut self) -> &mut StepBudget {
+        &mut self.budget
+    }
+}
+
+#[cfg(feature = "alloc-metrics")]
+impl<Label, Value, C, M> Stats for PostfixIter<'_, Label, Value, C, M> {
+    fn stats(&self) -> SearchStats {
+        self.stats
+    }
+}
+
+#[cfg(test)]
+mod postfix_iter_tests {
+    use crate::map::Trie;
+
+    #[test]
+    fn length_filters() {
+        let trie = Trie::from_iter([("a", 0), ("ab", 1), ("abc", 2), ("abcd", 3), ("ax", 4)]);
+        let lens = |min, max| -> Vec<String> {
+            trie.postfix_search("a")
+                .min_len(min)
+                .max_len(max)
+                .map(|(k, _): (String, _)| k)
+                .collect()
+        };
+        assert_eq!(lens(0, usize::MAX), ["b", "bc", "bcd", "x"]);
+        assert_eq!(lens(2, 3), ["bc", "bcd"]);
+        assert_eq!(lens(1, 1), ["b", "x"]);
+        assert_eq!(lens(0, 0), Vec::<String>::new());
+        assert_eq!(lens(4, 10), Vec::<String>::new());
+    }
+
+    #[test]
+    fn skip_to() {
+        let keys = ["a", "ab", "abc", "abd", "ac", "b", "ba", "bb", "c"];
+        let trie = Trie::from_iter(keys.map(|k| (k, ())));
+        let targets = ["", "a", "aa", "ab", "abca", "abz", "b", "bab", "bc", "d"];
+        for query in ["", "a", "ab", "b", "x"] {
+            for target in targets {
+                let mut results = trie.predictive_search::<String, _>(query);
+                results.skip_to(target);
+                let skipped: Vec<String> = results.map(|(k, _)| k).collect();
+                let expected: Vec<&str> = keys
+                    .iter()
+                    .filter(|k| k.starts_with(query) && **k >= target)
+                    .copied()
+                    .collect();
+                assert_eq!(skipped, expected, "{:?} {:?}", query, target);
+            }
+        }
+
+        // After some results, and with a length limit.
+        let mut postfixes = trie.postfix_search::<String, _>("a").max_len(1);
+        assert_eq!(postfixes.next(), Some(("b".to_string(), &())));
+        postfixes.skip_to("a");
+        postfixes.skip_to("bz");
+        let rest: Vec<String> = postfixes.map(|(k, _)| k).collect();
+        assert_eq!(rest, ["c"]);
+    }
+
+    #[test]
+    fn full_keys() {
+        let trie = Trie::from_iter([("a", 0), ("ab", 1), ("abc", 2), ("abcd", 3), ("ax", 4)]);
+        let keys = |query, min, max, skip| -> Vec<String> {
+            let mut results = trie
+                .postfix_search(query)
+                .min_len(min)
+                .max_len(max)
+                .full_keys();
+            results.skip_to(skip);
+            results.map(|(k, _): (String, _)| k).collect()
+        };
+        assert_eq!(keys("a", 0, usize::MAX, ""), ["ab", "abc", "abcd", "ax"]);
+        assert_eq!(keys("ab", 0, usize::MAX, ""), ["abc", "abcd"]);
+        assert_eq!(keys("a", 2, 3, ""), ["abc", "abcd"]);
+        assert_eq!(keys("a", 0, usize::MAX, "bcd"), ["abcd", "ax"]);
+        assert_eq!(keys("", 0, 1, ""), ["a"]);
+        assert_eq!(keys("abcd", 0, usize::MAX, ""), Vec::<String>::new());
+
+        // Midway through, and more than once.
+        let mut results = trie.postfix_search::<String, _>("a");
+        assert_eq!(results.next(), Some(("b".to_string(), &1)));
+        let rest: Vec<String> = results.full_keys().full_keys().map(|(k, _)| k).collect();
+        assert_eq!(rest, ["abc", "abcd", "ax"]);
+    }
+
+    #[test]
+    fn preallocation_is_capped() {
+        let long = "x".repeat(100_000);
+        let trie = Trie::from_iter([("ab", 0), (long.as_str(), 1)]);
+        let results = trie.postfix_search::<String, _>("a");
+        assert!(results.buffer.capacity() <= super::PREALLOCATED_LABELS);
+        let keys: Vec<(String, &u8)> = trie.predictive_search("x").collect();
+        assert_eq!(keys, [(long, &1)]);
+    }
+}
+
 // impl<Label: Ord, V, C, M> Value<V> for PostfixIter<'_, Label, V, C, M> {
 //     fn value(&self) -> Option<&V> {
 //         self.value
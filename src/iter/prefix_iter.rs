@@ -1,3 +1,6 @@
+#[cfg(feature = "alloc-metrics")]
+use crate::alloc_metrics::{Probe, SearchStats, Stats};
+use crate::iter::PairsWith;
 use crate::map::Trie;
 use crate::try_collect::{TryCollect, TryFromIterator};
 use louds_rs::LoudsNodeNum;
@@ -12,6 +15,8 @@ pub struct PrefixIter<'a, Label, Value, C, M> {
     node: LoudsNodeNum,
     buffer: Vec<&'a Label>,
     consume: Option<&'a Value>,
+    #[cfg(feature = "alloc-metrics")]
+    stats: SearchStats,
     col: PhantomData<(C, M)>,
 }
 
@@ -25,31 +30,27 @@ impl<'a, Label: Ord + Clone, Value, C, M> PrefixIter<'a, Label, Value, C, M> {
             node: LoudsNodeNum(1),
             buffer: Vec::new(),
             consume: None,
+            #[cfg(feature = "alloc-metrics")]
+            stats: SearchStats::default(),
             col: PhantomData,
         }
     }
 }
 
-impl<'a, Label: Ord + Clone, Value, C, M> Iterator for PrefixIter<'a, Label, Value, C, M>
+impl<'a, Label: Ord + Clone, Value, C, M> PrefixIter<'a, Label, Value, C, M>
 where
     C: TryFromIterator<Label, M>,
 {
-    type Item = (C, &'a Value);
-    fn next(&mut self) -> Option<Self::Item> {
+    fn advance(&mut self) -> Option<(C, &'a Value)> {
         while self.consume.is_none() {
             if let Some(chr) = self.query.get(self.index) {
-                let children_node_nums: Vec<_> = self.trie.children_node_nums(self.node).collect();
-                let res = self
-                    .trie
-                    .bin_search_by_children_labels(chr, &children_node_nums[..]);
-                match res {
-                    Ok(j) => {
-                        let child_node_num = children_node_nums[j];
+                match self.trie.child(self.node, chr) {
+                    Some(child_node_num) => {
                         self.buffer.push(self.trie.label(child_node_num));
                         self.consume = self.trie.value(child_node_num);
                         self.node = child_node_num;
                     }
-                    Err(_) => break,
+                    None => break,
                 }
             } else {
                 return None;
@@ -70,3 +71,77 @@ where
         }
     }
 }
+
+impl<'a, Label, Value, C, M> PairsWith<Label> for PrefixIter<'a, Label, Value, C, M> {
+    type With<C2, M2> = PrefixIter<'a, Label, Value, C2, M2>;
+
+    fn pairs_with<C2, M2>(self) -> Self::With<C2, M2>
+    where
+        C2: TryFromIterator<Label, M2>,
+    {
+        PrefixIter {
+            trie: self.trie,
+            query: self.query,
+            index: self.index,
+            node: self.node,
+            buffer: self.buffer,
+            consume: self.consume,
+            #[cfg(feature = "alloc-metrics")]
+            stats: self.stats,
+            col: PhantomData,
+        }
+    }
+}
+
+impl<'a, Label: Ord + Clone, Value, C, M> Iterator for PrefixIter<'a, Label, Value, C, M>
+where
+    C: TryFromIterator<Label, M>,
+{
+    type Item = (C, &'a Value);
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        #[cfg(feature = "alloc-metrics")]
+        let probe = Probe::start();
+        let item = self.advance();
+        #[cfg(feature = "alloc-metrics")]
+        probe.finish(&mut self.stats);
+        item
+    }
+}
+
+#[cfg(feature = "alloc-metrics")]
+impl<Label, Value, C, M> Stats for PrefixIter<'_, Label, Value, C, M> {
+    fn stats(&self) -> SearchStats {
+        self.stats
+    }
+}
+
+#[derive(Debug, Clone)]
+/// Iterates through all the common prefixes of a given query along with the
+/// offset into the query where each one ends.
+pub struct PrefixOffsetIter<'a, Label, Value, C, M>(PrefixIter<'a, Label, Value, C, M>);
+
+impl<'a, Label: Ord + Clone, Value, C, M> PrefixOffsetIter<'a, Label, Value, C, M> {
+    #[inline]
+    pub(crate) fn new(trie: &'a Trie<Label, Value>, query: impl AsRef<[Label]>) -> Self {
+        Self(PrefixIter::new(trie, query))
+    }
+}
+
+impl<'a, Label: Ord + Clone, Value, C, M> Iterator for PrefixOffsetIter<'a, Label, Value, C, M>
+where
+    C: TryFromIterator<Label, M>,
+{
+    type Item = (C, usize, &'a Value);
+    fn next(&mut self) -> Option<Self::Item> {
+        // `index` is one past the last consumed label once a match is yielded.
+        self.0.next().map(|(key, value)| (key, self.0.index, value))
+    }
+}
+
+#[cfg(feature = "alloc-metrics")]
+impl<Label, Value, C, M> Stats for PrefixOffsetIter<'_, Label, Value, C, M> {
+    fn stats(&self) -> SearchStats {
+        self.0.stats
+    }
+}
@@ -0,0 +1,198 @@
+use crate::map::Trie;
+use crate::try_collect::{TryCollect, TryFromIterator};
+use louds_rs::LoudsNodeNum;
+use std::marker::PhantomData;
+use std::ops::Bound;
+
+fn to_owned<Label: Clone>(bound: Bound<&[Label]>) -> Bound<Vec<Label>> {
+    match bound {
+        Bound::Included(key) => Bound::Included(key.to_vec()),
+        Bound::Excluded(key) => Bound::Excluded(key.to_vec()),
+        Bound::Unbounded => Bound::Unbounded,
+    }
+}
+
+#[derive(Debug, Clone)]
+/// Iterates through the entries whose keys fall within a range, in
+/// lexicographic order. See [Trie::range].
+///
+/// Keys are only ever compared with the bounds, never incremented to find a
+/// successor, so labels at their type's maximum, e.g. `u8::MAX`, need no
+/// special care.
+pub struct RangeIter<'a, Label, Value, C, M> {
+    trie: &'a Trie<Label, Value>,
+    start: Bound<Vec<Label>>,
+    end: Bound<Vec<Label>>,
+    /// Nodes to visit with their depth, last first.
+    stack: Vec<(usize, LoudsNodeNum)>,
+    buffer: Vec<Label>,
+    col: PhantomData<(C, M)>,
+}
+
+impl<'a, Label: Ord + Clone, Value, C, M> RangeIter<'a, Label, Value, C, M> {
+    pub(crate) fn new(
+        trie: &'a Trie<Label, Value>,
+        start: Bound<&[Label]>,
+        end: Bound<&[Label]>,
+    ) -> Self {
+        let mut stack: Vec<_> = trie
+            .children_node_nums(LoudsNodeNum(1))
+            .map(|n| (1, n))
+            .collect();
+        stack.reverse();
+        Self {
+            trie,
+            start: to_owned(start),
+            end: to_owned(end),
+            stack,
+            buffer: Vec::new(),
+            col: PhantomData,
+        }
+    }
+
+    /// Return true if every key starting with `prefix` is before the start.
+    fn before_start(&self, prefix: &[Label]) -> bool {
+        match &self.start {
+            Bound::Included(start) | Bound::Excluded(start) => {
+                prefix < &start[..] && !start.starts_with(prefix)
+            }
+            Bound::Unbounded => false,
+        }
+    }
+
+    /// Return true if keys longer than `prefix` that start with it may be
+    /// before the end.
+    fn extensions_before_end(&self, prefix: &[Label]) -> bool {
+        match &self.end {
+            Bound::Included(end) | Bound::Excluded(end) => prefix < &end[..],
+            Bound::Unbounded => true,
+        }
+    }
+
+    fn contains(&self, key: &[Label]) -> bool {
+        let after_start = match &self.start {
+            Bound::Included(start) => key >= &start[..],
+            Bound::Excluded(start) => key > &start[..],
+            Bound::Unbounded => true,
+        };
+        let before_end = match &self.end {
+            Bound::Included(end) => key <= &end[..],
+            Bound::Excluded(end) => key < &end[..],
+            Bound::Unbounded => true,
+        };
+        after_start && before_end
+    }
+}
+
+impl<'a, Label: Ord + Clone, Value, C, M> Iterator for RangeIter<'a, Label, Value, C, M>
+where
+    C: TryFromIterator<Label, M>,
+{
+    type Item = (C, &'a Value);
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((depth, node)) = self.stack.pop() {
+            self.buffer.truncate(depth - 1);
+            self.buffer.push(self.trie.label(node).clone());
+            if self.before_start(&self.buffer) {
+                continue;
+            }
+            if !self.extensions_before_end(&self.buffer) {
+                // Later siblings and their subtrees are after the end too.
+                self.stack.retain(|&(d, _)| d < depth);
+                if !self.contains(&self.buffer) {
+                    continue;
+                }
+            } else {
+                let children = self.trie.children_node_nums(node);
+                self.stack
+                    .extend(children.rev().map(|child| (depth + 1, child)));
+            }
+            if let Some(value) = self.trie.value(node) {
+                if self.contains(&self.buffer) {
+                    return Some((
+                        self.buffer
+                            .iter()
+                            .cloned()
+                            .try_collect()
+                            .expect("Could not collect"),
+                        value,
+                    ));
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod range_iter_tests {
+    use crate::map::Trie;
+    use std::ops::Bound::{self, Excluded, Included, Unbounded};
+
+    fn build_trie() -> Trie<u8, u8> {
+        Trie::from_iter([
+            (&[0x00][..], 0),
+            (&[0x01, 0xff], 1),
+            (&[0xfe], 2),
+            (&[0xff], 3),
+            (&[0xff, 0x00], 4),
+            (&[0xff, 0xff], 5),
+            (&[0xff, 0xff, 0xff], 6),
+        ])
+    }
+
+    macro_rules! parameterized_tests {
+        ($($name:ident: $value:expr,)*) => {
+        $(
+            #[test]
+            fn $name() {
+                let (start, end, expected): (Bound<&[u8]>, Bound<&[u8]>, &[u8]) = $value;
+                let trie = build_trie();
+                let results: Vec<u8> = trie.range::<Vec<u8>, _>((start, end)).map(|(_, v)| *v).collect();
+                assert_eq!(results, expected);
+            }
+        )*
+        }
+    }
+
+    parameterized_tests! {
+        t1: (Unbounded, Unbounded, &[0, 1, 2, 3, 4, 5, 6]),
+        t2: (Included(&[0xff]), Unbounded, &[3, 4, 5, 6]),
+        t3: (Excluded(&[0xff]), Unbounded, &[4, 5, 6]),
+        t4: (Unbounded, Excluded(&[0xff]), &[0, 1, 2]),
+        t5: (Unbounded, Included(&[0xff]), &[0, 1, 2, 3]),
+        t6: (Included(&[0xff, 0xff]), Included(&[0xff, 0xff]), &[5]),
+        t7: (Excluded(&[0xff, 0xff]), Excluded(&[0xff, 0xff, 0xff]), &[]),
+        t8: (Excluded(&[0xff, 0xff]), Included(&[0xff, 0xff, 0xff]), &[6]),
+        t9: (Included(&[0x01]), Excluded(&[0x02]), &[1]),
+        t10: (Included(&[0x01, 0xff, 0xff]), Included(&[0xfe, 0x00]), &[2]),
+        t11: (Included(&[]), Excluded(&[]), &[]),
+        t12: (Excluded(&[]), Excluded(&[0x00, 0x00]), &[0]),
+    }
+
+    #[test]
+    fn char_max() {
+        let trie: Trie<char, u8> = Trie::from_iter([
+            (vec!['a'], 0),
+            (vec!['a', char::MAX], 1),
+            (vec![char::MAX], 2),
+            (vec![char::MAX, char::MAX], 3),
+        ]);
+        let max = [char::MAX];
+        let results: Vec<(String, &u8)> = trie
+            .range((Excluded(&['a'][..]), Included(&max[..])))
+            .collect();
+        assert_eq!(
+            results,
+            [
+                (['a', char::MAX].iter().collect(), &1),
+                (char::MAX.to_string(), &2)
+            ]
+        );
+        assert_eq!(
+            trie.range::<String, _>((Excluded(&max[..]), Unbounded))
+                .count(),
+            1
+        );
+    }
+}
@@ -1,4 +1,6 @@
-use crate::iter::PostfixIter;
+#[cfg(feature = "alloc-metrics")]
+use crate::alloc_metrics::{Probe, SearchStats, Stats};
+use crate::iter::{PairsWith, PostfixIter, StepBudget, StepLimit};
 use crate::map::Trie;
 use crate::try_collect::{Collect, TryCollect, TryFromIterator};
 use louds_rs::LoudsNodeNum;
@@ -9,7 +11,10 @@ use std::marker::PhantomData;
 pub struct SearchIter<'a, Label, Value, C, M> {
     prefix: Vec<Label>,
     first: Option<(C, &'a Value)>,
-    postfix_iter: PostfixIter<'a, Label, Value, Vec<Label>, Collect>,
+    /// Yields full keys, see [PostfixIter::full_keys].
+    postfix_iter: PostfixIter<'a, Label, Value, C, M>,
+    #[cfg(feature = "alloc-metrics")]
+    stats: SearchStats,
     col: PhantomData<(C, M)>,
 }
 
@@ -18,16 +23,26 @@ where
     C: TryFromIterator<Label, M> + Clone,
 {
     pub(crate) fn new(trie: &'a Trie<Label, Value>, query: impl AsRef<[Label]>) -> Self {
+        #[cfg(feature = "alloc-metrics")]
+        {
+            let probe = Probe::start();
+            let mut iter = Self::descend(trie, query);
+            probe.finish(&mut iter.stats);
+            iter
+        }
+        #[cfg(not(feature = "alloc-metrics"))]
+        Self::descend(trie, query)
+    }
+
+    fn descend(trie: &'a Trie<Label, Value>, query: impl AsRef<[Label]>) -> Self {
         let mut cur_node_num = LoudsNodeNum(1);
-        let mut prefix = Vec::new();
+        let mut prefix = Vec::with_capacity(query.as_ref().len());
 
         // Consumes query (prefix)
         for chr in query.as_ref() {
-            let children_node_nums: Vec<_> = trie.children_node_nums(cur_node_num).collect();
-            let res = trie.bin_search_by_children_labels(chr, &children_node_nums[..]);
-            match res {
-                Ok(i) => cur_node_num = children_node_nums[i],
-                Err(_) => return Self::empty(trie),
+            match trie.child(cur_node_num, chr) {
+                Some(child) => cur_node_num = child,
+                None => return Self::empty(trie),
             }
             prefix.push(trie.label(cur_node_num).clone());
         }
@@ -45,7 +60,9 @@ where
         SearchIter {
             prefix,
             first,
-            postfix_iter: PostfixIter::new(trie, cur_node_num),
+            postfix_iter: PostfixIter::new(trie, cur_node_num).full_keys(),
+            #[cfg(feature = "alloc-metrics")]
+            stats: SearchStats::default(),
             col: PhantomData,
         }
     }
@@ -55,11 +72,53 @@ where
             prefix: Vec::new(),
             first: None,
             postfix_iter: PostfixIter::empty(trie),
+            #[cfg(feature = "alloc-metrics")]
+            stats: SearchStats::default(),
             col: PhantomData,
         }
     }
 }
 
+impl<'a, Label: Ord + Clone, Value, C, M> SearchIter<'a, Label, Value, C, M>
+where
+    C: TryFromIterator<Label, M> + Clone,
+    Vec<Label>: TryFromIterator<Label, Collect>,
+{
+    /// Skip ahead to the first key not less than `key`, without collecting
+    /// the keys skipped, e.g. to resume a long list of completions after the
+    /// last key shown. See [PostfixIter::skip_to].
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("ta", 0), ("tab", 1), ("tan", 2), ("tea", 3), ("to", 4)]);
+    /// let mut results = trie.predictive_search::<String, _>("t");
+    /// results.skip_to("tan");
+    /// let rest: Vec<(String, &u8)> = results.collect();
+    /// assert_eq!(rest, [("tan".to_string(), &2), ("tea".to_string(), &3), ("to".to_string(), &4)]);
+    /// ```
+    pub fn skip_to(&mut self, key: impl AsRef<[Label]>) {
+        let key = key.as_ref();
+        if let Some(postfix) = key.strip_prefix(&self.prefix[..]) {
+            if !postfix.is_empty() {
+                self.first = None;
+                self.postfix_iter.skip_to(postfix);
+            }
+        } else if key > &self.prefix[..] {
+            // Past every match.
+            self.first = None;
+            self.postfix_iter.clear();
+        }
+    }
+
+    fn advance(&mut self) -> Option<(C, &'a Value)> {
+        match self.first.take() {
+            None => self.postfix_iter.next(),
+            x => x,
+        }
+    }
+}
+
 impl<'a, Label: Ord + Clone, Value, C, M> Iterator for SearchIter<'a, Label, Value, C, M>
 where
     C: TryFromIterator<Label, M> + Clone,
@@ -68,21 +127,65 @@ where
     type Item = (C, &'a Value);
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        match self.first.take() {
-            // None => None,
-            None => self.postfix_iter.next().map(|(postfix, v)| {
-                let entry = C::try_from_iter(self.prefix.clone().into_iter().chain(postfix))
-                    .expect("Could not collect postfix");
-                // let mut entry = self.prefix.clone();
-                // let ext: C = postfix.into_iter().try_collect().expect("Could not collect postfix");
-                // entry.extend([ext]);
-                (entry, v)
-            }),
-            x => x,
+        #[cfg(feature = "alloc-metrics")]
+        let probe = Probe::start();
+        let item = self.advance();
+        #[cfg(feature = "alloc-metrics")]
+        probe.finish(&mut self.stats);
+        item
+    }
+}
+
+impl<'a, Label: Clone, Value, C, M> PairsWith<Label> for SearchIter<'a, Label, Value, C, M> {
+    type With<C2, M2> = SearchIter<'a, Label, Value, C2, M2>;
+
+    fn pairs_with<C2, M2>(self) -> Self::With<C2, M2>
+    where
+        C2: TryFromIterator<Label, M2>,
+    {
+        // The exact match was collected up front, so collect it again.
+        let first = self.first.map(|(_, value)| {
+            (
+                self.prefix
+                    .iter()
+                    .cloned()
+                    .try_collect()
+                    .expect("Could not collect"),
+                value,
+            )
+        });
+        SearchIter {
+            prefix: self.prefix,
+            first,
+            postfix_iter: self.postfix_iter.pairs_with(),
+            #[cfg(feature = "alloc-metrics")]
+            stats: self.stats,
+            col: PhantomData,
         }
     }
 }
 
+impl<'a, Label: Ord + Clone, Value, C, M> StepLimit for SearchIter<'a, Label, Value, C, M>
+where
+    C: TryFromIterator<Label, M> + Clone,
+    Vec<Label>: TryFromIterator<Label, Collect>,
+{
+    fn budget(&self) -> &StepBudget {
+        self.postfix_iter.budget()
+    }
+
+    fn budget_mut(&mut self) -> &mut StepBudget {
+        self.postfix_iter.budget_mut()
+    }
+}
+
+#[cfg(feature = "alloc-metrics")]
+impl<Label, Value, C, M> Stats for SearchIter<'_, Label, Value, C, M> {
+    fn stats(&self) -> SearchStats {
+        self.stats
+    }
+}
+
 // impl<'a, Label: Ord + Clone, Value, C> Iterator for SearchIter<'a, Label, Value, C, Collect>
 // where C: TryFromIterator<Label, Collect> + Extend<Label> + Clone,
 // Vec<Label>: TryFromIterator<Label, Collect>
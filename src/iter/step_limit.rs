@@ -0,0 +1,200 @@
+use std::time::Instant;
+
+/// How often, in steps, a deadline is compared with the clock.
+const DEADLINE_CHECK_INTERVAL: usize = 64;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// Yielded by a [Truncating] iterator in place of the remaining items once its
+/// budget ran out.
+pub struct Truncated;
+
+#[derive(Debug, Clone)]
+/// The work a search may do: a number of steps, each visiting one node, and a
+/// deadline. Both are unlimited unless set through [StepLimit].
+pub struct StepBudget {
+    steps: usize,
+    max_steps: usize,
+    deadline: Option<Instant>,
+    exhausted: bool,
+}
+
+impl Default for StepBudget {
+    fn default() -> Self {
+        Self {
+            steps: 0,
+            max_steps: usize::MAX,
+            deadline: None,
+            exhausted: false,
+        }
+    }
+}
+
+impl StepBudget {
+    /// Return the steps taken so far.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Return true if the search stopped with work left.
+    pub fn is_exhausted(&self) -> bool {
+        self.exhausted
+    }
+
+    /// Take a step, or return false and stop for good if the budget ran out.
+    pub(crate) fn spend(&mut self) -> bool {
+        if self.exhausted {
+            return false;
+        }
+        // Checking the clock on every step would cost more than the step.
+        let past_deadline = match self.deadline {
+            Some(deadline) if self.steps & (DEADLINE_CHECK_INTERVAL - 1) == 0 => {
+                Instant::now() >= deadline
+            }
+            _ => false,
+        };
+        if self.steps >= self.max_steps || past_deadline {
+            self.exhausted = true;
+            return false;
+        }
+        self.steps += 1;
+        true
+    }
+}
+
+/// Search iterators whose work can be bounded, so that interactive callers
+/// can cap the latency of a search over a large or unlucky subtree.
+///
+/// ```rust
+/// use trie_rs::iter::{StepLimit, Truncated};
+/// use trie_rs::map::Trie;
+///
+/// let trie = Trie::from_iter((0..1000).map(|i| (format!("key{}", i), i)));
+/// let results: Vec<Result<(String, &i32), Truncated>> =
+///     trie.predictive_search("key").with_step_limit(10).collect();
+/// // At most one entry per step, then the marker.
+/// assert!(results.len() <= 11);
+/// assert_eq!(results.last(), Some(&Err(Truncated)));
+///
+/// // With enough budget, no marker.
+/// assert!(trie.predictive_search::<String, _>("key9").with_step_limit(1000).all(|r| r.is_ok()));
+/// ```
+pub trait StepLimit: Iterator + Sized {
+    /// Return the budget of this search.
+    fn budget(&self) -> &StepBudget;
+
+    /// Return the budget of this search, to limit it.
+    fn budget_mut(&mut self) -> &mut StepBudget;
+
+    /// Stop after visiting `steps` more nodes, then yield [Truncated] if
+    /// there was work left.
+    fn with_step_limit(self, steps: usize) -> Truncating<Self> {
+        Truncating::new(self).with_step_limit(steps)
+    }
+
+    /// Stop once `deadline` has passed, then yield [Truncated] if there was
+    /// work left. The clock is only read every few steps.
+    fn with_deadline(self, deadline: Instant) -> Truncating<Self> {
+        Truncating::new(self).with_deadline(deadline)
+    }
+}
+
+#[derive(Debug, Clone)]
+/// Yields `Ok` items from a search with a budget, then [Truncated] once if the
+/// budget ran out before the search finished. See [StepLimit].
+pub struct Truncating<I> {
+    iter: I,
+    done: bool,
+}
+
+impl<I: StepLimit> Truncating<I> {
+    fn new(iter: I) -> Self {
+        Self { iter, done: false }
+    }
+
+    /// Stop after visiting `steps` more nodes. See [StepLimit::with_step_limit].
+    pub fn with_step_limit(mut self, steps: usize) -> Self {
+        let budget = self.iter.budget_mut();
+        budget.max_steps = budget.steps.saturating_add(steps);
+        self
+    }
+
+    /// Stop once `deadline` has passed. See [StepLimit::with_deadline].
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.iter.budget_mut().deadline = Some(deadline);
+        self
+    }
+
+    /// Return the budget of the search.
+    pub fn budget(&self) -> &StepBudget {
+        self.iter.budget()
+    }
+
+    /// Return the search iterator.
+    pub fn into_inner(self) -> I {
+        self.iter
+    }
+}
+
+impl<I: StepLimit> Iterator for Truncating<I> {
+    type Item = Result<I::Item, Truncated>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.iter.next() {
+            Some(item) => Some(Ok(item)),
+            None => {
+                self.done = true;
+                self.iter.budget().is_exhausted().then_some(Err(Truncated))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod step_limit_tests {
+    use crate::iter::{StepLimit, Truncated};
+    use crate::map::Trie;
+    use crate::Trie as SetTrie;
+    use std::time::Instant;
+
+    #[test]
+    fn step_limit() {
+        let trie = Trie::from_iter([("a", 0), ("ab", 1), ("abc", 2), ("b", 3)]);
+        // Exactly enough steps is not truncated.
+        let results: Vec<Result<(String, &u8), Truncated>> =
+            trie.iter().with_step_limit(4).collect();
+        assert_eq!(results.len(), 4);
+        assert!(results.iter().all(Result::is_ok));
+
+        let results: Vec<Result<(String, &u8), Truncated>> =
+            trie.iter().with_step_limit(2).collect();
+        assert_eq!(
+            results,
+            [
+                Ok(("a".to_string(), &0)),
+                Ok(("ab".to_string(), &1)),
+                Err(Truncated)
+            ]
+        );
+
+        let mut iter = trie.predictive_search::<String, _>("a").with_step_limit(0);
+        // The exact match is found while descending, for free.
+        assert_eq!(iter.next(), Some(Ok(("a".to_string(), &0))));
+        assert_eq!(iter.next(), Some(Err(Truncated)));
+        assert_eq!(iter.next(), None);
+        assert!(iter.budget().is_exhausted());
+
+        let results: Vec<Result<(String, usize, &u8), Truncated>> =
+            trie.fuzzy_search("b", 0).with_step_limit(1).collect();
+        assert_eq!(results, [Err(Truncated)]);
+    }
+
+    #[test]
+    fn deadline() {
+        let trie = SetTrie::from_iter(["a", "b"]);
+        let results: Vec<Result<String, Truncated>> =
+            trie.iter().with_deadline(Instant::now()).collect();
+        assert_eq!(results, [Err(Truncated)]);
+    }
+}
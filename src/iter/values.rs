@@ -0,0 +1,42 @@
+#[derive(Debug, Clone)]
+/// Retains values and strips off keys from a [crate::iter] iterator.
+pub struct Values<I>(I);
+
+impl<I> Values<I> {
+    /// Creates a new `Values` iterator.
+    pub fn new(iter: I) -> Self {
+        Self(iter)
+    }
+}
+
+impl<I, C, V> Iterator for Values<I>
+where
+    I: Iterator<Item = (C, V)>,
+{
+    type Item = V;
+    fn next(&mut self) -> Option<V> {
+        self.0.next().map(|x| x.1)
+    }
+}
+
+/// Strip an iterator items `(K, V)` to only have `V`.
+pub trait ValuesExt: Iterator {
+    /// Retain values and strip keys from a [crate::iter] iterator.
+    ///
+    /// ```rust
+    /// use trie_rs::iter::ValuesExt;
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("a", 0), ("app", 1), ("apple", 2)]);
+    /// let values: Vec<&u8> = trie.predictive_search::<String, _>("app").values().collect();
+    /// assert_eq!(values, [&1, &2]);
+    /// ```
+    fn values(self) -> Values<Self>
+    where
+        Self: Sized,
+    {
+        Values::new(self)
+    }
+}
+
+impl<T> ValuesExt for T where T: Iterator + ?Sized {}
@@ -2,10 +2,21 @@
 #![doc(html_root_url = "https://docs.rs/trie-rs/0.4.2")]
 #![doc = include_str!("../README.md")]
 
+#[cfg(feature = "alloc-metrics")]
+pub mod alloc_metrics;
+#[cfg(feature = "capi")]
+pub mod capi;
 pub mod inc_search;
 mod internal_data_structure;
 pub mod iter;
 pub mod map;
+pub mod raw;
+#[cfg(feature = "testing")]
+pub mod reference;
+#[cfg(feature = "testing")]
+pub mod testing;
 mod trie;
 pub mod try_collect;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 pub use trie::{Trie, TrieBuilder};
@@ -1,9 +1,86 @@
 //! A trie that maps sequence of `Label`s to a `Value`.
 use crate::internal_data_structure::naive_trie::NaiveTrie;
-use louds_rs::Louds;
+use louds_rs::{Louds, LoudsNodeNum};
 
+mod alignment;
+mod arc_values;
+mod binary;
+mod build_report;
+mod case_insensitive;
+mod compression_report;
+mod expiring_trie;
+mod flag_index;
+mod ingest;
+mod key_len_limit;
+mod label_arena;
+mod label_runs;
+mod multi_trie;
+mod mutation;
+mod node_data;
+mod node_ref;
+mod node_values;
+mod pattern_trie;
+#[cfg(feature = "phonetic")]
+mod phonetic;
+mod popular;
+mod random_walk;
+mod remapped_trie;
+mod reserved_values;
+mod scan;
+mod scored_lines;
+mod shards;
+mod shared_trie;
+mod snapshot_trie;
+mod sorted_trie_builder;
+mod stable_hash;
+mod tiny_paths;
+mod token_views;
+mod top_k;
 mod trie;
 mod trie_builder;
+mod trie_ref;
+mod try_queries;
+mod updatable_trie;
+mod validating_builder;
+#[cfg(feature = "serde")]
+mod versioned;
+mod word_tokens;
+
+pub use alignment::Alignment;
+pub use binary::{BinaryCodec, BINARY_FORMAT_VERSION};
+pub use build_report::BuildReport;
+pub use case_insensitive::{CaseFold, CaseInsensitiveTrie, CaseInsensitiveTrieBuilder};
+pub use compression_report::{CompressionReport, SuffixSharing};
+pub use expiring_trie::ExpiringTrie;
+pub use flag_index::FlagIndex;
+pub use ingest::IngestAborted;
+pub use key_len_limit::{InvalidKey, KeyLenLimited, KeyLenLimitedBuilder, KeyTooLong};
+pub use label_arena::LabelArena;
+use label_runs::LabelRuns;
+pub use multi_trie::{MultiIter, MultiTrie};
+pub use node_data::NodeData;
+pub use node_ref::{Ancestors, ChildNodeRefs, Dfs, DfsOrder, NodeRef, PrefixNodes};
+use node_values::NodeValues;
+pub use pattern_trie::{PatternTrie, PatternTrieBuilder};
+#[cfg(feature = "phonetic")]
+pub use phonetic::{PhoneticEncoder, PhoneticTrie, PhoneticTrieBuilder, Soundex};
+pub use remapped_trie::RemappedTrie;
+pub use reserved_values::NotAKey;
+pub use scan::Scan;
+pub use scored_lines::ScoredLinesError;
+pub use shared_trie::{SharedNode, SharedSearchIter, SharedTrie, SharedValue};
+pub use snapshot_trie::{Snapshot, SnapshotTrie};
+pub use sorted_trie_builder::SortedTrieBuilder;
+pub use stable_hash::StableHasher;
+use tiny_paths::TinyPaths;
+pub use token_views::{ByteQueries, CharQueries};
+pub use top_k::ScoreIndex;
+pub use trie_ref::{DynTrieRef, TrieRef};
+pub use updatable_trie::{UpdatableSearchIter, UpdatableTrie};
+pub use validating_builder::ValidatingTrieBuilder;
+#[cfg(feature = "serde")]
+pub use versioned::{FormatVersionMismatch, Versioned, FORMAT_VERSION};
+pub use word_tokens::WordQueries;
 
 #[cfg(feature = "mem_dbg")]
 use mem_dbg::MemDbg;
@@ -15,8 +92,25 @@ use mem_dbg::MemDbg;
 pub struct Trie<Label, Value> {
     louds: Louds,
 
-    /// (LoudsNodeNum - 2) -> TrieLabel
-    trie_labels: Vec<TrieLabel<Label, Value>>,
+    /// (LoudsNodeNum - 2) -> Label
+    pub(crate) trie_labels: Vec<Label>,
+
+    /// (LoudsNodeNum - 2) -> `Option<Value>`
+    pub(crate) values: NodeValues<Value>,
+
+    /// Contiguous copies of single-child chains. See [Trie::build_label_runs].
+    runs: Option<LabelRuns<Label>>,
+
+    /// Terminal nodes in the order their values were pushed. See
+    /// [TrieBuilder::keep_insertion_order].
+    insertion_order: Option<Vec<LoudsNodeNum>>,
+
+    /// Lengths that keys have and the number of keys of each, by length.
+    /// See [Trie::key_len_histogram].
+    key_lens: Vec<(usize, usize)>,
+
+    /// Paths to every key if there are only a few. See [TinyPaths].
+    tiny: Option<TinyPaths>,
 }
 
 #[derive(Debug, Clone)]
@@ -25,12 +119,5 @@ pub struct Trie<Label, Value> {
 /// A trie builder for [Trie].
 pub struct TrieBuilder<Label, Value> {
     naive_trie: NaiveTrie<Label, Value>,
-}
-
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-struct TrieLabel<Label, Value> {
-    label: Label,
-    value: Option<Value>,
+    keep_insertion_order: bool,
 }
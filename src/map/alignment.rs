@@ -0,0 +1,78 @@
+use super::Trie;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+/// Where a query over a `u8` trie of UTF-8 keys may end. See
+/// [Trie::is_prefix_aligned].
+pub enum Alignment {
+    /// Anywhere, like [Trie::is_prefix]: a query that ends partway through a
+    /// multi-byte `char` of a key is a prefix too.
+    #[default]
+    Byte,
+    /// Only where a `char` of the key ends, so a query cut partway through a
+    /// `char` is never a prefix.
+    Char,
+}
+
+/// Return true if `byte` continues a multi-byte UTF-8 `char`.
+fn is_continuation(byte: u8) -> bool {
+    byte & 0b1100_0000 == 0b1000_0000
+}
+
+impl<Value> Trie<u8, Value> {
+    /// Return true if `query` is a prefix, with its end aligned as
+    /// `alignment` says.
+    ///
+    /// With [Alignment::Char], some key must continue after `query` with the
+    /// first byte of a new `char`.
+    ///
+    /// ```rust
+    /// use trie_rs::map::{Alignment, Trie};
+    ///
+    /// let trie = Trie::from_iter([("アップル", 0)]);
+    /// let query = "アップ".as_bytes();
+    /// assert!(trie.is_prefix_aligned(query, Alignment::Char));
+    /// // "ア" is three bytes, so its first byte is a prefix only byte-wise.
+    /// assert!(trie.is_prefix_aligned(&query[..1], Alignment::Byte));
+    /// assert!(!trie.is_prefix_aligned(&query[..1], Alignment::Char));
+    /// ```
+    pub fn is_prefix_aligned(&self, query: impl AsRef<[u8]>, alignment: Alignment) -> bool {
+        match alignment {
+            Alignment::Byte => self.is_prefix(query),
+            Alignment::Char => match self.node_num(query.as_ref()) {
+                Some(node) => self
+                    .children_node_nums(node)
+                    .any(|child| !is_continuation(*self.label(child))),
+                None => false,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod alignment_tests {
+    use crate::map::{Alignment, Trie};
+
+    #[test]
+    fn both_alignments() {
+        let trie = Trie::from_iter([("ab", 0), ("aé", 1), ("🍎🍏", 2)]);
+        let apples = "🍎🍏".as_bytes();
+        for len in 0..apples.len() {
+            let query = &apples[..len];
+            assert!(trie.is_prefix_aligned(query, Alignment::Byte), "{}", len);
+            assert_eq!(
+                trie.is_prefix_aligned(query, Alignment::Char),
+                len == 0 || len == 4,
+                "{}",
+                len
+            );
+        }
+        assert!(!trie.is_prefix_aligned(apples, Alignment::Byte));
+        assert!(!trie.is_prefix_aligned(apples, Alignment::Char));
+
+        // "a" is followed by both a whole char and the start of one.
+        assert!(trie.is_prefix_aligned("a", Alignment::Char));
+        assert!(!trie.is_prefix_aligned(&"aé".as_bytes()[..2], Alignment::Char));
+        assert!(!trie.is_prefix_aligned("b", Alignment::Byte));
+        assert_eq!(Alignment::default(), Alignment::Byte);
+    }
+}
@@ -0,0 +1,78 @@
+use super::Trie;
+use crate::try_collect::TryFromIterator;
+use std::sync::Arc;
+
+impl<Label: Ord, Value> Trie<Label, Arc<Value>> {
+    /// Return a clone of the [Arc] if query is an exact match. Unlike
+    /// [Trie::exact_match], the value outlives the borrow of the trie, e.g.
+    /// to send it to another thread, without needing `Value: Clone`.
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("config", Arc::new(vec![1, 2, 3]))]);
+    /// let value = trie.exact_match_arc("config").unwrap();
+    /// drop(trie);
+    /// assert_eq!(*value, [1, 2, 3]);
+    /// ```
+    pub fn exact_match_arc(&self, query: impl AsRef<[Label]>) -> Option<Arc<Value>> {
+        self.exact_match(query).cloned()
+    }
+
+    /// Like [Trie::predictive_search], with clones of the [Arc]s.
+    pub fn predictive_search_arc<'a, C, M>(
+        &'a self,
+        query: impl AsRef<[Label]>,
+    ) -> impl Iterator<Item = (C, Arc<Value>)> + 'a
+    where
+        C: TryFromIterator<Label, M> + Clone + 'a,
+        M: 'a,
+        Label: Clone,
+    {
+        self.predictive_search(query)
+            .map(|(key, value)| (key, Arc::clone(value)))
+    }
+
+    /// Like [Trie::common_prefix_search], with clones of the [Arc]s.
+    pub fn common_prefix_search_arc<'a, C, M>(
+        &'a self,
+        query: impl AsRef<[Label]>,
+    ) -> impl Iterator<Item = (C, Arc<Value>)> + 'a
+    where
+        C: TryFromIterator<Label, M> + 'a,
+        M: 'a,
+        Label: Clone,
+    {
+        self.common_prefix_search(query)
+            .map(|(key, value)| (key, Arc::clone(value)))
+    }
+}
+
+#[cfg(test)]
+mod arc_values_tests {
+    use crate::map::Trie;
+    use std::sync::{mpsc, Arc};
+
+    #[test]
+    fn across_threads() {
+        let trie = Trie::from_iter([
+            ("a", Arc::new("A".to_string())),
+            ("ab", Arc::new("AB".into())),
+        ]);
+        let (tx, rx) = mpsc::channel();
+        for (key, value) in trie.predictive_search_arc::<String, _>("a") {
+            let tx = tx.clone();
+            std::thread::spawn(move || tx.send((key, value)).unwrap());
+        }
+        drop(tx);
+        let mut received: Vec<(String, Arc<String>)> = rx.iter().collect();
+        received.sort();
+        assert_eq!(received[1].1.as_str(), "AB");
+        assert_eq!(Arc::strong_count(&received[0].1), 2);
+
+        let prefixes: Vec<(String, Arc<String>)> = trie.common_prefix_search_arc("abc").collect();
+        assert_eq!(prefixes.len(), 2);
+        assert_eq!(trie.exact_match_arc("b"), None);
+    }
+}
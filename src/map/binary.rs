@@ -0,0 +1,434 @@
+use super::{NodeValues, Trie};
+use louds_rs::{Louds, LoudsNodeNum};
+use std::io::{self, Read, Write};
+
+const MAGIC: &[u8; 4] = b"TRIE";
+
+/// The version of the format written by [Trie::serialize_into]. It is bumped
+/// whenever the format changes.
+pub const BINARY_FORMAT_VERSION: u32 = 1;
+
+/// A label or value that [Trie::serialize_into] can write as bytes and
+/// [Trie::deserialize_from] read back.
+///
+/// Integers are written as little-endian bytes, `char` as a `u32`, lengths
+/// as a `u64`, and [Option] as a tag byte followed by its value, so the
+/// bytes are the same on every platform.
+pub trait BinaryCodec: Sized {
+    /// Append the bytes of `self` to `out`.
+    fn encode(&self, out: &mut Vec<u8>);
+
+    /// Read a value from the front of `bytes`, and advance past it.
+    fn decode(bytes: &mut &[u8]) -> io::Result<Self>;
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn take<'a>(bytes: &mut &'a [u8], n: usize) -> io::Result<&'a [u8]> {
+    if bytes.len() < n {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "trie data is truncated",
+        ));
+    }
+    let (head, tail) = bytes.split_at(n);
+    *bytes = tail;
+    Ok(head)
+}
+
+fn decode_len(bytes: &mut &[u8]) -> io::Result<usize> {
+    usize::try_from(u64::decode(bytes)?).map_err(|_| invalid("length overflows usize"))
+}
+
+macro_rules! int_codec {
+    ($($int:ty),*) => {
+        $(
+            impl BinaryCodec for $int {
+                fn encode(&self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&self.to_le_bytes());
+                }
+
+                fn decode(bytes: &mut &[u8]) -> io::Result<Self> {
+                    let head = take(bytes, std::mem::size_of::<Self>())?;
+                    Ok(Self::from_le_bytes(head.try_into().unwrap()))
+                }
+            }
+        )*
+    };
+}
+
+int_codec!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+impl BinaryCodec for usize {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (*self as u64).encode(out);
+    }
+
+    fn decode(bytes: &mut &[u8]) -> io::Result<Self> {
+        decode_len(bytes)
+    }
+}
+
+impl BinaryCodec for () {
+    fn encode(&self, _: &mut Vec<u8>) {}
+
+    fn decode(_: &mut &[u8]) -> io::Result<Self> {
+        Ok(())
+    }
+}
+
+impl BinaryCodec for bool {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(u8::from(*self));
+    }
+
+    fn decode(bytes: &mut &[u8]) -> io::Result<Self> {
+        match u8::decode(bytes)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(invalid("invalid bool")),
+        }
+    }
+}
+
+impl BinaryCodec for char {
+    fn encode(&self, out: &mut Vec<u8>) {
+        u32::from(*self).encode(out);
+    }
+
+    fn decode(bytes: &mut &[u8]) -> io::Result<Self> {
+        char::from_u32(u32::decode(bytes)?).ok_or_else(|| invalid("invalid char"))
+    }
+}
+
+impl BinaryCodec for String {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.len().encode(out);
+        out.extend_from_slice(self.as_bytes());
+    }
+
+    fn decode(bytes: &mut &[u8]) -> io::Result<Self> {
+        let len = decode_len(bytes)?;
+        let head = take(bytes, len)?;
+        String::from_utf8(head.to_vec()).map_err(|_| invalid("invalid UTF-8"))
+    }
+}
+
+impl<T: BinaryCodec> BinaryCodec for Vec<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.len().encode(out);
+        for item in self {
+            item.encode(out);
+        }
+    }
+
+    fn decode(bytes: &mut &[u8]) -> io::Result<Self> {
+        let len = decode_len(bytes)?;
+        // Every item but a zero-sized one takes at least a byte.
+        let mut items = Vec::with_capacity(len.min(bytes.len()));
+        for _ in 0..len {
+            items.push(T::decode(bytes)?);
+        }
+        Ok(items)
+    }
+}
+
+impl<T: BinaryCodec> BinaryCodec for Option<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            None => out.push(0),
+            Some(value) => {
+                out.push(1);
+                value.encode(out);
+            }
+        }
+    }
+
+    fn decode(bytes: &mut &[u8]) -> io::Result<Self> {
+        match u8::decode(bytes)? {
+            0 => Ok(None),
+            1 => T::decode(bytes).map(Some),
+            _ => Err(invalid("invalid option tag")),
+        }
+    }
+}
+
+impl<A: BinaryCodec, B: BinaryCodec> BinaryCodec for (A, B) {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.0.encode(out);
+        self.1.encode(out);
+    }
+
+    fn decode(bytes: &mut &[u8]) -> io::Result<Self> {
+        Ok((A::decode(bytes)?, B::decode(bytes)?))
+    }
+}
+
+/// Append `bits`, least significant bit first.
+fn encode_bits(bits: impl IntoIterator<Item = bool>, out: &mut Vec<u8>) {
+    let mut byte = 0u8;
+    let mut used = 0;
+    for bit in bits {
+        byte |= u8::from(bit) << used;
+        used += 1;
+        if used == 8 {
+            out.push(byte);
+            byte = 0;
+            used = 0;
+        }
+    }
+    if used > 0 {
+        out.push(byte);
+    }
+}
+
+fn decode_bits(bytes: &mut &[u8], len: usize) -> io::Result<Vec<bool>> {
+    let head = take(bytes, len / 8 + usize::from(len & 7 > 0))?;
+    Ok((0..len)
+        .map(|i| (head[i / 8] >> (i % 8)) & 1 == 1)
+        .collect())
+}
+
+impl<Label: Ord + Clone + BinaryCodec, Value: BinaryCodec> Trie<Label, Value> {
+    /// Write the trie to `writer` in a compact binary format: the LOUDS bits
+    /// of the tree, the labels, and the values, each label and value written
+    /// by [BinaryCodec]. Indexes that can be recomputed are left out, so the
+    /// output is a fraction of the size of serializing the trie with serde.
+    ///
+    /// The output starts with [BINARY_FORMAT_VERSION];
+    /// [Trie::deserialize_from] rejects any other version. Insertion order is
+    /// kept, and label runs are rebuilt on reading.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("app", 1u32), ("apple", 2), ("アップル🍎", 3)]);
+    /// let mut bytes = Vec::new();
+    /// trie.serialize_into(&mut bytes).unwrap();
+    ///
+    /// let loaded = Trie::<u8, u32>::deserialize_from(&bytes[..]).unwrap();
+    /// assert_eq!(loaded.exact_match("apple"), Some(&2));
+    /// assert!(loaded.canonical_eq(&trie));
+    /// ```
+    pub fn serialize_into<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let node_count = self.trie_labels.len();
+        let mut out = Vec::new();
+        out.extend_from_slice(MAGIC);
+        BINARY_FORMAT_VERSION.encode(&mut out);
+        node_count.encode(&mut out);
+
+        // Children are counted in unary, node by node.
+        let mut bits = vec![true, false];
+        for node in 1..=node_count as u64 + 1 {
+            let children = self.children_node_nums(LoudsNodeNum(node)).len();
+            bits.resize(bits.len() + children, true);
+            bits.push(false);
+        }
+        encode_bits(bits, &mut out);
+        for label in &self.trie_labels {
+            label.encode(&mut out);
+        }
+        encode_bits((0..node_count).map(|i| self.values.is_some(i)), &mut out);
+        for value in (0..node_count).filter_map(|i| self.values.get(i)) {
+            value.encode(&mut out);
+        }
+
+        let order: Option<Vec<u64>> = self
+            .insertion_order
+            .as_ref()
+            .map(|nodes| nodes.iter().map(|node| node.0).collect());
+        order.encode(&mut out);
+        self.runs.is_some().encode(&mut out);
+        writer.write_all(&out)
+    }
+
+    /// Read a trie written by [Trie::serialize_into].
+    ///
+    /// Fail with [io::ErrorKind::InvalidData] if the data is not a trie, was
+    /// written with another [BINARY_FORMAT_VERSION], or holds labels or
+    /// values that do not decode, and with [io::ErrorKind::UnexpectedEof] if
+    /// it is cut short.
+    pub fn deserialize_from<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        let bytes = &mut &data[..];
+
+        if take(bytes, MAGIC.len())? != MAGIC {
+            return Err(invalid("not a trie"));
+        }
+        let version = u32::decode(bytes)?;
+        if version != BINARY_FORMAT_VERSION {
+            return Err(invalid(&format!(
+                "trie written with binary format version {}, but this version of trie-rs reads {}",
+                version, BINARY_FORMAT_VERSION
+            )));
+        }
+        let node_count = decode_len(bytes)?;
+        let bit_len = node_count
+            .checked_mul(2)
+            .and_then(|n| n.checked_add(3))
+            .ok_or_else(|| invalid("too many nodes"))?;
+        let bits = decode_bits(bytes, bit_len)?;
+        // Every node but the root must be a child of a node before it.
+        let mut children = 0;
+        let mut node = 0;
+        for bit in &bits[2..] {
+            if *bit {
+                children += 1;
+            } else {
+                node += 1;
+                if node <= node_count && children < node {
+                    return Err(invalid("invalid tree"));
+                }
+            }
+        }
+        if bits[..2] != [true, false] || children != node_count || node != node_count + 1 {
+            return Err(invalid("invalid tree"));
+        }
+        let louds = Louds::from(&bits[..]);
+
+        let trie_labels = (0..node_count)
+            .map(|_| Label::decode(bytes))
+            .collect::<io::Result<Vec<_>>>()?;
+        let mut values = NodeValues::default();
+        for present in decode_bits(bytes, node_count)? {
+            values.push(if present {
+                Some(Value::decode(bytes)?)
+            } else {
+                None
+            });
+        }
+        let insertion_order = Option::<Vec<u64>>::decode(bytes)?
+            .map(|nodes| {
+                nodes
+                    .into_iter()
+                    .map(|node| match usize::try_from(node) {
+                        Ok(n) if (2..node_count + 2).contains(&n) && values.is_some(n - 2) => {
+                            Ok(LoudsNodeNum(node))
+                        }
+                        _ => Err(invalid("invalid insertion order")),
+                    })
+                    .collect::<io::Result<Vec<_>>>()
+            })
+            .transpose()?;
+        let runs = bool::decode(bytes)?;
+        if !bytes.is_empty() {
+            return Err(invalid("trailing data after trie"));
+        }
+
+        let mut trie = Trie::from_parts(louds, trie_labels, values, insertion_order);
+        if !(1..=node_count as u64 + 1).all(|node| trie.children_sorted(LoudsNodeNum(node))) {
+            return Err(invalid("children out of order"));
+        }
+        if runs {
+            trie.build_label_runs();
+        }
+        Ok(trie)
+    }
+
+    /// Return true if the labels of the children of `node` strictly ascend.
+    fn children_sorted(&self, node: LoudsNodeNum) -> bool {
+        let mut children = self.children_node_nums(node).map(|child| self.label(child));
+        let Some(mut last) = children.next() else {
+            return true;
+        };
+        children.all(|label| {
+            let ascends = last < label;
+            last = label;
+            ascends
+        })
+    }
+}
+
+#[cfg(test)]
+mod binary_tests {
+    use crate::map::{Trie, TrieBuilder};
+    use std::io::ErrorKind;
+
+    fn round_trip<Label, Value>(trie: &Trie<Label, Value>) -> (Vec<u8>, Trie<Label, Value>)
+    where
+        Label: Ord + Clone + super::BinaryCodec,
+        Value: super::BinaryCodec,
+    {
+        let mut bytes = Vec::new();
+        trie.serialize_into(&mut bytes).unwrap();
+        let loaded = Trie::deserialize_from(&bytes[..]).unwrap();
+        (bytes, loaded)
+    }
+
+    #[test]
+    fn round_trips() {
+        let mut builder = TrieBuilder::new();
+        builder.keep_insertion_order();
+        for (i, key) in ["better", "a", "app", "application", "apple", "アップル🍎"]
+            .iter()
+            .enumerate()
+        {
+            builder.push(
+                key.chars().collect::<Vec<char>>(),
+                (i as u64, key.to_string()),
+            );
+        }
+        let mut trie = builder.build();
+        trie.build_label_runs();
+        let (_, loaded) = round_trip(&trie);
+        assert!(loaded.canonical_eq(&trie));
+        assert!(loaded.runs.is_some());
+        let order: Vec<(String, &(u64, String))> = loaded.iter_insertion_order().unwrap().collect();
+        let expected: Vec<(String, &(u64, String))> =
+            trie.iter_insertion_order().unwrap().collect();
+        assert_eq!(order, expected);
+        assert_eq!(loaded.key_len_histogram(), trie.key_len_histogram());
+
+        let set = crate::Trie::from_iter(["a", "app", "apple"]);
+        let (bytes, loaded) = round_trip(&set.0);
+        assert!(loaded.canonical_eq(&set.0));
+        // Header, node count, 2 bytes of tree, 5 labels, 1 byte of presence
+        // bits, no insertion order and no runs.
+        assert_eq!(bytes.len(), 8 + 8 + 2 + 5 + 1 + 1 + 1);
+
+        let empty: Trie<u8, u8> = TrieBuilder::new().build();
+        let (_, loaded) = round_trip(&empty);
+        assert!(loaded.canonical_eq(&empty));
+    }
+
+    #[test]
+    fn rejects_bad_data() {
+        let trie = Trie::from_iter([("ab", 1u16), ("ac", 2)]);
+        let mut bytes = Vec::new();
+        trie.serialize_into(&mut bytes).unwrap();
+        let error = |bytes: &[u8]| Trie::<u8, u16>::deserialize_from(bytes).unwrap_err();
+
+        assert_eq!(error(b"TREE").kind(), ErrorKind::InvalidData);
+        let mut newer = bytes.clone();
+        newer[4] = 2;
+        assert!(error(&newer)
+            .to_string()
+            .contains("binary format version 2"));
+        for len in 0..bytes.len() {
+            assert_eq!(
+                error(&bytes[..len]).kind(),
+                ErrorKind::UnexpectedEof,
+                "{}",
+                len
+            );
+        }
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert_eq!(error(&trailing).kind(), ErrorKind::InvalidData);
+        let mut huge = bytes.clone();
+        huge[8..16].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert_eq!(error(&huge).kind(), ErrorKind::InvalidData);
+        // Swap the labels of "b" and "c".
+        let labels = 16 + 2;
+        let mut unsorted = bytes.clone();
+        unsorted.swap(labels + 1, labels + 2);
+        assert_eq!(error(&unsorted).to_string(), "children out of order");
+        // Give the root a third child.
+        let mut tree = bytes.clone();
+        tree[16] |= 1 << 3;
+        assert_eq!(error(&tree).kind(), ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,93 @@
+use super::{Trie, TrieBuilder};
+use std::mem::size_of;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// What went into a [Trie], e.g. for a data pipeline to log. See
+/// [TrieBuilder::build_with_report].
+pub struct BuildReport {
+    /// Number of entries pushed, counting repeated keys each time.
+    pub pushes: usize,
+    /// Number of distinct keys.
+    pub keys: usize,
+    /// Number of pushes whose key had been pushed before, and whose value
+    /// replaced the earlier one.
+    pub duplicates: usize,
+    /// Number of nodes, including the root.
+    pub nodes: usize,
+    /// Number of labels in the longest key.
+    pub max_depth: usize,
+    /// Bytes allocated for the labels, the LOUDS bit vector (excluding its
+    /// rank/select index) and the values. Memory owned by the values
+    /// themselves is not counted.
+    pub bytes: usize,
+}
+
+impl<Label: Ord, Value> TrieBuilder<Label, Value> {
+    /// Build a [Trie] like [TrieBuilder::build], and report on it from what
+    /// the build already counts, without another pass over the trie.
+    ///
+    /// ```rust
+    /// use trie_rs::map::TrieBuilder;
+    ///
+    /// let mut builder = TrieBuilder::new();
+    /// for (key, value) in [("app", 0), ("apple", 1), ("app", 2)] {
+    ///     builder.push(key, value);
+    /// }
+    /// let (trie, report) = builder.build_with_report();
+    /// assert_eq!(trie.exact_match("app"), Some(&2));
+    /// assert_eq!((report.pushes, report.keys, report.duplicates), (3, 2, 1));
+    /// assert_eq!((report.nodes, report.max_depth), (6, 5));
+    /// ```
+    pub fn build_with_report(self) -> (Trie<Label, Value>, BuildReport) {
+        let pushes = self.naive_trie.pushes();
+        let trie = self.build();
+        let keys = trie.key_count();
+        let nodes = trie.trie_labels.len() + 1;
+        // 2 * nodes + 1 bits, as in Trie::compression_report.
+        let louds_bytes = (nodes + 4) / 4;
+        let report = BuildReport {
+            pushes,
+            keys,
+            duplicates: pushes - keys,
+            nodes,
+            max_depth: trie.max_key_len().unwrap_or(0),
+            bytes: trie.trie_labels.capacity() * size_of::<Label>()
+                + louds_bytes
+                + trie.values.heap_bytes(),
+        };
+        (trie, report)
+    }
+}
+
+#[cfg(test)]
+mod build_report_tests {
+    use crate::map::{BuildReport, TrieBuilder};
+
+    #[test]
+    fn counts() {
+        let (_, report) = TrieBuilder::<u8, u8>::new().build_with_report();
+        assert_eq!(
+            report,
+            BuildReport {
+                pushes: 0,
+                keys: 0,
+                duplicates: 0,
+                nodes: 1,
+                max_depth: 0,
+                bytes: 1,
+            }
+        );
+
+        let mut builder = TrieBuilder::new();
+        for word in ["a", "b", "a", "abc", "b"] {
+            builder.push(word, ());
+        }
+        let (trie, report) = builder.build_with_report();
+        assert_eq!(report.pushes, 5);
+        assert_eq!(report.keys, 3);
+        assert_eq!(report.duplicates, 2);
+        assert_eq!(report.nodes, 5);
+        assert_eq!(report.max_depth, 3);
+        assert!(report.bytes >= trie.compression_report().stored_bytes());
+    }
+}
@@ -0,0 +1,234 @@
+use super::{Trie, TrieBuilder};
+use crate::try_collect::{Collect, TryCollect, TryFromIterator};
+use louds_rs::LoudsNodeNum;
+
+#[cfg(feature = "mem_dbg")]
+use mem_dbg::MemDbg;
+
+/// A label that can be folded to a case-insensitive form.
+pub trait CaseFold {
+    /// Return the case-folded form of this label.
+    fn case_fold(&self) -> Self;
+}
+
+impl CaseFold for u8 {
+    /// ASCII lowercase; other bytes, including UTF-8 continuation bytes, are
+    /// left alone.
+    fn case_fold(&self) -> Self {
+        self.to_ascii_lowercase()
+    }
+}
+
+impl CaseFold for char {
+    /// Unicode lowercase when it is a single `char`, e.g. not for 'İ'.
+    fn case_fold(&self) -> Self {
+        let mut lower = self.to_lowercase();
+        match (lower.next(), lower.next()) {
+            (Some(c), None) => c,
+            _ => *self,
+        }
+    }
+}
+
+fn fold<Label: CaseFold>(key: &[Label]) -> Vec<Label> {
+    key.iter().map(CaseFold::case_fold).collect()
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A trie map whose keys match regardless of case, but which returns keys as
+/// they were pushed.
+///
+/// ```rust
+/// use trie_rs::map::CaseInsensitiveTrieBuilder;
+///
+/// let mut builder = CaseInsensitiveTrieBuilder::new();
+/// builder.push("HashMap", 0);
+/// builder.push("hash", 1);
+/// builder.push("HashSet", 2);
+/// let trie = builder.build();
+///
+/// assert_eq!(trie.exact_match("hashmap"), Some(&0));
+/// let results: Vec<(String, &u8)> = trie.predictive_search("HASH").collect();
+/// assert_eq!(results, [("hash".to_string(), &1), ("HashMap".to_string(), &0), ("HashSet".to_string(), &2)]);
+/// ```
+pub struct CaseInsensitiveTrie<Label, Value>(Trie<Label, (Vec<Label>, Value)>);
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A trie builder for [CaseInsensitiveTrie].
+pub struct CaseInsensitiveTrieBuilder<Label, Value>(TrieBuilder<Label, (Vec<Label>, Value)>);
+
+impl<Label: Ord + Clone + CaseFold, Value> Default for CaseInsensitiveTrieBuilder<Label, Value> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Label: Ord + Clone + CaseFold, Value> CaseInsensitiveTrieBuilder<Label, Value> {
+    /// Return a [CaseInsensitiveTrieBuilder].
+    pub fn new() -> Self {
+        Self(TrieBuilder::new())
+    }
+
+    /// Add an entry and value. Keys that differ only in case are the same
+    /// entry; the last push wins, including its casing.
+    pub fn push<Arr: AsRef<[Label]>>(&mut self, entry: Arr, value: Value) {
+        let entry = entry.as_ref();
+        self.0.insert(fold(entry), (entry.to_vec(), value));
+    }
+
+    /// Build a [CaseInsensitiveTrie].
+    pub fn build(self) -> CaseInsensitiveTrie<Label, Value> {
+        CaseInsensitiveTrie(self.0.build())
+    }
+}
+
+impl<Label: Ord + Clone + CaseFold, Value> CaseInsensitiveTrie<Label, Value> {
+    /// Return `Some(&Value)` if query is an exact match ignoring case.
+    pub fn exact_match(&self, query: impl AsRef<[Label]>) -> Option<&Value> {
+        self.0.exact_match(fold(query.as_ref())).map(|(_, v)| v)
+    }
+
+    /// Return true if `query` is a key ignoring case. Labels are folded one
+    /// at a time, so unlike [CaseInsensitiveTrie::exact_match] this does not
+    /// allocate. See [Trie::contains_key].
+    pub fn contains_key(&self, query: impl AsRef<[Label]>) -> bool {
+        let mut node = LoudsNodeNum(1);
+        for label in query.as_ref() {
+            match self.0.child(node, &label.case_fold()) {
+                Some(child) => node = child,
+                None => return false,
+            }
+        }
+        self.0.is_terminal(node)
+    }
+
+    /// Return the key as it was pushed and its value if query is an exact
+    /// match ignoring case.
+    pub fn exact_match_original<C, M>(&self, query: impl AsRef<[Label]>) -> Option<(C, &Value)>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        self.0
+            .exact_match(fold(query.as_ref()))
+            .map(|(key, v)| (original(key), v))
+    }
+
+    /// Return true if `query` is a prefix ignoring case.
+    pub fn is_prefix(&self, query: impl AsRef<[Label]>) -> bool {
+        self.0.is_prefix(fold(query.as_ref()))
+    }
+
+    /// Return all entries and their values that match `query` ignoring case,
+    /// with keys as they were pushed.
+    pub fn predictive_search<'a, C, M>(
+        &'a self,
+        query: impl AsRef<[Label]>,
+    ) -> impl Iterator<Item = (C, &'a Value)> + 'a
+    where
+        C: TryFromIterator<Label, M> + 'a,
+        M: 'a,
+    {
+        self.0
+            .predictive_search::<Vec<Label>, Collect>(fold(query.as_ref()))
+            .map(|(_, (key, v))| (original(key), v))
+    }
+
+    /// Return the entries that are prefixes of `query` ignoring case, with
+    /// keys as they were pushed.
+    pub fn common_prefix_search<'a, C, M>(
+        &'a self,
+        query: impl AsRef<[Label]>,
+    ) -> impl Iterator<Item = (C, &'a Value)> + 'a
+    where
+        C: TryFromIterator<Label, M> + 'a,
+        M: 'a,
+    {
+        self.0
+            .common_prefix_search::<Vec<Label>, Collect>(fold(query.as_ref()))
+            .map(|(_, (key, v))| (original(key), v))
+    }
+
+    /// Return all entries in the order of their case-folded keys, with keys as
+    /// they were pushed.
+    pub fn iter<'a, C, M>(&'a self) -> impl Iterator<Item = (C, &'a Value)> + 'a
+    where
+        C: TryFromIterator<Label, M> + 'a,
+        M: 'a,
+    {
+        self.predictive_search([])
+    }
+}
+
+fn original<Label: Clone, C, M>(key: &[Label]) -> C
+where
+    C: TryFromIterator<Label, M>,
+{
+    key.iter()
+        .cloned()
+        .try_collect()
+        .expect("Could not collect")
+}
+
+#[cfg(test)]
+mod case_insensitive_tests {
+    use crate::map::{CaseInsensitiveTrie, CaseInsensitiveTrieBuilder};
+
+    fn build_trie() -> CaseInsensitiveTrie<u8, u8> {
+        let mut builder = CaseInsensitiveTrieBuilder::new();
+        builder.push("Apple", 0);
+        builder.push("APP", 1);
+        builder.push("banana", 2);
+        builder.push("アップル🍎", 3);
+        builder.build()
+    }
+
+    #[test]
+    fn folds_queries() {
+        let trie = build_trie();
+        assert_eq!(trie.exact_match("apple"), Some(&0));
+        assert_eq!(trie.exact_match("APPLE"), Some(&0));
+        assert_eq!(trie.exact_match("アップル🍎"), Some(&3));
+        assert_eq!(trie.exact_match("appl"), None);
+        assert!(trie.is_prefix("aPp"));
+        assert_eq!(
+            trie.exact_match_original::<String, _>("app"),
+            Some(("APP".to_string(), &1))
+        );
+    }
+
+    #[test]
+    fn returns_original_case() {
+        let trie = build_trie();
+        let results: Vec<(String, &u8)> = trie.common_prefix_search("applesauce").collect();
+        assert_eq!(
+            results,
+            [("APP".to_string(), &1), ("Apple".to_string(), &0)]
+        );
+        let results: Vec<(String, &u8)> = trie.iter().collect();
+        assert_eq!(results.len(), 4);
+    }
+
+    #[test]
+    fn last_push_wins() {
+        let mut builder = CaseInsensitiveTrieBuilder::new();
+        builder.push("Rust", 0);
+        builder.push("RUST", 1);
+        let trie = builder.build();
+        let results: Vec<(String, &u8)> = trie.iter().collect();
+        assert_eq!(results, [("RUST".to_string(), &1)]);
+    }
+
+    #[test]
+    fn chars() {
+        let mut builder: CaseInsensitiveTrieBuilder<char, u8> = CaseInsensitiveTrieBuilder::new();
+        let key: Vec<char> = "ÉCOLE".chars().collect();
+        builder.push(&key, 0);
+        let trie = builder.build();
+        let query: Vec<char> = "école".chars().collect();
+        assert_eq!(trie.exact_match(&query), Some(&0));
+    }
+}
@@ -0,0 +1,216 @@
+use super::Trie;
+use louds_rs::LoudsNodeNum;
+use std::collections::BTreeMap;
+use std::mem::size_of;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// How much space a [Trie] saves by sharing prefixes. See
+/// [Trie::compression_report].
+///
+/// Sizes are in bytes and count `Label`s and LOUDS bits only, not `Value`s,
+/// so they compare the same for a set and a map with the same keys.
+pub struct CompressionReport {
+    /// Number of keys.
+    pub keys: usize,
+    /// Size of all keys laid end to end, i.e. what storing every key on its
+    /// own would take.
+    pub key_bytes: usize,
+    /// Size of the labels stored in the trie, one per node.
+    pub label_bytes: usize,
+    /// Size of the LOUDS bit vector, excluding its rank/select index.
+    pub louds_bytes: usize,
+    /// Estimated size of the same keys in a `HashSet<Vec<Label>>`.
+    pub hash_set_bytes: usize,
+}
+
+impl CompressionReport {
+    /// Return the bytes stored by the trie, `label_bytes + louds_bytes`.
+    pub fn stored_bytes(&self) -> usize {
+        self.label_bytes + self.louds_bytes
+    }
+
+    /// Return `stored_bytes / key_bytes`; below 1.0 the trie is smaller than
+    /// its keys. `None` when there are no key bytes.
+    pub fn ratio(&self) -> Option<f64> {
+        (self.key_bytes > 0).then(|| self.stored_bytes() as f64 / self.key_bytes as f64)
+    }
+
+    /// Return the estimated bytes saved against a `HashSet<Vec<Label>>`,
+    /// negative if the trie is larger.
+    pub fn hash_set_savings(&self) -> isize {
+        self.hash_set_bytes as isize - self.stored_bytes() as isize
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// How many subtrees of a [Trie] are identical, i.e. what merging them into a
+/// DAWG would save. See [Trie::suffix_sharing].
+///
+/// Subtrees are compared by their keys alone, not their values.
+pub struct SuffixSharing {
+    /// Number of nodes, including the root.
+    pub nodes: usize,
+    /// Number of distinct subtrees; a DAWG would have this many nodes.
+    pub unique_subtrees: usize,
+    /// Number of distinct subtrees that occur more than once.
+    pub duplicated_subtrees: usize,
+}
+
+impl SuffixSharing {
+    /// Return the nodes a DAWG would save, `nodes - unique_subtrees`.
+    pub fn mergeable_nodes(&self) -> usize {
+        self.nodes - self.unique_subtrees
+    }
+}
+
+impl<Label: Ord, Value> Trie<Label, Value> {
+    /// Report the size of the keys against the size of the trie storing them.
+    ///
+    /// This walks the whole trie.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("internationalize", 0), ("internationalization", 1)]);
+    /// let report = trie.compression_report();
+    /// assert_eq!(report.keys, 2);
+    /// assert_eq!(report.key_bytes, 36);
+    /// assert_eq!(report.label_bytes, 21);
+    /// assert!(report.ratio().unwrap() < 1.0);
+    /// assert!(report.hash_set_savings() > 0);
+    /// ```
+    pub fn compression_report(&self) -> CompressionReport {
+        let mut keys: usize = 0;
+        let mut key_labels: usize = 0;
+        let mut stack = vec![(LoudsNodeNum(1), 0)];
+        while let Some((node, depth)) = stack.pop() {
+            if self.is_terminal(node) {
+                keys += 1;
+                key_labels += depth;
+            }
+            stack.extend(
+                self.children_node_nums(node)
+                    .map(|child| (child, depth + 1)),
+            );
+        }
+        let nodes = self.trie_labels.len() + 1;
+        // "10" for the super root, then one 1 per child and one 0 per node:
+        // 2 * nodes + 1 bits, rounded up to (nodes + 4) / 4 bytes.
+        let louds_bytes = (nodes + 4) / 4;
+        // hashbrown keeps one control byte per bucket and at most 7/8 of the
+        // buckets full.
+        let buckets = if keys == 0 {
+            0
+        } else {
+            (keys * 8 / 7).next_power_of_two()
+        };
+        CompressionReport {
+            keys,
+            key_bytes: key_labels * size_of::<Label>(),
+            label_bytes: self.trie_labels.len() * size_of::<Label>(),
+            louds_bytes,
+            hash_set_bytes: buckets * (size_of::<Vec<Label>>() + 1)
+                + key_labels * size_of::<Label>(),
+        }
+    }
+}
+
+type Subtree<'a, Label> = (bool, Vec<(&'a Label, usize)>);
+
+impl<Label: Ord, Value> Trie<Label, Value> {
+    /// Count the identical subtrees, without building a DAWG.
+    ///
+    /// Each node is assigned the id of its subtree, determined by whether it
+    /// is terminal and by its children's labels and ids. This walks the whole
+    /// trie once and keeps one entry per distinct subtree.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("tapping", 0), ("topping", 1), ("top", 2)]);
+    /// let sharing = trie.suffix_sharing();
+    /// assert_eq!(sharing.nodes, 14);
+    /// // "ping" is shared below "tap" and "top".
+    /// assert_eq!(sharing.mergeable_nodes(), 4);
+    /// assert_eq!(sharing.duplicated_subtrees, 4);
+    /// ```
+    pub fn suffix_sharing(&self) -> SuffixSharing {
+        let nodes = self.trie_labels.len() + 1;
+        // ids[LoudsNodeNum - 1]: id of the node's subtree.
+        let mut ids = vec![0; nodes];
+        // Whether a subtree's root is terminal, and its children's labels
+        // and ids -> its id and how often it occurs.
+        let mut classes: BTreeMap<Subtree<'_, Label>, (usize, usize)> = BTreeMap::new();
+        // Children are numbered after their parents.
+        for node in (1..=nodes as u64).rev() {
+            let node = LoudsNodeNum(node);
+            let children = self
+                .children_node_nums(node)
+                .map(|child| (self.label(child), ids[child.0 as usize - 1]))
+                .collect();
+            let next_id = classes.len();
+            let (id, count) = classes
+                .entry((self.is_terminal(node), children))
+                .or_insert((next_id, 0));
+            *count += 1;
+            ids[node.0 as usize - 1] = *id;
+        }
+        SuffixSharing {
+            nodes,
+            unique_subtrees: classes.len(),
+            duplicated_subtrees: classes.values().filter(|(_, count)| *count > 1).count(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod compression_report_tests {
+    use crate::map::{Trie, TrieBuilder};
+
+    #[test]
+    fn counts() {
+        let trie = Trie::from_iter([("a", 0), ("app", 1), ("apple", 2), ("bee", 3)]);
+        let report = trie.compression_report();
+        assert_eq!(report.keys, 4);
+        assert_eq!(report.key_bytes, 1 + 3 + 5 + 3);
+        assert_eq!(report.label_bytes, 8);
+        // 9 nodes with the root: 19 bits.
+        assert_eq!(report.louds_bytes, 3);
+        assert_eq!(report.stored_bytes(), 11);
+    }
+
+    #[test]
+    fn wide_labels() {
+        let trie: Trie<char, ()> = Trie::from_iter([(vec!['a', 'b'], ()), (vec!['a', 'c'], ())]);
+        let report = trie.compression_report();
+        assert_eq!(report.key_bytes, 16);
+        assert_eq!(report.label_bytes, 12);
+    }
+
+    #[test]
+    fn suffix_sharing() {
+        let trie = Trie::from_iter([("bats", 0), ("cats", 1), ("bat", 2), ("cat", 3)]);
+        let sharing = trie.suffix_sharing();
+        // The subtrees at "b" and "c" are the same.
+        assert_eq!(sharing.nodes, 9);
+        assert_eq!(sharing.unique_subtrees, 5);
+        assert_eq!(sharing.duplicated_subtrees, 4);
+        // Labels matter: "ts" and "gs" differ at the "a" and "o" above them.
+        let trie = Trie::from_iter([("cats", 0), ("dogs", 1)]);
+        assert_eq!(trie.suffix_sharing().mergeable_nodes(), 2);
+
+        let trie = Trie::from_iter([("cats", 0), ("dog", 1)]);
+        assert_eq!(trie.suffix_sharing().mergeable_nodes(), 1);
+        let empty: Trie<u8, ()> = TrieBuilder::new().build();
+        assert_eq!(empty.suffix_sharing().unique_subtrees, 1);
+    }
+
+    #[test]
+    fn empty() {
+        let trie: Trie<u8, ()> = TrieBuilder::new().build();
+        let report = trie.compression_report();
+        assert_eq!(report.keys, 0);
+        assert_eq!(report.ratio(), None);
+        assert_eq!(report.hash_set_bytes, 0);
+    }
+}
@@ -0,0 +1,191 @@
+use super::{Trie, TrieBuilder};
+use crate::try_collect::TryFromIterator;
+use std::time::Instant;
+
+#[derive(Debug, Clone)]
+/// A trie map whose entries each expire at a deadline, e.g. session tokens.
+///
+/// Entries are pushed as `(deadline, value)`. Reads take the time to check
+/// against, usually [Instant::now], and skip entries whose deadline is not
+/// after it, so expired entries vanish at once; [ExpiringTrie::purge_expired]
+/// drops them from memory.
+///
+/// ```rust
+/// use std::time::{Duration, Instant};
+/// use trie_rs::map::{ExpiringTrie, TrieBuilder};
+///
+/// let start = Instant::now();
+/// let mut builder = TrieBuilder::new();
+/// builder.push("sess-a1", (start + Duration::from_secs(60), "alice"));
+/// builder.push("sess-b2", (start + Duration::from_secs(3600), "bob"));
+/// let mut sessions = ExpiringTrie::from(builder.build());
+///
+/// let later = start + Duration::from_secs(120);
+/// assert_eq!(sessions.exact_match("sess-a1", start), Some(&"alice"));
+/// assert_eq!(sessions.exact_match("sess-a1", later), None);
+/// let live: Vec<(String, &&str)> = sessions.predictive_search("sess-", later).collect();
+/// assert_eq!(live, [("sess-b2".to_string(), &"bob")]);
+///
+/// assert_eq!(sessions.purge_expired(later), 1);
+/// assert_eq!(sessions.trie().iter::<String, _>().count(), 1);
+/// ```
+pub struct ExpiringTrie<Label, Value>(Trie<Label, (Instant, Value)>);
+
+impl<Label, Value> From<Trie<Label, (Instant, Value)>> for ExpiringTrie<Label, Value> {
+    fn from(trie: Trie<Label, (Instant, Value)>) -> Self {
+        Self(trie)
+    }
+}
+
+/// Return true if an entry has not expired by `now`.
+fn live(entry: &(Instant, impl Sized), now: Instant) -> bool {
+    entry.0 > now
+}
+
+impl<Label: Ord, Value> ExpiringTrie<Label, Value> {
+    /// Return `Some(&Value)` if query is an exact match that has not expired
+    /// by `now`.
+    pub fn exact_match(&self, query: impl AsRef<[Label]>, now: Instant) -> Option<&Value> {
+        self.0
+            .exact_match(query)
+            .filter(|entry| live(entry, now))
+            .map(|(_, value)| value)
+    }
+
+    /// Return true if `query` is a key that has not expired by `now`, without
+    /// allocating. See [Trie::contains_key].
+    pub fn contains_key(&self, query: impl AsRef<[Label]>, now: Instant) -> bool {
+        let entry = self
+            .0
+            .walk(query.as_ref())
+            .and_then(|node| self.0.value(node));
+        matches!(entry, Some(entry) if live(entry, now))
+    }
+
+    /// Return the deadline of `query` if it is an exact match, expired or not.
+    pub fn deadline(&self, query: impl AsRef<[Label]>) -> Option<Instant> {
+        self.0.exact_match(query).map(|(deadline, _)| *deadline)
+    }
+
+    /// Return all entries and their values that match `query` and have not
+    /// expired by `now`.
+    pub fn predictive_search<'a, C, M>(
+        &'a self,
+        query: impl AsRef<[Label]>,
+        now: Instant,
+    ) -> impl Iterator<Item = (C, &'a Value)> + 'a
+    where
+        C: TryFromIterator<Label, M> + Clone + 'a,
+        M: 'a,
+        Label: Clone,
+    {
+        self.0
+            .predictive_search(query)
+            .filter(move |(_, entry)| live(entry, now))
+            .map(|(key, (_, value))| (key, value))
+    }
+
+    /// Return all entries and their values that have not expired by `now`.
+    pub fn iter<'a, C, M>(&'a self, now: Instant) -> impl Iterator<Item = (C, &'a Value)> + 'a
+    where
+        C: TryFromIterator<Label, M> + 'a,
+        M: 'a,
+        Label: Clone,
+    {
+        self.0
+            .iter()
+            .filter(move |(_, entry)| live(entry, now))
+            .map(|(key, (_, value))| (key, value))
+    }
+
+    /// Return the common prefixes of `query` that have not expired by `now`.
+    pub fn common_prefix_search<'a, C, M>(
+        &'a self,
+        query: impl AsRef<[Label]>,
+        now: Instant,
+    ) -> impl Iterator<Item = (C, &'a Value)> + 'a
+    where
+        C: TryFromIterator<Label, M> + 'a,
+        M: 'a,
+        Label: Clone,
+    {
+        self.0
+            .common_prefix_search(query)
+            .filter(move |(_, entry)| live(entry, now))
+            .map(|(key, (_, value))| (key, value))
+    }
+
+    /// Drop the entries expired by `now` and the branches only they used, see
+    /// [Trie::rebuild]. Return the number of entries dropped.
+    pub fn purge_expired(&mut self, now: Instant) -> usize
+    where
+        Label: Clone,
+    {
+        let expired: Vec<usize> = (0..self.0.values.len())
+            .filter(|&i| matches!(self.0.values.get(i), Some(entry) if !live(entry, now)))
+            .collect();
+        if expired.is_empty() {
+            return 0;
+        }
+        for &i in &expired {
+            self.0.values.take(i);
+        }
+        let trie = std::mem::replace(&mut self.0, TrieBuilder::new().build());
+        self.0 = trie.rebuild();
+        expired.len()
+    }
+
+    /// Return the trie of `(deadline, value)` entries underneath.
+    pub fn trie(&self) -> &Trie<Label, (Instant, Value)> {
+        &self.0
+    }
+
+    /// Return the trie of `(deadline, value)` entries underneath.
+    pub fn into_trie(self) -> Trie<Label, (Instant, Value)> {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod expiring_trie_tests {
+    use crate::map::{ExpiringTrie, Trie};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn expires() {
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let trie = ExpiringTrie::from(Trie::from_iter([
+            ("a", (at(10), 0)),
+            ("ab", (at(20), 1)),
+            ("abc", (at(10), 2)),
+            ("b", (at(30), 3)),
+        ]));
+        assert_eq!(trie.exact_match("ab", at(19)), Some(&1));
+        assert_eq!(trie.exact_match("ab", at(20)), None);
+        assert_eq!(trie.deadline("ab"), Some(at(20)));
+        let prefixes: Vec<(String, &u8)> = trie.common_prefix_search("abc", at(15)).collect();
+        assert_eq!(prefixes, [("ab".to_string(), &1)]);
+        let all: Vec<(String, &u8)> = trie.iter(at(25)).collect();
+        assert_eq!(all, [("b".to_string(), &3)]);
+        assert_eq!(trie.predictive_search::<String, _>("a", at(0)).count(), 3);
+    }
+
+    #[test]
+    fn purge_expired() {
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let mut trie = ExpiringTrie::from(Trie::from_iter([
+            ("a", (at(20), 0)),
+            ("abc", (at(10), 1)),
+            ("b", (at(10), 2)),
+        ]));
+        assert_eq!(trie.purge_expired(at(5)), 0);
+        assert_eq!(trie.purge_expired(at(10)), 2);
+        assert!(!trie.trie().is_prefix("ab"));
+        assert!(!trie.trie().is_prefix("b"));
+        assert_eq!(trie.exact_match("a", at(10)), Some(&0));
+        assert_eq!(trie.purge_expired(at(20)), 1);
+        assert_eq!(trie.into_trie().iter::<String, _>().count(), 0);
+    }
+}
@@ -0,0 +1,142 @@
+use super::Trie;
+use louds_rs::LoudsNodeNum;
+
+#[cfg(feature = "mem_dbg")]
+use mem_dbg::MemDbg;
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The number of keys flagged true and false below each node of a [Trie], for
+/// asking whether any or all keys under a prefix are flagged without visiting
+/// them, e.g. feature flags keyed by dotted config paths.
+///
+/// Like [super::ScoreIndex], it is filled from one trie and must only be used
+/// with that trie.
+///
+/// ```rust
+/// use trie_rs::map::{FlagIndex, Trie};
+///
+/// let flags = Trie::from_iter([
+///     ("ui.dark_mode", true),
+///     ("ui.new_toolbar", false),
+///     ("net.http2", true),
+///     ("net.quic", true),
+/// ]);
+/// let index = FlagIndex::new(&flags, |enabled| *enabled);
+/// assert!(index.any_true_under(&flags, "ui."));
+/// assert!(!index.all_true_under(&flags, "ui."));
+/// assert!(index.all_true_under(&flags, "net."));
+/// assert_eq!(index.count_true_under(&flags, ""), 3);
+/// ```
+pub struct FlagIndex {
+    /// Keys flagged true at or below each node, indexed by `LoudsNodeNum - 1`.
+    trues: Vec<usize>,
+    /// Keys flagged false at or below each node, indexed by `LoudsNodeNum - 1`.
+    falses: Vec<usize>,
+}
+
+impl FlagIndex {
+    /// Flag every key of `trie` with `flag`.
+    pub fn new<Label: Ord, Value>(
+        trie: &Trie<Label, Value>,
+        flag: impl Fn(&Value) -> bool,
+    ) -> Self {
+        let node_count = trie.trie_labels.len() + 1;
+        let mut trues = vec![0; node_count];
+        let mut falses = vec![0; node_count];
+        for node in 2..=node_count {
+            match trie.value(LoudsNodeNum(node as u64)).map(&flag) {
+                Some(true) => trues[node - 1] = 1,
+                Some(false) => falses[node - 1] = 1,
+                None => {}
+            }
+        }
+        // Children are numbered after their parents, so one backward pass
+        // sees every subtree before its root.
+        for node in (2..=node_count as u64).rev() {
+            let parent = trie.parent(LoudsNodeNum(node)).unwrap().0 as usize - 1;
+            trues[parent] += trues[node as usize - 1];
+            falses[parent] += falses[node as usize - 1];
+        }
+        Self { trues, falses }
+    }
+
+    /// Return the number of keys starting with `prefix` that are flagged
+    /// true.
+    pub fn count_true_under<Label: Ord, Value>(
+        &self,
+        trie: &Trie<Label, Value>,
+        prefix: impl AsRef<[Label]>,
+    ) -> usize {
+        self.counts(trie, prefix.as_ref()).0
+    }
+
+    /// Return true if any key starting with `prefix` is flagged true.
+    pub fn any_true_under<Label: Ord, Value>(
+        &self,
+        trie: &Trie<Label, Value>,
+        prefix: impl AsRef<[Label]>,
+    ) -> bool {
+        self.counts(trie, prefix.as_ref()).0 > 0
+    }
+
+    /// Return true if every key starting with `prefix` is flagged true,
+    /// including when no key does, like [Iterator::all].
+    pub fn all_true_under<Label: Ord, Value>(
+        &self,
+        trie: &Trie<Label, Value>,
+        prefix: impl AsRef<[Label]>,
+    ) -> bool {
+        self.counts(trie, prefix.as_ref()).1 == 0
+    }
+
+    /// Return the keys flagged true and false at or below `prefix`.
+    fn counts<Label: Ord, Value>(
+        &self,
+        trie: &Trie<Label, Value>,
+        prefix: &[Label],
+    ) -> (usize, usize) {
+        match trie.node_num(prefix) {
+            Some(node) => {
+                let i = node.0 as usize - 1;
+                (self.trues[i], self.falses[i])
+            }
+            None => (0, 0),
+        }
+    }
+}
+
+impl<Label: Ord> Trie<Label, bool> {
+    /// Return a [FlagIndex] of the values.
+    pub fn flag_index(&self) -> FlagIndex {
+        FlagIndex::new(self, |flag| *flag)
+    }
+}
+
+#[cfg(test)]
+mod flag_index_tests {
+    use crate::map::Trie;
+
+    #[test]
+    fn counts() {
+        let trie = Trie::from_iter([
+            ("a", true),
+            ("a.b", false),
+            ("a.b.c", true),
+            ("a.d", true),
+            ("e", false),
+        ]);
+        let index = trie.flag_index();
+        assert_eq!(index.count_true_under(&trie, ""), 3);
+        assert_eq!(index.count_true_under(&trie, "a.b"), 1);
+        assert!(index.any_true_under(&trie, "a.b"));
+        assert!(!index.all_true_under(&trie, "a.b"));
+        assert!(index.all_true_under(&trie, "a.b."));
+        assert!(index.all_true_under(&trie, "a.d"));
+        assert!(!index.any_true_under(&trie, "e"));
+        assert!(!index.all_true_under(&trie, "e"));
+        assert!(!index.any_true_under(&trie, "x"));
+        assert!(index.all_true_under(&trie, "x"));
+    }
+}
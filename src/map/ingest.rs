@@ -0,0 +1,227 @@
+use super::{InvalidKey, KeyLenLimitedBuilder, TrieBuilder};
+use std::fmt;
+use std::ops::ControlFlow;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Error of [TrieBuilder::ingest] when the progress callback stops it, or a
+/// key is rejected. The entries before it stay pushed.
+pub struct IngestAborted {
+    /// Number of entries pushed before stopping.
+    pub entries: usize,
+    /// The error of the key that stopped it, or `None` if the progress
+    /// callback did.
+    pub invalid_key: Option<InvalidKey>,
+}
+
+impl fmt::Display for IngestAborted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ingestion aborted after {} entries", self.entries)?;
+        if let Some(e) = &self.invalid_key {
+            write!(f, ": {}", e)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for IngestAborted {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.invalid_key
+            .as_ref()
+            .map(|e| e as &(dyn std::error::Error + 'static))
+    }
+}
+
+impl<Label: Ord, Value> TrieBuilder<Label, Value> {
+    /// Push entries as they arrive, e.g. from the receiving end of a channel,
+    /// and call `progress` with the number pushed so far after every
+    /// `batch_size` entries and once at the end. Return the number of entries
+    /// pushed.
+    ///
+    /// If `progress` returns [ControlFlow::Break], or a key is empty, stop and
+    /// return [IngestAborted].
+    /// The entries pushed so far stay in the builder, which may be built or
+    /// fed more. A `batch_size` of 0 is taken as 1.
+    ///
+    /// ```rust
+    /// use std::ops::ControlFlow;
+    /// use std::sync::mpsc;
+    /// use trie_rs::map::TrieBuilder;
+    ///
+    /// let (sender, receiver) = mpsc::channel();
+    /// std::thread::spawn(move || {
+    ///     for i in 0..2500u32 {
+    ///         sender.send((format!("key{}", i).into_bytes(), i)).unwrap();
+    ///     }
+    /// });
+    ///
+    /// let mut builder = TrieBuilder::new();
+    /// let mut reports = Vec::new();
+    /// let ingested = builder.ingest(receiver, 1000, |pushed| {
+    ///     reports.push(pushed);
+    ///     ControlFlow::Continue(())
+    /// });
+    /// assert_eq!(ingested, Ok(2500));
+    /// assert_eq!(reports, [1000, 2000, 2500]);
+    /// assert_eq!(builder.build().exact_match("key42"), Some(&42));
+    /// ```
+    pub fn ingest<Key, F>(
+        &mut self,
+        entries: impl IntoIterator<Item = (Key, Value)>,
+        batch_size: usize,
+        progress: F,
+    ) -> Result<usize, IngestAborted>
+    where
+        Key: IntoIterator<Item = Label>,
+        F: FnMut(usize) -> ControlFlow<()>,
+    {
+        self.ingest_within(entries, batch_size, progress, None)
+    }
+
+    fn ingest_within<Key, F>(
+        &mut self,
+        entries: impl IntoIterator<Item = (Key, Value)>,
+        batch_size: usize,
+        mut progress: F,
+        limit: Option<usize>,
+    ) -> Result<usize, IngestAborted>
+    where
+        Key: IntoIterator<Item = Label>,
+        F: FnMut(usize) -> ControlFlow<()>,
+    {
+        let batch_size = batch_size.max(1);
+        let mut pushed = 0;
+        let mut batch = 0;
+        for (key, value) in entries {
+            if let Err(e) = self.try_insert_within(key, value, limit) {
+                return Err(IngestAborted {
+                    entries: pushed,
+                    invalid_key: Some(e),
+                });
+            }
+            pushed += 1;
+            batch += 1;
+            if batch == batch_size {
+                batch = 0;
+                if progress(pushed).is_break() {
+                    return Err(IngestAborted {
+                        entries: pushed,
+                        invalid_key: None,
+                    });
+                }
+            }
+        }
+        if batch > 0 && progress(pushed).is_break() {
+            return Err(IngestAborted {
+                entries: pushed,
+                invalid_key: None,
+            });
+        }
+        Ok(pushed)
+    }
+}
+
+impl<Label: Ord, Value> KeyLenLimitedBuilder<Label, Value> {
+    /// Like [TrieBuilder::ingest], also stopping at a key longer than the
+    /// limit.
+    pub fn ingest<Key, F>(
+        &mut self,
+        entries: impl IntoIterator<Item = (Key, Value)>,
+        batch_size: usize,
+        progress: F,
+    ) -> Result<usize, IngestAborted>
+    where
+        Key: IntoIterator<Item = Label>,
+        F: FnMut(usize) -> ControlFlow<()>,
+    {
+        self.builder
+            .ingest_within(entries, batch_size, progress, Some(self.limit))
+    }
+}
+
+#[cfg(test)]
+mod ingest_tests {
+    use crate::map::{IngestAborted, InvalidKey, KeyTooLong, TrieBuilder};
+    use std::ops::ControlFlow;
+
+    #[test]
+    fn aborts_with_partial_builder() {
+        let mut builder = TrieBuilder::new();
+        let entries = (0..10u8).map(|i| (vec![b'a' + i], i));
+        let aborted = builder.ingest(entries, 3, |pushed| {
+            if pushed >= 6 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+        assert_eq!(
+            aborted,
+            Err(IngestAborted {
+                entries: 6,
+                invalid_key: None
+            })
+        );
+        assert_eq!(
+            aborted.unwrap_err().to_string(),
+            "ingestion aborted after 6 entries"
+        );
+
+        // Nothing is lost, and more can be fed.
+        let mut calls = 0;
+        let rest = (6..10u8).map(|i| (vec![b'a' + i], i));
+        let ingested = builder.ingest(rest, 0, |_| {
+            calls += 1;
+            ControlFlow::Continue(())
+        });
+        assert_eq!(ingested, Ok(4));
+        assert_eq!(calls, 4);
+        let trie = builder.build();
+        assert_eq!(trie.iter::<String, _>().count(), 10);
+
+        let mut calls = 0;
+        let ingested = TrieBuilder::<u8, u8>::new().ingest(Vec::<(Vec<u8>, u8)>::new(), 5, |_| {
+            calls += 1;
+            ControlFlow::Continue(())
+        });
+        assert_eq!((ingested, calls), (Ok(0), 0));
+    }
+
+    #[test]
+    fn stops_at_long_keys() {
+        let mut builder = TrieBuilder::new().limit_key_len(3);
+        let entries = ["abc", "abcd", "ab"].map(|key| (key.bytes(), ()));
+        let aborted = builder.ingest(entries, 1, |_| ControlFlow::Continue(()));
+        let expected = IngestAborted {
+            entries: 1,
+            invalid_key: Some(InvalidKey::TooLong(KeyTooLong { limit: 3 })),
+        };
+        assert_eq!(aborted, Err(expected));
+        assert_eq!(
+            expected.to_string(),
+            "ingestion aborted after 1 entries: key is longer than the limit of 3 labels"
+        );
+        assert_eq!(builder.build().iter::<String, _>().count(), 1);
+    }
+
+    #[test]
+    fn stops_at_empty_keys() {
+        let mut builder = TrieBuilder::new();
+        let entries = ["a", "", "b"].map(|key| (key.bytes(), ()));
+        let aborted = builder.ingest(entries, 1, |_| ControlFlow::Continue(()));
+        let expected = IngestAborted {
+            entries: 1,
+            invalid_key: Some(InvalidKey::Empty),
+        };
+        assert_eq!(aborted, Err(expected));
+        assert_eq!(
+            expected.to_string(),
+            "ingestion aborted after 1 entries: key is empty"
+        );
+
+        // The builder is intact and takes the rest.
+        let ingested = builder.ingest([("b".bytes(), ())], 1, |_| ControlFlow::Continue(()));
+        assert_eq!(ingested, Ok(1));
+        let keys: Vec<String> = builder.build().iter().map(|(k, _)| k).collect();
+        assert_eq!(keys, ["a", "b"]);
+    }
+}
@@ -0,0 +1,345 @@
+use super::{Trie, TrieBuilder};
+use crate::iter::{PostfixIter, PrefixIter, SearchIter};
+use crate::try_collect::TryFromIterator;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Error of a key or query longer than a key length limit. See
+/// [TrieBuilder::limit_key_len] and [Trie::with_key_len_limit].
+pub struct KeyTooLong {
+    /// The maximum number of labels allowed.
+    pub limit: usize,
+}
+
+impl fmt::Display for KeyTooLong {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "key is longer than the limit of {} labels", self.limit)
+    }
+}
+
+impl std::error::Error for KeyTooLong {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Error of a key a checked push rejects. See [TrieBuilder::try_push] and
+/// [KeyLenLimitedBuilder::try_push].
+pub enum InvalidKey {
+    /// The key has no labels. Only the root could hold its value, and it
+    /// holds none.
+    Empty,
+    /// The key is longer than the limit.
+    TooLong(KeyTooLong),
+}
+
+impl fmt::Display for InvalidKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvalidKey::Empty => write!(f, "key is empty"),
+            InvalidKey::TooLong(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for InvalidKey {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            InvalidKey::Empty => None,
+            InvalidKey::TooLong(e) => Some(e),
+        }
+    }
+}
+
+impl From<KeyTooLong> for InvalidKey {
+    fn from(e: KeyTooLong) -> Self {
+        InvalidKey::TooLong(e)
+    }
+}
+
+#[derive(Debug, Clone)]
+/// A [TrieBuilder] that only takes keys through checked pushes, which reject
+/// keys longer than a limit. See [TrieBuilder::limit_key_len].
+pub struct KeyLenLimitedBuilder<Label, Value> {
+    pub(crate) builder: TrieBuilder<Label, Value>,
+    pub(crate) limit: usize,
+}
+
+impl<Label: Ord, Value> TrieBuilder<Label, Value> {
+    /// Add a cloneable entry and value, or return [InvalidKey::Empty] if the
+    /// entry is empty.
+    pub fn try_push<Arr: AsRef<[Label]>>(
+        &mut self,
+        entry: Arr,
+        value: Value,
+    ) -> Result<(), InvalidKey>
+    where
+        Label: Clone,
+    {
+        self.try_insert_within(entry.as_ref().iter().cloned(), value, None)
+    }
+
+    /// Add an entry and value, or return [InvalidKey::Empty] if the entry is
+    /// empty.
+    pub fn try_insert<Arr: IntoIterator<Item = Label>>(
+        &mut self,
+        entry: Arr,
+        value: Value,
+    ) -> Result<(), InvalidKey> {
+        self.try_insert_within(entry, value, None)
+    }
+
+    /// Push `entry` unless it is empty or longer than `limit`, reading no more
+    /// than one label past the limit.
+    pub(crate) fn try_insert_within<Arr: IntoIterator<Item = Label>>(
+        &mut self,
+        entry: Arr,
+        value: Value,
+        limit: Option<usize>,
+    ) -> Result<(), InvalidKey> {
+        let mut entry = entry.into_iter().peekable();
+        if entry.peek().is_none() {
+            return Err(InvalidKey::Empty);
+        }
+        match limit {
+            None => self.naive_trie.push(entry, value),
+            Some(limit) => {
+                let entry: Vec<Label> = entry.take(limit.saturating_add(1)).collect();
+                if entry.len() > limit {
+                    return Err(KeyTooLong { limit }.into());
+                }
+                self.naive_trie.push(entry.into_iter(), value);
+            }
+        }
+        Ok(())
+    }
+
+    /// Reject keys longer than `limit` labels from now on, e.g. multi-megabyte
+    /// "words" in untrusted input. The returned builder only has checked
+    /// pushes, which return [InvalidKey] for such keys. Keys already pushed
+    /// are not checked.
+    ///
+    /// ```rust
+    /// use trie_rs::map::{InvalidKey, KeyTooLong, TrieBuilder};
+    ///
+    /// let mut builder = TrieBuilder::new().limit_key_len(5);
+    /// builder.try_push("apple", 0).unwrap();
+    /// assert_eq!(
+    ///     builder.try_push("application", 1),
+    ///     Err(InvalidKey::TooLong(KeyTooLong { limit: 5 }))
+    /// );
+    /// assert_eq!(builder.try_push("", 2), Err(InvalidKey::Empty));
+    /// let trie = builder.build();
+    /// assert_eq!(trie.iter::<String, _>().count(), 1);
+    /// ```
+    pub fn limit_key_len(self, limit: usize) -> KeyLenLimitedBuilder<Label, Value> {
+        KeyLenLimitedBuilder {
+            builder: self,
+            limit,
+        }
+    }
+}
+
+impl<Label: Ord, Value> KeyLenLimitedBuilder<Label, Value> {
+    /// Return the maximum key length.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Add a cloneable entry and value, or return an error if the entry is
+    /// empty or longer than the limit.
+    pub fn try_push<Arr: AsRef<[Label]>>(
+        &mut self,
+        entry: Arr,
+        value: Value,
+    ) -> Result<(), InvalidKey>
+    where
+        Label: Clone,
+    {
+        let entry = entry.as_ref();
+        if entry.len() > self.limit {
+            return Err(KeyTooLong { limit: self.limit }.into());
+        }
+        self.builder.try_push(entry, value)
+    }
+
+    /// Add an entry and value, or return an error if the entry is empty or
+    /// longer than the limit. No more than one label past the limit is read
+    /// from `entry`.
+    pub fn try_insert<Arr: IntoIterator<Item = Label>>(
+        &mut self,
+        entry: Arr,
+        value: Value,
+    ) -> Result<(), InvalidKey> {
+        self.builder
+            .try_insert_within(entry, value, Some(self.limit))
+    }
+
+    /// Return the builder without the limit.
+    pub fn into_inner(self) -> TrieBuilder<Label, Value> {
+        self.builder
+    }
+
+    /// Build a [Trie].
+    pub fn build(self) -> Trie<Label, Value> {
+        self.builder.build()
+    }
+}
+
+#[derive(Debug)]
+/// A [Trie] whose searches reject queries longer than a limit before
+/// searching. See [Trie::with_key_len_limit].
+pub struct KeyLenLimited<'a, Label, Value> {
+    trie: &'a Trie<Label, Value>,
+    limit: usize,
+}
+
+impl<Label, Value> Clone for KeyLenLimited<'_, Label, Value> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Label, Value> Copy for KeyLenLimited<'_, Label, Value> {}
+
+impl<Label: Ord + Clone, Value> Trie<Label, Value> {
+    /// Return a view whose searches return [KeyTooLong] for queries longer
+    /// than `limit` labels, e.g. to bound the work and buffers spent on
+    /// untrusted queries.
+    ///
+    /// ```rust
+    /// use trie_rs::map::{KeyTooLong, Trie};
+    ///
+    /// let trie = Trie::from_iter([("app", 0), ("apple", 1)]);
+    /// let limited = trie.with_key_len_limit(8);
+    /// assert_eq!(limited.exact_match("apple"), Ok(Some(&1)));
+    /// let results: Vec<(String, &u8)> = limited.predictive_search("app").unwrap().collect();
+    /// assert_eq!(results.len(), 2);
+    /// assert_eq!(limited.exact_match("a".repeat(1 << 20)), Err(KeyTooLong { limit: 8 }));
+    /// ```
+    pub fn with_key_len_limit(&self, limit: usize) -> KeyLenLimited<'_, Label, Value> {
+        KeyLenLimited { trie: self, limit }
+    }
+}
+
+impl<'a, Label: Ord + Clone, Value> KeyLenLimited<'a, Label, Value> {
+    /// Return the maximum query length.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Return the limited trie.
+    pub fn trie(&self) -> &'a Trie<Label, Value> {
+        self.trie
+    }
+
+    fn check(&self, query: &[Label]) -> Result<(), KeyTooLong> {
+        if query.len() > self.limit {
+            Err(KeyTooLong { limit: self.limit })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Like [Trie::exact_match], for a query within the limit.
+    pub fn exact_match(&self, query: impl AsRef<[Label]>) -> Result<Option<&'a Value>, KeyTooLong> {
+        let query = query.as_ref();
+        self.check(query)?;
+        Ok(self.trie.exact_match(query))
+    }
+
+    /// Like [Trie::is_prefix], for a query within the limit.
+    pub fn is_prefix(&self, query: impl AsRef<[Label]>) -> Result<bool, KeyTooLong> {
+        let query = query.as_ref();
+        self.check(query)?;
+        Ok(self.trie.is_prefix(query))
+    }
+
+    /// Like [Trie::predictive_search], for a query within the limit.
+    pub fn predictive_search<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> Result<SearchIter<'a, Label, Value, C, M>, KeyTooLong>
+    where
+        C: TryFromIterator<Label, M> + Clone,
+    {
+        let query = query.as_ref();
+        self.check(query)?;
+        Ok(self.trie.predictive_search(query))
+    }
+
+    /// Like [Trie::postfix_search], for a query within the limit.
+    pub fn postfix_search<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> Result<PostfixIter<'a, Label, Value, C, M>, KeyTooLong>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        let query = query.as_ref();
+        self.check(query)?;
+        Ok(self.trie.postfix_search(query))
+    }
+
+    /// Like [Trie::common_prefix_search], for a query within the limit.
+    pub fn common_prefix_search<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> Result<PrefixIter<'a, Label, Value, C, M>, KeyTooLong>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        let query = query.as_ref();
+        self.check(query)?;
+        Ok(self.trie.common_prefix_search(query))
+    }
+}
+
+#[cfg(test)]
+mod key_len_limit_tests {
+    use crate::map::{InvalidKey, KeyTooLong, TrieBuilder};
+
+    #[test]
+    fn limits_keys_and_queries() {
+        let mut builder = TrieBuilder::new();
+        builder.push("unchecked", 0);
+        let mut builder = builder.limit_key_len(3);
+        assert_eq!(builder.limit(), 3);
+        builder
+            .try_insert("abc".chars().map(|c| c as u8), 1)
+            .unwrap();
+        let too_long = InvalidKey::TooLong(KeyTooLong { limit: 3 });
+        let endless = std::iter::repeat(b'a');
+        assert_eq!(builder.try_insert(endless, 2), Err(too_long));
+        assert_eq!(builder.try_push("abcd", 3), Err(too_long));
+        let trie = builder.build();
+        assert_eq!(trie.iter::<String, _>().count(), 2);
+
+        let limited = trie.with_key_len_limit(3);
+        assert_eq!(limited.limit(), 3);
+        assert_eq!(limited.is_prefix("ab"), Ok(true));
+        assert!(limited.exact_match("unchecked").is_err());
+        assert!(limited.postfix_search::<String, _>("abcd").is_err());
+        let prefixes: Vec<(String, &u8)> = limited.common_prefix_search("abc").unwrap().collect();
+        assert_eq!(prefixes, [("abc".to_string(), &1)]);
+        assert_eq!(
+            too_long.to_string(),
+            "key is longer than the limit of 3 labels"
+        );
+    }
+
+    #[test]
+    fn rejects_empty_keys() {
+        let mut builder = TrieBuilder::<u8, u8>::new();
+        assert_eq!(builder.try_push("", 0), Err(InvalidKey::Empty));
+        assert_eq!(builder.try_insert(None, 1), Err(InvalidKey::Empty));
+        builder.try_push("a", 2).unwrap();
+        let mut builder = builder.limit_key_len(0);
+        assert_eq!(builder.try_push("", 3), Err(InvalidKey::Empty));
+        assert_eq!(
+            builder.try_insert(*b"b", 4),
+            Err(InvalidKey::TooLong(KeyTooLong { limit: 0 }))
+        );
+        let trie = builder.into_inner().build();
+        let keys: Vec<(String, &u8)> = trie.iter().collect();
+        assert_eq!(keys, [("a".to_string(), &2)]);
+        assert_eq!(InvalidKey::Empty.to_string(), "key is empty");
+    }
+}
@@ -0,0 +1,142 @@
+use super::Trie;
+use louds_rs::LoudsNodeNum;
+
+#[derive(Debug, Clone)]
+/// Reusable storage for the keys found by a search, e.g. one search per
+/// keystroke. See [Trie::predictive_search_in].
+///
+/// All the keys of one search are stored back to back in one buffer, and a
+/// new search reuses the buffers of the last, so a warm arena searches
+/// without allocating unless a search finds more than any before it.
+pub struct LabelArena<'a, Label, Value> {
+    labels: Vec<Label>,
+    /// The end of each key in `labels`, and its value.
+    entries: Vec<(usize, &'a Value)>,
+    stack: Vec<(usize, LoudsNodeNum)>,
+    path: Vec<Label>,
+}
+
+impl<Label, Value> Default for LabelArena<'_, Label, Value> {
+    fn default() -> Self {
+        Self {
+            labels: Vec::new(),
+            entries: Vec::new(),
+            stack: Vec::new(),
+            path: Vec::new(),
+        }
+    }
+}
+
+impl<'a, Label, Value> LabelArena<'a, Label, Value> {
+    /// Return an empty arena.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drop the keys of the last search, keeping the memory.
+    pub fn clear(&mut self) {
+        self.labels.clear();
+        self.entries.clear();
+        self.stack.clear();
+        self.path.clear();
+    }
+
+    /// Return the number of keys found.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Return true if nothing was found.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Return the `i`th key found and its value.
+    pub fn get(&self, i: usize) -> Option<(&[Label], &'a Value)> {
+        let (end, value) = *self.entries.get(i)?;
+        let start = match i {
+            0 => 0,
+            _ => self.entries[i - 1].0,
+        };
+        Some((&self.labels[start..end], value))
+    }
+
+    /// Return the keys found and their values, in lexicographic order.
+    pub fn iter(&self) -> impl Iterator<Item = (&[Label], &'a Value)> + '_ {
+        (0..self.len()).map(move |i| self.get(i).unwrap())
+    }
+}
+
+impl<Label: Ord + Clone, Value> Trie<Label, Value> {
+    /// Like [Trie::predictive_search], but store the keys in `arena` instead
+    /// of collecting each into its own allocation. The arena's previous keys
+    /// are dropped first.
+    ///
+    /// ```rust
+    /// use trie_rs::map::{LabelArena, Trie};
+    ///
+    /// let trie = Trie::from_iter([("app", 0), ("apple", 1), ("banana", 2)]);
+    /// let mut arena = LabelArena::new();
+    /// for query in ["a", "ap", "app", "appl"] {
+    ///     trie.predictive_search_in(query, &mut arena);
+    /// }
+    /// let results: Vec<(&[u8], &u8)> = arena.iter().collect();
+    /// assert_eq!(results, [(&b"apple"[..], &1)]);
+    /// ```
+    pub fn predictive_search_in<'a>(
+        &'a self,
+        query: impl AsRef<[Label]>,
+        arena: &mut LabelArena<'a, Label, Value>,
+    ) {
+        arena.clear();
+        let query = query.as_ref();
+        let Some(node) = self.walk(query) else {
+            return;
+        };
+        arena.path.extend_from_slice(query);
+        arena.stack.push((query.len(), node));
+        while let Some(node) = self.dfs_step(&mut arena.stack, &mut arena.path, query.len()) {
+            if let Some(value) = self.value(node) {
+                arena.labels.extend_from_slice(&arena.path);
+                arena.entries.push((arena.labels.len(), value));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod label_arena_tests {
+    use crate::map::{LabelArena, Trie};
+
+    #[test]
+    fn matches_predictive_search() {
+        let trie = Trie::from_iter([
+            ("a", 0),
+            ("app", 1),
+            ("apple", 2),
+            ("application", 3),
+            ("better", 4),
+            ("アップル🍎", 5),
+        ]);
+        let mut arena = LabelArena::new();
+        for query in [
+            "",
+            "a",
+            "app",
+            "appl",
+            "b",
+            "better",
+            "bet",
+            "x",
+            "アップル🍎🍏",
+        ] {
+            trie.predictive_search_in(query, &mut arena);
+            let expected: Vec<(Vec<u8>, &u8)> = trie.predictive_search(query).collect();
+            let found: Vec<(Vec<u8>, &u8)> = arena.iter().map(|(k, v)| (k.to_vec(), v)).collect();
+            assert_eq!(found, expected, "{}", query);
+            assert_eq!(arena.len(), expected.len());
+        }
+        assert!(arena.is_empty());
+        assert_eq!(arena.get(0), None);
+    }
+}
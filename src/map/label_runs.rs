@@ -0,0 +1,239 @@
+//! Contiguous label runs for single-child chains.
+//!
+//! LOUDS numbers nodes in breadth-first order, so the labels of a long
+//! single-child chain (e.g. the tail "cation" of "application") end up
+//! scattered across `trie_labels`, and descending them costs one LOUDS child
+//! lookup per label. A [LabelRuns] keeps a contiguous copy of each such chain
+//! so a query can be compared against several labels at once and jump
+//! straight to the node at the end of the chain.
+use super::Trie;
+use louds_rs::LoudsNodeNum;
+
+#[cfg(feature = "mem_dbg")]
+use mem_dbg::MemDbg;
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct LabelRuns<Label> {
+    /// Sorted by `head`.
+    runs: Vec<LabelRun>,
+    labels: Vec<Label>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct LabelRun {
+    /// The node whose only child starts the chain.
+    head: LoudsNodeNum,
+    /// The last node of the chain. It is a terminal or does not have exactly
+    /// one child; every node before it is a non-terminal with one child.
+    last: LoudsNodeNum,
+    /// Range into `LabelRuns::labels`.
+    start: usize,
+    len: usize,
+}
+
+/// Result of descending the trie along a query.
+pub(crate) enum Descent {
+    /// The whole query was consumed and ended on this node.
+    Node(LoudsNodeNum),
+    /// The whole query was consumed and ended on a non-terminal node with
+    /// children, inside a label run or on a path of a tiny trie.
+    InRun,
+    /// The query is not a path in the trie.
+    Miss,
+}
+
+impl<Label> LabelRuns<Label> {
+    /// Return the bytes allocated for the runs and their labels.
+    pub(crate) fn heap_bytes(&self) -> usize {
+        self.runs.capacity() * std::mem::size_of::<LabelRun>()
+            + self.labels.capacity() * std::mem::size_of::<Label>()
+    }
+
+    fn get(&self, head: LoudsNodeNum) -> Option<&LabelRun> {
+        self.runs
+            .binary_search_by_key(&head.0, |run| run.head.0)
+            .ok()
+            .map(|i| &self.runs[i])
+    }
+}
+
+impl<Label: Ord + Clone, Value> Trie<Label, Value> {
+    /// Store the labels of single-child chains contiguously.
+    ///
+    /// Afterwards [exact_match](Trie::exact_match) and
+    /// [is_prefix](Trie::is_prefix) compare a query against a whole chain at
+    /// once instead of stepping node by node, which pays off for long keys
+    /// with long unique tails. The labels of those chains are stored twice.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let mut trie = Trie::from_iter([("application", 0), ("apple", 1)]);
+    /// trie.build_label_runs();
+    /// assert_eq!(trie.exact_match("application"), Some(&0));
+    /// assert!(trie.is_prefix("applic"));
+    /// ```
+    pub fn build_label_runs(&mut self) {
+        let mut runs = Vec::new();
+        let mut labels = Vec::new();
+        let mut stack = vec![LoudsNodeNum(1)];
+        while let Some(node) = stack.pop() {
+            let mut children = self.children_node_nums(node);
+            match (children.next(), children.next()) {
+                (Some(first), None) => {
+                    let start = labels.len();
+                    let mut last = first;
+                    labels.push(self.label(last).clone());
+                    while !self.is_terminal(last) {
+                        let mut children = self.children_node_nums(last);
+                        match (children.next(), children.next()) {
+                            (Some(child), None) => {
+                                last = child;
+                                labels.push(self.label(last).clone());
+                            }
+                            _ => break,
+                        }
+                    }
+                    if labels.len() - start > 1 {
+                        runs.push(LabelRun {
+                            head: node,
+                            last,
+                            start,
+                            len: labels.len() - start,
+                        });
+                    } else {
+                        labels.truncate(start);
+                    }
+                    stack.push(last);
+                }
+                (Some(first), Some(second)) => {
+                    stack.push(first);
+                    stack.push(second);
+                    stack.extend(children);
+                }
+                (None, _) => {}
+            }
+        }
+        runs.sort_by_key(|run| run.head.0);
+        runs.shrink_to_fit();
+        labels.shrink_to_fit();
+        self.runs = Some(LabelRuns { runs, labels });
+    }
+}
+
+impl<Label: Ord, Value> Trie<Label, Value> {
+    /// Descend from the root along `query`, using label runs if present.
+    pub(crate) fn descend(&self, query: &[Label]) -> Descent {
+        if let Some(tiny) = &self.tiny {
+            if !query.is_empty() {
+                return self.descend_tiny(tiny, query);
+            }
+        }
+        let mut cur_node_num = LoudsNodeNum(1);
+        let mut i = 0;
+        while i < query.len() {
+            if let Some((run, labels)) = self.runs.as_ref().and_then(|runs| {
+                runs.get(cur_node_num)
+                    .map(|run| (run, &runs.labels[run.start..run.start + run.len]))
+            }) {
+                let rest = &query[i..];
+                if rest.len() < labels.len() {
+                    return if labels[..rest.len()] == *rest {
+                        Descent::InRun
+                    } else {
+                        Descent::Miss
+                    };
+                }
+                if labels[..] != rest[..labels.len()] {
+                    return Descent::Miss;
+                }
+                cur_node_num = run.last;
+                i += labels.len();
+                continue;
+            }
+            match self.child(cur_node_num, &query[i]) {
+                Some(child) => cur_node_num = child,
+                None => return Descent::Miss,
+            }
+            i += 1;
+        }
+        Descent::Node(cur_node_num)
+    }
+}
+
+#[cfg(test)]
+mod label_runs_tests {
+    use crate::map::Trie;
+
+    fn build_trie() -> Trie<u8, u8> {
+        let mut trie = Trie::from_iter([
+            ("a", 0),
+            ("app", 1),
+            ("apple", 2),
+            ("better", 3),
+            ("application", 4),
+            ("アップル🍎", 5),
+        ]);
+        trie.build_label_runs();
+        trie
+    }
+
+    #[test]
+    fn runs_are_maximal_chains() {
+        let trie = build_trie();
+        let runs = trie.runs.as_ref().unwrap();
+        let chains: Vec<&[u8]> = runs
+            .runs
+            .iter()
+            .map(|run| &runs.labels[run.start..run.start + run.len])
+            .collect();
+        assert!(chains.contains(&&b"etter"[..]));
+        assert!(chains.contains(&&b"cation"[..]));
+        assert!(chains.contains(&&b"pp"[..]));
+    }
+
+    #[test]
+    fn results_match_without_runs() {
+        let plain = Trie::<u8, u8>::from_iter([
+            ("a", 0),
+            ("app", 1),
+            ("apple", 2),
+            ("better", 3),
+            ("application", 4),
+            ("アップル🍎", 5),
+        ]);
+        let trie = build_trie();
+        for query in [
+            "",
+            "a",
+            "ap",
+            "app",
+            "appl",
+            "apple",
+            "appler",
+            "applic",
+            "application",
+            "applications",
+            "b",
+            "bet",
+            "better",
+            "betters",
+            "bx",
+            "アップル",
+            "アップル🍎",
+            "z",
+        ] {
+            assert_eq!(
+                trie.exact_match(query),
+                plain.exact_match(query),
+                "{}",
+                query
+            );
+            assert_eq!(trie.is_prefix(query), plain.is_prefix(query), "{}", query);
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use super::Trie;
+use crate::iter::{merge_sorted, MergeSorted};
+use crate::try_collect::{Collect, TryCollect, TryFromIterator};
+use std::marker::PhantomData;
+
+type Entries<'a, Label, Value> = Box<dyn Iterator<Item = (Vec<Label>, &'a Value)> + 'a>;
+
+#[derive(Debug, Clone)]
+/// Several tries read as one map, e.g. a user dictionary layered over a
+/// system dictionary.
+///
+/// Tries are given from highest to lowest priority. A key in more than one
+/// trie takes its value from the first of them, and is returned once.
+///
+/// ```rust
+/// use trie_rs::map::{MultiTrie, Trie};
+///
+/// let user = Trie::from_iter([("rust", "my language"), ("rustup", "installer")]);
+/// let system = Trie::from_iter([("rust", "corrosion"), ("rusty", "rust-covered")]);
+/// let dict = MultiTrie::new([&user, &system]);
+///
+/// assert_eq!(dict.exact_match("rust"), Some(&"my language"));
+/// assert_eq!(dict.exact_match("rusty"), Some(&"rust-covered"));
+/// let results: Vec<(String, &&str)> = dict.predictive_search("rust").collect();
+/// assert_eq!(
+///     results,
+///     [
+///         ("rust".to_string(), &"my language"),
+///         ("rustup".to_string(), &"installer"),
+///         ("rusty".to_string(), &"rust-covered"),
+///     ]
+/// );
+/// ```
+pub struct MultiTrie<'a, Label, Value> {
+    tries: Vec<&'a Trie<Label, Value>>,
+}
+
+impl<'a, Label: Ord + Clone, Value> MultiTrie<'a, Label, Value> {
+    /// Return a view of `tries`, from highest to lowest priority.
+    pub fn new(tries: impl IntoIterator<Item = &'a Trie<Label, Value>>) -> Self {
+        Self {
+            tries: tries.into_iter().collect(),
+        }
+    }
+
+    /// Return the tries, from highest to lowest priority.
+    pub fn tries(&self) -> &[&'a Trie<Label, Value>] {
+        &self.tries
+    }
+
+    /// Return the value of the first trie where `query` is a key.
+    pub fn exact_match(&self, query: impl AsRef<[Label]>) -> Option<&'a Value> {
+        let query = query.as_ref();
+        self.tries.iter().find_map(|trie| trie.exact_match(query))
+    }
+
+    /// Return true if `query` is a key in any trie, without allocating. See
+    /// [Trie::contains_key].
+    pub fn contains_key(&self, query: impl AsRef<[Label]>) -> bool {
+        let query = query.as_ref();
+        self.tries.iter().any(|trie| trie.contains_key(query))
+    }
+
+    /// Return true if `query` is a prefix in any trie.
+    ///
+    /// Note: A prefix may be an exact match or not, and an exact match may be
+    /// a prefix or not.
+    pub fn is_prefix(&self, query: impl AsRef<[Label]>) -> bool {
+        let query = query.as_ref();
+        self.tries.iter().any(|trie| trie.is_prefix(query))
+    }
+
+    /// Return all entries and their values that match `query`, in
+    /// lexicographic order.
+    pub fn predictive_search<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> MultiIter<'a, Label, Value, C, M> {
+        let query = query.as_ref();
+        MultiIter::new(self.tries.iter().map(|trie| {
+            Box::new(trie.predictive_search::<Vec<Label>, Collect>(query))
+                as Entries<'a, Label, Value>
+        }))
+    }
+
+    /// Return the postfixes and values of all entries that match `query`, in
+    /// lexicographic order.
+    pub fn postfix_search<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> MultiIter<'a, Label, Value, C, M> {
+        let query = query.as_ref();
+        MultiIter::new(self.tries.iter().map(|trie| {
+            Box::new(trie.postfix_search::<Vec<Label>, Collect>(query)) as Entries<'a, Label, Value>
+        }))
+    }
+
+    /// Return all entries and their values, in lexicographic order.
+    pub fn iter<C, M>(&self) -> MultiIter<'a, Label, Value, C, M> {
+        self.postfix_search([])
+    }
+
+    /// Return the common prefixes of `query`, shortest first.
+    pub fn common_prefix_search<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> MultiIter<'a, Label, Value, C, M> {
+        let query = query.as_ref();
+        MultiIter::new(self.tries.iter().map(|trie| {
+            Box::new(trie.common_prefix_search::<Vec<Label>, Collect>(query))
+                as Entries<'a, Label, Value>
+        }))
+    }
+
+    /// Return the longest of the tries' [Trie::longest_prefix]es of `query`.
+    pub fn longest_prefix<C, M>(&self, query: impl AsRef<[Label]>) -> Option<C>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        let query = query.as_ref();
+        self.tries
+            .iter()
+            .filter_map(|trie| trie.longest_prefix::<Vec<Label>, Collect>(query))
+            .max_by_key(Vec::len)
+            .map(|prefix| prefix.into_iter().try_collect().expect("Could not collect"))
+    }
+}
+
+/// Iterates through the entries of a [MultiTrie], merged from each trie's
+/// entries in order, keeping the first trie's value for a shared key.
+pub struct MultiIter<'a, Label, Value, C, M> {
+    merged: MergeSorted<Entries<'a, Label, Value>>,
+    col: PhantomData<(C, M)>,
+}
+
+impl<'a, Label: Ord, Value, C, M> MultiIter<'a, Label, Value, C, M> {
+    fn new(iters: impl Iterator<Item = Entries<'a, Label, Value>>) -> Self {
+        Self {
+            merged: merge_sorted(iters).first_wins(),
+            col: PhantomData,
+        }
+    }
+}
+
+impl<'a, Label: Ord, Value, C, M> Iterator for MultiIter<'a, Label, Value, C, M>
+where
+    C: TryFromIterator<Label, M>,
+{
+    type Item = (C, &'a Value);
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, value) = self.merged.next()?;
+        Some((
+            key.into_iter().try_collect().expect("Could not collect"),
+            value,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod multi_trie_tests {
+    use crate::map::{MultiTrie, Trie};
+
+    fn build_tries() -> [Trie<u8, u8>; 3] {
+        [
+            Trie::from_iter([("a", 0), ("abc", 0)]),
+            Trie::from_iter([("a", 1), ("ab", 1), ("b", 1)]),
+            Trie::from_iter([("ab", 2), ("abcd", 2), ("c", 2)]),
+        ]
+    }
+
+    #[test]
+    fn merges_by_priority() {
+        let tries = build_tries();
+        let dict = MultiTrie::new(&tries);
+        let all: Vec<(String, &u8)> = dict.iter().collect();
+        assert_eq!(
+            all,
+            [
+                ("a".to_string(), &0),
+                ("ab".to_string(), &1),
+                ("abc".to_string(), &0),
+                ("abcd".to_string(), &2),
+                ("b".to_string(), &1),
+                ("c".to_string(), &2),
+            ]
+        );
+        let postfixes: Vec<(String, &u8)> = dict.postfix_search("ab").collect();
+        assert_eq!(postfixes, [("c".to_string(), &0), ("cd".to_string(), &2)]);
+        let prefixes: Vec<(String, &u8)> = dict.common_prefix_search("abcde").collect();
+        assert_eq!(prefixes.len(), 4);
+        assert_eq!(prefixes[1], ("ab".to_string(), &1));
+    }
+
+    #[test]
+    fn lookups() {
+        let tries = build_tries();
+        let dict = MultiTrie::new(&tries);
+        assert_eq!(dict.exact_match("ab"), Some(&1));
+        assert_eq!(dict.exact_match("abcd"), Some(&2));
+        assert_eq!(dict.exact_match("abd"), None);
+        assert!(dict.is_prefix("abc"));
+        assert!(!dict.is_prefix("d"));
+        assert_eq!(
+            dict.longest_prefix::<String, _>("abcd"),
+            Some("abcd".to_string())
+        );
+        assert_eq!(dict.tries().len(), 3);
+
+        let empty = MultiTrie::<u8, u8>::new([]);
+        assert_eq!(empty.iter::<String, _>().count(), 0);
+        assert_eq!(empty.exact_match("a"), None);
+    }
+}
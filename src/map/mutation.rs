@@ -0,0 +1,135 @@
+use super::{Trie, TrieBuilder};
+use louds_rs::LoudsNodeNum;
+use std::mem::size_of;
+
+impl<Label: Ord + Clone, Value> Trie<Label, Value> {
+    /// Lay the trie out anew without branches left without a key, see
+    /// [Trie::rebuild], and drop the spare capacity of its arrays, e.g. as
+    /// maintenance in a long-running service. Return the bytes reclaimed.
+    /// Insertion order and label runs are kept.
+    ///
+    /// Bytes are counted as for [super::BuildReport::bytes], plus the
+    /// insertion order and label runs.
+    ///
+    /// ```rust
+    /// use trie_rs::map::{Trie, UpdatableTrie};
+    ///
+    /// let trie = Trie::from_iter((0..100).map(|i| (format!("key{}", i), i)));
+    /// let mut keys = UpdatableTrie::from(trie);
+    /// for i in 10..100 {
+    ///     keys.remove(format!("key{}", i));
+    /// }
+    /// let mut trie = keys.into_trie();
+    /// assert!(trie.shrink() > 0);
+    /// assert_eq!(trie.shrink(), 0);
+    /// assert_eq!(trie.exact_match("key7"), Some(&7));
+    /// ```
+    pub fn shrink(&mut self) -> usize {
+        let before = self.heap_bytes();
+        let had_runs = self.runs.is_some();
+        *self = self.take().rebuild();
+        if had_runs {
+            self.build_label_runs();
+        }
+        self.shrink_to_fit();
+        before.saturating_sub(self.heap_bytes())
+    }
+
+    /// Drop the spare capacity of the trie's arrays, without laying it out
+    /// anew.
+    pub(crate) fn shrink_to_fit(&mut self) {
+        self.trie_labels.shrink_to_fit();
+        self.values.shrink_to_fit();
+        if let Some(order) = &mut self.insertion_order {
+            order.shrink_to_fit();
+        }
+        self.key_lens.shrink_to_fit();
+    }
+
+    /// Return the bytes allocated for the trie's arrays.
+    pub(crate) fn heap_bytes(&self) -> usize {
+        let nodes = self.trie_labels.len() + 1;
+        // 2 * nodes + 1 bits, as in Trie::compression_report.
+        let louds_bytes = (nodes + 4) / 4;
+        self.trie_labels.capacity() * size_of::<Label>()
+            + louds_bytes
+            + self.values.heap_bytes()
+            + self.runs.as_ref().map_or(0, |runs| runs.heap_bytes())
+            + self
+                .insertion_order
+                .as_ref()
+                .map_or(0, |order| order.capacity() * size_of::<LoudsNodeNum>())
+            + self.key_lens.capacity() * size_of::<(usize, usize)>()
+    }
+
+    /// Move the trie out, leaving an empty one.
+    fn take(&mut self) -> Self {
+        std::mem::replace(self, TrieBuilder::new().build())
+    }
+}
+
+#[cfg(test)]
+mod mutation_tests {
+    use crate::map::{Trie, TrieBuilder};
+
+    /// Drop the value of `key`, leaving its nodes in place, as
+    /// [crate::map::UpdatableTrie::compact] does for removals.
+    fn take_value(trie: &mut Trie<u8, u32>, key: &str) -> Option<u32> {
+        let node = trie.node_num(key.as_bytes())?;
+        trie.values.take(node.0 as usize - 2)
+    }
+
+    #[test]
+    fn shrink() {
+        let mut builder = TrieBuilder::new();
+        builder.keep_insertion_order();
+        for i in 0..200u32 {
+            builder.push(format!("{}-{}", i % 7, i), i);
+        }
+        let mut trie = builder.build();
+        trie.build_label_runs();
+        for i in (0..200).filter(|i| i % 5 != 0) {
+            take_value(&mut trie, &format!("{}-{}", i % 7, i));
+        }
+        assert!(trie.shrink() > 0);
+        assert_eq!(trie.shrink(), 0);
+        assert!(trie.runs.is_some());
+        let keys: Vec<(String, &u32)> = trie.iter_insertion_order().unwrap().collect();
+        let wanted: Vec<(String, u32)> = (0..200)
+            .filter(|i| i % 5 == 0)
+            .map(|i| (format!("{}-{}", i % 7, i), i))
+            .collect();
+        assert_eq!(keys.len(), wanted.len());
+        assert!(keys
+            .iter()
+            .zip(&wanted)
+            .all(|(a, b)| a.0 == b.0 && *a.1 == b.1));
+        let rebuilt = Trie::from_iter(wanted);
+        assert!(trie.canonical_eq(&rebuilt));
+
+        let mut set = crate::map::UpdatableTrie::from(Trie::from_iter(
+            (0..100).map(|i| (format!("{:03}", i), ())),
+        ));
+        for i in 10..100 {
+            set.remove(format!("{:03}", i));
+        }
+        set.insert("x", ());
+        set.shrink();
+        assert_eq!(set.overlay_len(), 0);
+        assert_eq!(set.iter::<String, _>().count(), 11);
+        assert_eq!(set.shrink(), 0);
+    }
+
+    #[test]
+    fn remove_prefix_of_another_key() {
+        let mut builder = TrieBuilder::new();
+        builder.keep_insertion_order();
+        builder.push("app", 0);
+        builder.push("apple", 1);
+        let mut trie = builder.build();
+        assert_eq!(take_value(&mut trie, "app"), Some(0));
+        trie.shrink();
+        let order: Vec<(String, &u32)> = trie.iter_insertion_order().unwrap().collect();
+        assert_eq!(order, [("apple".to_string(), &1)]);
+    }
+}
@@ -0,0 +1,130 @@
+use super::{NodeRef, Trie};
+use std::collections::BTreeMap;
+
+#[cfg(feature = "mem_dbg")]
+use mem_dbg::MemDbg;
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// User data attached to nodes of a [Trie], including internal nodes that are
+/// not keys.
+///
+/// The data lives beside the trie and is looked up by node, so attaching it
+/// does not turn a prefix into an entry. Nodes are identified by their number
+/// within one trie; using a [NodeData] with another trie than the one it was
+/// filled from returns unrelated data.
+///
+/// ```rust
+/// use trie_rs::map::{NodeData, Trie};
+///
+/// let trie = Trie::from_iter([("std::io::Read", 0), ("std::fmt", 1)]);
+/// let mut boundaries = NodeData::new();
+/// assert_eq!(boundaries.set(&trie, "std::", "namespace"), Ok(None));
+/// assert!(boundaries.set(&trie, "core::", "namespace").is_err());
+///
+/// let node = trie.node("std::").unwrap();
+/// assert!(!node.is_terminal());
+/// assert_eq!(boundaries.get(node), Some(&"namespace"));
+/// assert_eq!(boundaries.get(trie.node("std").unwrap()), None);
+/// ```
+pub struct NodeData<D> {
+    /// LoudsNodeNum -> D
+    data: BTreeMap<u64, D>,
+}
+
+impl<D> Default for NodeData<D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D> NodeData<D> {
+    /// Return an empty [NodeData].
+    pub fn new() -> Self {
+        Self {
+            data: BTreeMap::new(),
+        }
+    }
+
+    /// Attach `data` to the node reached by `prefix` and return the data it
+    /// replaced. Return `Err(data)` if `prefix` is not a node of `trie`.
+    pub fn set<Label: Ord, Value>(
+        &mut self,
+        trie: &Trie<Label, Value>,
+        prefix: impl AsRef<[Label]>,
+        data: D,
+    ) -> Result<Option<D>, D> {
+        match trie.node(prefix) {
+            Some(node) => Ok(self.insert(node, data)),
+            None => Err(data),
+        }
+    }
+
+    /// Attach `data` to `node` and return the data it replaced.
+    pub fn insert<Label: Ord, Value>(
+        &mut self,
+        node: NodeRef<'_, Label, Value>,
+        data: D,
+    ) -> Option<D> {
+        self.data.insert(node.node_num().0, data)
+    }
+
+    /// Return the data attached to `node`.
+    pub fn get<Label: Ord, Value>(&self, node: NodeRef<'_, Label, Value>) -> Option<&D> {
+        self.data.get(&node.node_num().0)
+    }
+
+    /// Detach and return the data attached to `node`.
+    pub fn remove<Label: Ord, Value>(&mut self, node: NodeRef<'_, Label, Value>) -> Option<D> {
+        self.data.remove(&node.node_num().0)
+    }
+
+    /// Return the number of nodes with data attached.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Return true if no node has data attached.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+}
+
+impl<'a, Label: Ord, Value> NodeRef<'a, Label, Value> {
+    /// Return the data attached to this node in `node_data`. Shorthand for
+    /// [NodeData::get].
+    pub fn data<'d, D>(&self, node_data: &'d NodeData<D>) -> Option<&'d D> {
+        node_data.get(*self)
+    }
+}
+
+#[cfg(test)]
+mod node_data_tests {
+    use crate::map::{NodeData, Trie};
+
+    #[test]
+    fn internal_and_terminal_nodes() {
+        let trie = Trie::from_iter([("a.b.c", 0), ("a.d", 1)]);
+        let mut data = NodeData::new();
+        assert_eq!(data.set(&trie, "a.", 1), Ok(None));
+        assert_eq!(data.set(&trie, "a.d", 2), Ok(None));
+        assert_eq!(data.set(&trie, "a.", 3), Ok(Some(1)));
+        assert_eq!(data.set(&trie, "x", 4), Err(4));
+        assert_eq!(data.len(), 2);
+
+        let found: Vec<(String, &i32)> = trie
+            .node("a.")
+            .unwrap()
+            .children()
+            .filter_map(|child| child.data(&data).map(|d| (child.prefix(), d)))
+            .collect();
+        assert_eq!(found, [("a.d".to_string(), &2)]);
+        assert_eq!(trie.node("a.").unwrap().data(&data), Some(&3));
+        // Data does not make a prefix an entry.
+        assert_eq!(trie.exact_match("a."), None);
+
+        assert_eq!(data.remove(trie.node("a.").unwrap()), Some(3));
+        assert_eq!(data.get(trie.node("a.").unwrap()), None);
+    }
+}
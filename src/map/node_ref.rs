@@ -0,0 +1,648 @@
+use super::Trie;
+use crate::inc_search::Answer;
+use crate::raw::NodeId;
+use crate::try_collect::TryFromIterator;
+use louds_rs::{ChildNodeIter, LoudsNodeNum};
+
+#[derive(Debug)]
+/// A reference to a node of a [Trie].
+///
+/// Obtained from [Trie::node] or [Trie::children]; it borrows the trie so it
+/// may be navigated further without looking the node up again.
+pub struct NodeRef<'a, Label, Value> {
+    trie: &'a Trie<Label, Value>,
+    node: LoudsNodeNum,
+}
+
+impl<Label, Value> Clone for NodeRef<'_, Label, Value> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Label, Value> Copy for NodeRef<'_, Label, Value> {}
+
+impl<Label, Value> PartialEq for NodeRef<'_, Label, Value> {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.trie, other.trie) && self.node == other.node
+    }
+}
+
+impl<Label, Value> Eq for NodeRef<'_, Label, Value> {}
+
+impl<'a, Label: Ord, Value> NodeRef<'a, Label, Value> {
+    pub(crate) fn new(trie: &'a Trie<Label, Value>, node: LoudsNodeNum) -> Self {
+        Self { trie, node }
+    }
+
+    pub(crate) fn trie(&self) -> &'a Trie<Label, Value> {
+        self.trie
+    }
+
+    pub(crate) fn louds_node_num(&self) -> LoudsNodeNum {
+        self.node
+    }
+
+    /// Return the number of this node, as used by [Trie::raw].
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("ab", 0)]);
+    /// let node = trie.node("ab").unwrap();
+    /// assert_eq!(trie.raw().value(node.node_num()), Some(&0));
+    /// ```
+    pub fn node_num(&self) -> NodeId {
+        NodeId(self.node.0)
+    }
+
+    /// Return true if this is the root node.
+    pub fn is_root(&self) -> bool {
+        self.node.0 == 1
+    }
+
+    /// Return the label on the edge into this node, or `None` for the root.
+    pub fn label(&self) -> Option<&'a Label> {
+        (!self.is_root()).then(|| self.trie.label(self.node))
+    }
+
+    /// Return the value if this node is the end of a key.
+    pub fn value(&self) -> Option<&'a Value> {
+        self.trie.value(self.node)
+    }
+
+    /// Return true if this node is the end of a key.
+    pub fn is_terminal(&self) -> bool {
+        self.trie.is_terminal(self.node)
+    }
+
+    /// Return true if this node has any children.
+    pub fn has_children(&self) -> bool {
+        self.trie.has_children_node_nums(self.node)
+    }
+
+    /// Return how this node answers a query that ends on it, like
+    /// [crate::inc_search::IncSearch::query] does. `None` only for an empty
+    /// trie's root.
+    pub fn answer(&self) -> Option<Answer> {
+        Answer::new(self.has_children(), self.is_terminal())
+    }
+
+    /// Return the number of immediate children.
+    pub fn child_count(&self) -> usize {
+        self.trie.children_node_nums(self.node).len()
+    }
+
+    /// Return the number of keys strictly below this node, i.e. the keys
+    /// this node is a proper prefix of.
+    ///
+    /// This walks the whole subtree.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("a", 0), ("app", 1), ("apple", 2), ("bee", 3)]);
+    /// let a = trie.node("a").unwrap();
+    /// assert_eq!(a.child_count(), 1);
+    /// assert_eq!(a.descendants(), 2);
+    /// assert_eq!(trie.node("").unwrap().descendants(), 4);
+    /// ```
+    pub fn descendants(&self) -> usize {
+        let mut count = 0;
+        let mut stack: Vec<_> = self.trie.children_node_nums(self.node).collect();
+        while let Some(node) = stack.pop() {
+            if self.trie.is_terminal(node) {
+                count += 1;
+            }
+            stack.extend(self.trie.children_node_nums(node));
+        }
+        count
+    }
+
+    /// Return the labels of the children of this node in label order, as
+    /// they are stored: next to each other. The `i`th label is that of the
+    /// `i`th of [NodeRef::children].
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("a", 0), ("ab", 1), ("ac", 2), ("b", 3)]);
+    /// assert_eq!(trie.node("").unwrap().child_labels(), b"ab");
+    /// assert_eq!(trie.node("a").unwrap().child_labels(), b"bc");
+    /// assert_eq!(trie.node("ab").unwrap().child_labels(), b"");
+    /// ```
+    pub fn child_labels(&self) -> &'a [Label] {
+        self.trie.child_labels(self.node)
+    }
+
+    /// Return the children of this node in label order.
+    pub fn children(&self) -> ChildNodeRefs<'a, Label, Value> {
+        ChildNodeRefs {
+            trie: self.trie,
+            iter: Some(self.trie.children_node_nums(self.node)),
+        }
+    }
+
+    /// Return this node and all nodes below it, depth first, with children in
+    /// label order. [DfsOrder::PreOrder] yields a node before its children,
+    /// e.g. to filter; [DfsOrder::PostOrder] after them, e.g. to aggregate.
+    /// The walk keeps its own stack, so deep tries do not overflow.
+    ///
+    /// ```rust
+    /// use trie_rs::map::{DfsOrder, Trie};
+    ///
+    /// let trie = Trie::from_iter([("ab", 0), ("ac", 1), ("b", 2)]);
+    /// let root = trie.node("").unwrap();
+    /// let labels = |order| -> Vec<u8> {
+    ///     root.dfs(order).filter_map(|node| node.label().copied()).collect()
+    /// };
+    /// assert_eq!(labels(DfsOrder::PreOrder), b"abcb");
+    /// assert_eq!(labels(DfsOrder::PostOrder), b"bcab");
+    ///
+    /// // Sum the values below each node, children first.
+    /// let mut sums = Vec::new();
+    /// for node in root.dfs(DfsOrder::PostOrder) {
+    ///     let below: u8 = sums.drain(sums.len() - node.child_count()..).sum();
+    ///     sums.push(below + node.value().copied().unwrap_or(0));
+    /// }
+    /// assert_eq!(sums, [3]);
+    /// ```
+    pub fn dfs(&self, order: DfsOrder) -> Dfs<'a, Label, Value> {
+        Dfs {
+            trie: self.trie,
+            order,
+            stack: vec![(self.node, false)],
+        }
+    }
+
+    /// Return the parent of this node, or `None` for the root.
+    pub fn parent(&self) -> Option<Self> {
+        self.trie
+            .parent(self.node)
+            .map(|node| Self::new(self.trie, node))
+    }
+
+    /// Return the ancestors of this node from its parent up to the root.
+    ///
+    /// The walk is lazy, so it may stop after the nearest few.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("docs/api/node.md", 0)]);
+    /// let node = trie.node("docs/api/node.md").unwrap();
+    /// assert_eq!(node.label_len(), 16);
+    /// let crumbs: Vec<u8> = node.ancestors().take(3).map(|a| *a.label().unwrap()).collect();
+    /// assert_eq!(crumbs, b"m.e");
+    /// ```
+    pub fn ancestors(&self) -> Ancestors<'a, Label, Value> {
+        Ancestors {
+            trie: self.trie,
+            node: self.trie.parent(self.node),
+        }
+    }
+
+    /// Return the number of labels from the root to this node, i.e. its
+    /// depth, without collecting them.
+    pub fn label_len(&self) -> usize {
+        self.trie.child_to_ancestors(self.node).count()
+    }
+
+    /// Return the labels from the root to this node, i.e. the key or prefix
+    /// this node stands for.
+    pub fn prefix<C, M>(&self) -> C
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        self.trie.prefix_of(self.node)
+    }
+
+    /// Append the labels from the root to this node to `buf`, so one buffer
+    /// may be reused for many keys instead of allocating one per key.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("app", 0), ("apple", 1)]);
+    /// let mut buf = Vec::new();
+    /// for node in trie.iter_nodes() {
+    ///     buf.clear();
+    ///     node.write_label_into(&mut buf);
+    ///     assert_eq!(trie.exact_match(&buf), node.value());
+    /// }
+    /// ```
+    pub fn write_label_into(&self, buf: &mut Vec<Label>)
+    where
+        Label: Clone,
+    {
+        let start = buf.len();
+        buf.extend(
+            self.trie
+                .child_to_ancestors(self.node)
+                .map(|node| self.trie.label(node).clone()),
+        );
+        buf[start..].reverse();
+    }
+}
+
+impl<'a, Value> NodeRef<'a, u8, Value> {
+    /// Return the bytes of the children of this node in order, as one slice,
+    /// e.g. to test for several bytes at once. Same as
+    /// [NodeRef::child_labels].
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("cat", 0), ("cow", 1), ("cub", 2)]);
+    /// let c = trie.node("c").unwrap();
+    /// assert_eq!(c.child_tokens().iter().position(|&b| b == b'o'), Some(1));
+    /// assert_eq!(c.children().nth(1).unwrap().label(), Some(&b'o'));
+    /// ```
+    pub fn child_tokens(&self) -> &'a [u8] {
+        self.child_labels()
+    }
+
+    /// Append the key or prefix this node stands for to `buf`. Return an
+    /// error and leave `buf` as it was if it is not valid UTF-8, e.g. when
+    /// this node is in the middle of a multi-byte character.
+    ///
+    /// `buf` is checked as a whole, so clear it between keys.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("すし", 0)]);
+    /// let mut buf = String::from("> ");
+    /// trie.node("すし").unwrap().write_str_into(&mut buf).unwrap();
+    /// assert_eq!(buf, "> すし");
+    /// assert!(trie.node(&"す".as_bytes()[..1]).unwrap().write_str_into(&mut buf).is_err());
+    /// assert_eq!(buf, "> すし");
+    /// ```
+    pub fn write_str_into(&self, buf: &mut String) -> Result<(), std::str::Utf8Error> {
+        let mut bytes = std::mem::take(buf).into_bytes();
+        let start = bytes.len();
+        self.write_label_into(&mut bytes);
+        let result = std::str::from_utf8(&bytes[start..]).map(|_| ());
+        if result.is_err() {
+            bytes.truncate(start);
+        }
+        *buf = String::from_utf8(bytes).expect("Both parts are valid UTF-8");
+        result
+    }
+}
+
+impl<Value> NodeRef<'_, char, Value> {
+    /// Append the key or prefix this node stands for to `buf`.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie: Trie<char, u8> = Trie::from_iter([("すし".chars().collect::<Vec<_>>(), 0)]);
+    /// let mut buf = String::new();
+    /// trie.node(['す', 'し']).unwrap().write_str_into(&mut buf);
+    /// assert_eq!(buf, "すし");
+    /// ```
+    pub fn write_str_into(&self, buf: &mut String) {
+        let start = buf.len();
+        let chars = self.trie.child_to_ancestors(self.node);
+        let len: usize = chars.map(|node| self.trie.label(node).len_utf8()).sum();
+        buf.extend((0..len).map(|_| '\0'));
+        let mut end = buf.len();
+        for node in self.trie.child_to_ancestors(self.node) {
+            let c = self.trie.label(node);
+            end -= c.len_utf8();
+            buf.replace_range(end..end + c.len_utf8(), c.encode_utf8(&mut [0; 4]));
+        }
+        debug_assert_eq!(end, start);
+    }
+}
+
+/// Iterates over the ancestors of a node as [NodeRef]s, nearest first. See
+/// [NodeRef::ancestors].
+pub struct Ancestors<'a, Label, Value> {
+    trie: &'a Trie<Label, Value>,
+    node: Option<LoudsNodeNum>,
+}
+
+impl<'a, Label: Ord, Value> Iterator for Ancestors<'a, Label, Value> {
+    type Item = NodeRef<'a, Label, Value>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.node?;
+        self.node = self.trie.parent(node);
+        Some(NodeRef::new(self.trie, node))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// When [NodeRef::dfs] yields a node relative to its children.
+pub enum DfsOrder {
+    /// A node comes before its children.
+    PreOrder,
+    /// A node comes after its children.
+    PostOrder,
+}
+
+/// Iterates depth first over a node and the nodes below it as [NodeRef]s.
+/// See [NodeRef::dfs].
+pub struct Dfs<'a, Label, Value> {
+    trie: &'a Trie<Label, Value>,
+    order: DfsOrder,
+    /// Nodes to visit, last first, and whether their children were pushed.
+    stack: Vec<(LoudsNodeNum, bool)>,
+}
+
+impl<'a, Label: Ord, Value> Iterator for Dfs<'a, Label, Value> {
+    type Item = NodeRef<'a, Label, Value>;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (node, expanded) = self.stack.pop()?;
+            if expanded {
+                return Some(NodeRef::new(self.trie, node));
+            }
+            if self.order == DfsOrder::PostOrder {
+                self.stack.push((node, true));
+            }
+            let children = self.trie.children_node_nums(node);
+            self.stack
+                .extend(children.rev().map(|child| (child, false)));
+            if self.order == DfsOrder::PreOrder {
+                return Some(NodeRef::new(self.trie, node));
+            }
+        }
+    }
+}
+
+/// Iterates over the children of a node as [NodeRef]s.
+pub struct ChildNodeRefs<'a, Label, Value> {
+    trie: &'a Trie<Label, Value>,
+    iter: Option<ChildNodeIter<'a>>,
+}
+
+impl<'a, Label, Value> ChildNodeRefs<'a, Label, Value> {
+    pub(crate) fn empty(trie: &'a Trie<Label, Value>) -> Self {
+        Self { trie, iter: None }
+    }
+}
+
+impl<'a, Label: Ord, Value> Iterator for ChildNodeRefs<'a, Label, Value> {
+    type Item = NodeRef<'a, Label, Value>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter
+            .as_mut()?
+            .next()
+            .map(|node| NodeRef::new(self.trie, node))
+    }
+}
+
+impl<Label: Ord, Value> DoubleEndedIterator for ChildNodeRefs<'_, Label, Value> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter
+            .as_mut()?
+            .next_back()
+            .map(|node| NodeRef::new(self.trie, node))
+    }
+}
+
+/// Iterates over the nodes of the keys that are prefixes of a query,
+/// shortest first, descending one label at a time. See
+/// [Trie::prefixes_of_nodes].
+pub struct PrefixNodes<'a, Label, Value, Q> {
+    trie: &'a Trie<Label, Value>,
+    query: Q,
+    index: usize,
+    /// `None` once the query or the trie ran out.
+    node: Option<LoudsNodeNum>,
+}
+
+impl<'a, Label, Value, Q> PrefixNodes<'a, Label, Value, Q> {
+    pub(crate) fn new(trie: &'a Trie<Label, Value>, query: Q) -> Self {
+        Self {
+            trie,
+            query,
+            index: 0,
+            node: Some(LoudsNodeNum(1)),
+        }
+    }
+}
+
+impl<'a, Label: Ord, Value, Q: AsRef<[Label]>> Iterator for PrefixNodes<'a, Label, Value, Q> {
+    type Item = NodeRef<'a, Label, Value>;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let label = self.query.as_ref().get(self.index);
+            self.node = label.and_then(|label| self.trie.child(self.node?, label));
+            let node = self.node?;
+            self.index += 1;
+            if self.trie.is_terminal(node) {
+                return Some(NodeRef::new(self.trie, node));
+            }
+        }
+    }
+}
+
+impl<Label: Ord, Value, Q: AsRef<[Label]>> std::iter::FusedIterator
+    for PrefixNodes<'_, Label, Value, Q>
+{
+}
+
+#[cfg(test)]
+mod node_ref_tests {
+    use crate::map::Trie;
+
+    struct NoClone(u8);
+
+    impl PartialEq for NoClone {
+        fn eq(&self, other: &Self) -> bool {
+            self.0 == other.0
+        }
+    }
+    impl Eq for NoClone {}
+    impl PartialOrd for NoClone {
+        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+    impl Ord for NoClone {
+        fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+            self.0.cmp(&other.0)
+        }
+    }
+
+    #[test]
+    fn children_without_clone() {
+        let mut builder = crate::map::TrieBuilder::new();
+        builder.insert([NoClone(1), NoClone(2)], "a");
+        builder.insert([NoClone(1), NoClone(3)], "b");
+        let trie = builder.build();
+        let children: Vec<u8> = trie
+            .children([NoClone(1)])
+            .map(|c| c.label().unwrap().0)
+            .collect();
+        assert_eq!(children, [2, 3]);
+        assert_eq!(trie.children([NoClone(4)]).count(), 0);
+    }
+
+    #[test]
+    fn navigate() {
+        let trie = Trie::from_iter([("a", 0), ("app", 1), ("apple", 2), ("bee", 3)]);
+        let root = trie.node("").unwrap();
+        assert!(root.is_root());
+        assert_eq!(root.label(), None);
+        let labels: Vec<u8> = root.children().map(|c| *c.label().unwrap()).collect();
+        assert_eq!(labels, b"ab");
+
+        let ap = trie.node("ap").unwrap();
+        assert!(!ap.is_terminal());
+        assert_eq!(ap.value(), None);
+        assert_eq!(ap.prefix::<String, _>(), "ap");
+        let app = ap.children().next().unwrap();
+        assert_eq!(app.value(), Some(&1));
+        assert_eq!(app.parent(), Some(ap));
+        assert_eq!(root.parent(), None);
+        assert!(trie.node("apz").is_none());
+    }
+
+    #[test]
+    fn prefix_nodes_are_lazy() {
+        let trie = Trie::from_iter([("a", 0), ("app", 1), ("apple", 2)]);
+        let mut nodes = trie.prefixes_of_nodes("apples");
+        assert_eq!(nodes.next().map(|node| node.label_len()), Some(1));
+        assert_eq!(nodes.index, 1);
+        assert_eq!(nodes.next().map(|node| node.label_len()), Some(3));
+        assert_eq!(nodes.index, 3);
+        assert_eq!(nodes.next().map(|node| node.label_len()), Some(5));
+        assert_eq!(nodes.next(), None);
+        assert_eq!(nodes.next(), None);
+    }
+
+    #[test]
+    fn ancestors() {
+        let trie = Trie::from_iter([("a", 0), ("app", 1), ("apple", 2)]);
+        let apple = trie.node("apple").unwrap();
+        assert_eq!(apple.label_len(), 5);
+        assert_eq!(apple.label_len(), 5);
+        let ancestors: Vec<usize> = apple.ancestors().map(|a| a.label_len()).collect();
+        assert_eq!(ancestors, [4, 3, 2, 1, 0]);
+        assert!(apple.ancestors().last().unwrap().is_root());
+        assert_eq!(apple.ancestors().nth(1), trie.node("app"));
+        let root = trie.node("").unwrap();
+        assert_eq!(root.label_len(), 0);
+        assert_eq!(root.ancestors().count(), 0);
+    }
+
+    #[test]
+    fn counts() {
+        use crate::inc_search::Answer;
+        let trie = Trie::from_iter([("a", 0), ("app", 1), ("apple", 2), ("apply", 3)]);
+        let root = trie.node("").unwrap();
+        assert_eq!(root.child_count(), 1);
+        assert_eq!(root.descendants(), 4);
+        assert_eq!(root.answer(), Some(Answer::Prefix));
+        let app = trie.node("app").unwrap();
+        assert_eq!(app.answer(), Some(Answer::PrefixAndMatch));
+        assert_eq!(app.child_count(), 1);
+        assert_eq!(app.descendants(), 2);
+        let appl = trie.node("appl").unwrap();
+        assert_eq!(appl.child_count(), 2);
+        let apple = trie.node("apple").unwrap();
+        assert_eq!(apple.answer(), Some(Answer::Match));
+        assert_eq!(apple.child_count(), 0);
+        assert_eq!(apple.descendants(), 0);
+    }
+
+    #[test]
+    fn dfs() {
+        use crate::map::DfsOrder;
+        let keys = ["a", "ab", "abc", "abd", "b", "ba", "bb", "c", "アップル"];
+        let trie = Trie::from_iter(keys.map(|k| (k, ())));
+        let root = trie.node("").unwrap();
+
+        // Pre-order visits keys in lexicographic order.
+        let pre: Vec<String> = root
+            .dfs(DfsOrder::PreOrder)
+            .filter(|node| node.is_terminal())
+            .map(|node| node.prefix())
+            .collect();
+        assert_eq!(pre, keys);
+
+        // Post-order comes after every descendant, so counts add up.
+        let mut counts = Vec::new();
+        for node in root.dfs(DfsOrder::PostOrder) {
+            let below: usize = counts.drain(counts.len() - node.child_count()..).sum();
+            assert_eq!(below, node.descendants());
+            counts.push(below + usize::from(node.is_terminal()));
+        }
+        assert_eq!(counts, [keys.len()]);
+
+        for order in [DfsOrder::PreOrder, DfsOrder::PostOrder] {
+            assert_eq!(root.dfs(order).count(), trie.trie_labels.len() + 1);
+            let ab = trie.node("ab").unwrap();
+            let below: Vec<String> = ab.dfs(order).map(|node| node.prefix()).collect();
+            match order {
+                DfsOrder::PreOrder => assert_eq!(below, ["ab", "abc", "abd"]),
+                DfsOrder::PostOrder => assert_eq!(below, ["abc", "abd", "ab"]),
+            }
+        }
+        let deep = Trie::from_iter([(vec![0u8; 100_000], ())]);
+        let root = deep.node([]).unwrap();
+        assert_eq!(root.dfs(DfsOrder::PostOrder).count(), 100_001);
+    }
+
+    #[test]
+    fn child_labels() {
+        let trie = Trie::from_iter(
+            [
+                "a",
+                "ab",
+                "abc",
+                "abd",
+                "b",
+                "ba",
+                "bb",
+                "bc",
+                "c",
+                "アップル",
+            ]
+            .map(|k| (k, ())),
+        );
+        let mut stack = vec![trie.node("").unwrap()];
+        while let Some(node) = stack.pop() {
+            let labels: Vec<u8> = node
+                .children()
+                .map(|child| *child.label().unwrap())
+                .collect();
+            assert_eq!(node.child_labels(), &labels[..]);
+            assert_eq!(node.child_tokens(), &labels[..]);
+            stack.extend(node.children());
+        }
+        let empty = Trie::<u8, ()>::from_iter(Vec::<(&str, ())>::new());
+        assert_eq!(empty.node("").unwrap().child_labels(), b"");
+    }
+
+    #[test]
+    fn write_into() {
+        let trie = Trie::from_iter([("a", 0), ("app", 1), ("アップル", 2)]);
+        let mut labels = b"x".to_vec();
+        trie.node("app").unwrap().write_label_into(&mut labels);
+        trie.node("").unwrap().write_label_into(&mut labels);
+        assert_eq!(labels, b"xapp");
+
+        let mut buf = String::new();
+        for node in trie.iter_nodes() {
+            buf.clear();
+            node.write_str_into(&mut buf).unwrap();
+            assert_eq!(buf, node.prefix::<String, _>());
+        }
+        let partial = trie.node(&"ア".as_bytes()[..2]).unwrap();
+        buf = "ok".into();
+        assert!(partial.write_str_into(&mut buf).is_err());
+        assert_eq!(buf, "ok");
+
+        let trie: Trie<char, u8> = Trie::from_iter([("aé🍎".chars().collect::<Vec<_>>(), 0)]);
+        let mut buf = String::from("é");
+        let node = trie.iter_nodes().next().unwrap();
+        node.write_str_into(&mut buf);
+        assert_eq!(buf, "éaé🍎");
+    }
+}
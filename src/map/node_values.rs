@@ -0,0 +1,184 @@
+//! Values of a [Trie](super::Trie)'s nodes, indexed by `LoudsNodeNum - 2`.
+//!
+//! Usually each node holds an `Option<Value>`. A zero-sized `Value`, like the
+//! `()` of a set, would still cost the `Option`'s discriminant plus padding
+//! per node, so terminals are instead kept as one bit per node. The values
+//! themselves then take no space, and any one stands for all the others.
+use std::mem::size_of;
+
+#[cfg(feature = "mem_dbg")]
+use mem_dbg::MemDbg;
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct NodeValues<Value> {
+    /// Index -> value. Empty if `Value` is zero-sized.
+    slots: Vec<Option<Value>>,
+    /// Index -> whether the node is terminal, 64 per word. Empty unless
+    /// `Value` is zero-sized.
+    terminals: Vec<u64>,
+    /// One value per terminal if `Value` is zero-sized.
+    zero_sized: Vec<Value>,
+    len: usize,
+}
+
+impl<Value> Default for NodeValues<Value> {
+    fn default() -> Self {
+        Self {
+            slots: Vec::new(),
+            terminals: Vec::new(),
+            zero_sized: Vec::new(),
+            len: 0,
+        }
+    }
+}
+
+impl<Value> FromIterator<Option<Value>> for NodeValues<Value> {
+    fn from_iter<I: IntoIterator<Item = Option<Value>>>(iter: I) -> Self {
+        let mut values = Self::default();
+        for value in iter {
+            values.push(value);
+        }
+        values
+    }
+}
+
+impl<Value> NodeValues<Value> {
+    const ZERO_SIZED: bool = size_of::<Value>() == 0;
+
+    pub(crate) fn push(&mut self, value: Option<Value>) {
+        if Self::ZERO_SIZED {
+            if self.len / 64 == self.terminals.len() {
+                self.terminals.push(0);
+            }
+            if let Some(value) = value {
+                self.terminals[self.len / 64] |= 1 << (self.len % 64);
+                self.zero_sized.push(value);
+            }
+        } else {
+            self.slots.push(value);
+        }
+        self.len += 1;
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return the bytes allocated for the values.
+    pub(crate) fn heap_bytes(&self) -> usize {
+        self.slots.capacity() * size_of::<Option<Value>>()
+            + self.terminals.capacity() * size_of::<u64>()
+    }
+
+    /// Drop the spare capacity.
+    pub(crate) fn shrink_to_fit(&mut self) {
+        self.slots.shrink_to_fit();
+        self.terminals.shrink_to_fit();
+        self.zero_sized.shrink_to_fit();
+    }
+
+    pub(crate) fn is_some(&self, index: usize) -> bool {
+        if Self::ZERO_SIZED {
+            assert!(index < self.len, "index out of bounds");
+            self.terminals[index / 64] & (1 << (index % 64)) != 0
+        } else {
+            self.slots[index].is_some()
+        }
+    }
+
+    pub(crate) fn get(&self, index: usize) -> Option<&Value> {
+        if Self::ZERO_SIZED {
+            self.is_some(index).then(|| &self.zero_sized[0])
+        } else {
+            self.slots[index].as_ref()
+        }
+    }
+
+    pub(crate) fn get_mut(&mut self, index: usize) -> Option<&mut Value> {
+        if Self::ZERO_SIZED {
+            self.is_some(index).then(|| &mut self.zero_sized[0])
+        } else {
+            self.slots[index].as_mut()
+        }
+    }
+
+    pub(crate) fn take(&mut self, index: usize) -> Option<Value> {
+        if Self::ZERO_SIZED {
+            if !self.is_some(index) {
+                return None;
+            }
+            self.terminals[index / 64] &= !(1 << (index % 64));
+            self.zero_sized.pop()
+        } else {
+            self.slots[index].take()
+        }
+    }
+
+    /// Return a mutable reference to every node's value, by index.
+    pub(crate) fn iter_mut(&mut self) -> Vec<Option<&mut Value>> {
+        if Self::ZERO_SIZED {
+            let mut values = self.zero_sized.iter_mut();
+            let terminals = &self.terminals;
+            (0..self.len)
+                .map(|index| {
+                    (terminals[index / 64] & (1 << (index % 64)) != 0)
+                        .then(|| values.next().expect("one value per terminal"))
+                })
+                .collect()
+        } else {
+            self.slots.iter_mut().map(Option::as_mut).collect()
+        }
+    }
+
+    /// Return the values of the nodes for which `keep` yields true.
+    pub(crate) fn retain(mut self, keep: impl IntoIterator<Item = bool>) -> Self {
+        let mut kept = Self::default();
+        for (index, keep) in (0..self.len).zip(keep) {
+            if keep {
+                kept.push(self.take(index));
+            }
+        }
+        kept
+    }
+}
+
+#[cfg(test)]
+mod node_values_tests {
+    use super::NodeValues;
+    use std::mem::size_of_val;
+
+    #[test]
+    fn zero_sized() {
+        let mut values: NodeValues<()> = (0..130).map(|i| (i % 3 == 0).then_some(())).collect();
+        assert_eq!(values.len(), 130);
+        assert!(values.slots.is_empty());
+        assert_eq!(values.terminals.len(), 3);
+        assert_eq!(size_of_val(&values.zero_sized[..]), 0);
+        assert_eq!(values.get(129), Some(&()));
+        assert_eq!(values.get(128), None);
+        assert_eq!(values.take(129), Some(()));
+        assert_eq!(values.take(129), None);
+        assert!(!values.is_some(129));
+        assert_eq!(values.zero_sized.len(), 43);
+
+        let kept = values.retain((0..130).map(|i| i >= 120));
+        assert_eq!(kept.len(), 10);
+        let terminals: Vec<bool> = (0..10).map(|i| kept.is_some(i)).collect();
+        assert_eq!(
+            terminals,
+            [true, false, false, true, false, false, true, false, false, false]
+        );
+    }
+
+    #[test]
+    fn sized() {
+        let mut values: NodeValues<u8> = [None, Some(1), Some(2)].into_iter().collect();
+        assert!(values.terminals.is_empty());
+        *values.get_mut(1).unwrap() = 10;
+        assert_eq!(values.get(1), Some(&10));
+        assert_eq!(values.take(2), Some(2));
+        assert!(!values.is_some(2));
+    }
+}
@@ -0,0 +1,218 @@
+use super::{Trie, TrieBuilder};
+use louds_rs::LoudsNodeNum;
+
+#[cfg(feature = "mem_dbg")]
+use mem_dbg::MemDbg;
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A trie map of patterns in which a wildcard label matches any one label of
+/// a query, e.g. the routes of a web server split into path components.
+///
+/// At each label of a query, a literal child is tried before the wildcard,
+/// and if the rest of the query does not match below it, the wildcard is
+/// tried instead.
+///
+/// ```rust
+/// use trie_rs::map::PatternTrieBuilder;
+///
+/// let mut builder = PatternTrieBuilder::new("*");
+/// builder.push(["user", "*", "settings"], 0);
+/// builder.push(["user", "admin", "profile"], 1);
+/// let routes = builder.build();
+///
+/// assert_eq!(routes.exact_match(["user", "ann", "settings"]), Some(&0));
+/// assert_eq!(routes.exact_match(["user", "admin", "settings"]), Some(&0));
+/// assert_eq!(routes.exact_match(["user", "admin", "profile"]), Some(&1));
+/// assert_eq!(routes.exact_match(["user", "ann", "profile"]), None);
+/// ```
+pub struct PatternTrie<Label, Value> {
+    trie: Trie<Label, Value>,
+    wildcard: Label,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A trie builder for [PatternTrie].
+pub struct PatternTrieBuilder<Label, Value> {
+    builder: TrieBuilder<Label, Value>,
+    wildcard: Label,
+}
+
+impl<Label: Ord + Clone, Value> PatternTrieBuilder<Label, Value> {
+    /// Return a [PatternTrieBuilder] whose patterns use `wildcard` to match
+    /// any one label.
+    pub fn new(wildcard: Label) -> Self {
+        Self {
+            builder: TrieBuilder::new(),
+            wildcard,
+        }
+    }
+
+    /// Add a pattern and value.
+    pub fn push<Arr: AsRef<[Label]>>(&mut self, pattern: Arr, value: Value) {
+        self.builder.push(pattern, value);
+    }
+
+    /// Build a [PatternTrie].
+    pub fn build(self) -> PatternTrie<Label, Value> {
+        PatternTrie {
+            trie: self.builder.build(),
+            wildcard: self.wildcard,
+        }
+    }
+}
+
+impl<Label: Ord + Clone, Value> PatternTrie<Label, Value> {
+    /// Return the value of the pattern matching `query`, preferring literal
+    /// labels to the wildcard from the first label on.
+    pub fn exact_match(&self, query: impl AsRef<[Label]>) -> Option<&Value> {
+        let node = self.find(query.as_ref(), &mut Vec::new())?;
+        self.trie.value(node)
+    }
+
+    /// Like [PatternTrie::exact_match], but also return the labels of
+    /// `query` matched by the wildcard, in order.
+    ///
+    /// ```rust
+    /// use trie_rs::map::PatternTrieBuilder;
+    ///
+    /// let mut builder = PatternTrieBuilder::new("*");
+    /// builder.push(["repos", "*", "issues", "*"], "issue");
+    /// let routes = builder.build();
+    ///
+    /// let query = ["repos", "trie-rs", "issues", "42"];
+    /// assert_eq!(routes.captures(&query), Some((&"issue", vec![&"trie-rs", &"42"])));
+    /// ```
+    pub fn captures<'q>(&self, query: &'q [Label]) -> Option<(&Value, Vec<&'q Label>)> {
+        let mut wildcards = Vec::new();
+        let node = self.find(query, &mut wildcards)?;
+        let captured = wildcards.into_iter().map(|depth| &query[depth]).collect();
+        Some((self.trie.value(node)?, captured))
+    }
+
+    /// Return the wildcard label.
+    pub fn wildcard(&self) -> &Label {
+        &self.wildcard
+    }
+
+    /// Return the patterns as a trie in which the wildcard is an ordinary
+    /// label.
+    pub fn patterns(&self) -> &Trie<Label, Value> {
+        &self.trie
+    }
+
+    /// Return the key node matching `query`, and push the positions the
+    /// wildcard matched at onto `wildcards`. Each node is tried at most once,
+    /// as it can only match at its own depth, and the branches still to try
+    /// are kept on a stack rather than the call stack, so long queries cannot
+    /// overflow it.
+    fn find(&self, query: &[Label], wildcards: &mut Vec<usize>) -> Option<LoudsNodeNum> {
+        // The node, its depth, and whether it matched query[depth - 1] by the
+        // wildcard.
+        let mut stack = vec![(LoudsNodeNum(1), 0, false)];
+        while let Some((node, depth, by_wildcard)) = stack.pop() {
+            // Forget the wildcards of the branch given up on, if any.
+            while let Some(&last) = wildcards.last() {
+                if last + 1 < depth {
+                    break;
+                }
+                wildcards.pop();
+            }
+            if by_wildcard {
+                wildcards.push(depth - 1);
+            }
+            let Some(label) = query.get(depth) else {
+                if self.trie.is_terminal(node) {
+                    return Some(node);
+                }
+                continue;
+            };
+            // The literal child goes on top, to be tried first. A wildcard in
+            // the query is only tried as a literal.
+            if *label != self.wildcard {
+                if let Some(child) = self.trie.child(node, &self.wildcard) {
+                    stack.push((child, depth + 1, true));
+                }
+            }
+            if let Some(child) = self.trie.child(node, label) {
+                stack.push((child, depth + 1, false));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod pattern_trie_tests {
+    use crate::map::{PatternTrie, PatternTrieBuilder};
+
+    fn split(path: &str) -> Vec<String> {
+        path.split('/').map(String::from).collect()
+    }
+
+    fn build_routes() -> PatternTrie<String, u8> {
+        let mut builder = PatternTrieBuilder::new("*".to_string());
+        for (i, route) in [
+            "user/*/settings",
+            "user/admin/profile",
+            "user/admin",
+            "user/*",
+            "*/help",
+            "*/*/*/deep",
+            "a/b/c/deep",
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            builder.push(split(route), i as u8);
+        }
+        builder.build()
+    }
+
+    #[test]
+    fn literals_before_wildcards() {
+        let routes = build_routes();
+        let route = |path: &str| {
+            let query = split(path);
+            routes.captures(&query).map(|(v, captured)| {
+                let captured: Vec<String> = captured.into_iter().cloned().collect();
+                (*v, captured.join("/"))
+            })
+        };
+        assert_eq!(route("user/ann/settings"), Some((0, "ann".to_string())));
+        assert_eq!(route("user/admin/settings"), Some((0, "admin".to_string())));
+        assert_eq!(route("user/admin/profile"), Some((1, "".to_string())));
+        assert_eq!(route("user/admin"), Some((2, "".to_string())));
+        assert_eq!(route("user/ann"), Some((3, "ann".to_string())));
+        assert_eq!(route("user/help"), Some((3, "help".to_string())));
+        assert_eq!(route("docs/help"), Some((4, "docs".to_string())));
+        assert_eq!(route("a/b/c/deep"), Some((6, "".to_string())));
+        assert_eq!(route("a/b/x/deep"), Some((5, "a/b/x".to_string())));
+        assert_eq!(route("user/*"), Some((3, "".to_string())));
+        assert_eq!(route("user/ann/profile"), None);
+        assert_eq!(route("user"), None);
+        assert_eq!(route("a/b/c"), None);
+
+        assert_eq!(routes.exact_match(split("x/help")), Some(&4));
+        assert_eq!(routes.wildcard(), "*");
+        assert_eq!(routes.patterns().exact_match(split("*/help")), Some(&4));
+    }
+
+    #[test]
+    fn long_queries() {
+        let len = 20_000;
+        let mut builder = PatternTrieBuilder::new(0u8);
+        builder.push(vec![0; len], "wildcards");
+        builder.push(vec![1; len], "ones");
+        let patterns = builder.build();
+        let mut query = vec![1; len];
+        assert_eq!(patterns.exact_match(&query), Some(&"ones"));
+        query[len - 1] = 2;
+        let (value, captured) = patterns.captures(&query).unwrap();
+        assert_eq!(*value, "wildcards");
+        assert_eq!(captured.len(), len);
+    }
+}
@@ -0,0 +1,204 @@
+use super::{Trie, TrieBuilder};
+use std::collections::BTreeMap;
+
+/// Maps a word to a phonetic code; words that sound alike share a code.
+pub trait PhoneticEncoder {
+    /// Return the phonetic code of `word`.
+    fn encode(&self, word: &str) -> String;
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+/// American Soundex: the first letter followed by three digits, e.g. "S530"
+/// for both "Smith" and "Smyth". Characters other than ASCII letters are
+/// ignored.
+pub struct Soundex;
+
+impl PhoneticEncoder for Soundex {
+    fn encode(&self, word: &str) -> String {
+        fn digit(c: u8) -> Option<u8> {
+            match c {
+                b'B' | b'F' | b'P' | b'V' => Some(b'1'),
+                b'C' | b'G' | b'J' | b'K' | b'Q' | b'S' | b'X' | b'Z' => Some(b'2'),
+                b'D' | b'T' => Some(b'3'),
+                b'L' => Some(b'4'),
+                b'M' | b'N' => Some(b'5'),
+                b'R' => Some(b'6'),
+                _ => None,
+            }
+        }
+        let mut letters = word
+            .bytes()
+            .filter(u8::is_ascii_alphabetic)
+            .map(|c| c.to_ascii_uppercase());
+        let Some(first) = letters.next() else {
+            return String::new();
+        };
+        let mut code = vec![first];
+        let mut last = digit(first);
+        for c in letters {
+            match digit(c) {
+                Some(d) if last != Some(d) => {
+                    code.push(d);
+                    if code.len() == 4 {
+                        break;
+                    }
+                    last = Some(d);
+                }
+                Some(_) => {}
+                // 'H' and 'W' do not separate letters with the same code.
+                None if c == b'H' || c == b'W' => {}
+                None => last = None,
+            }
+        }
+        code.resize(4, b'0');
+        String::from_utf8(code).unwrap()
+    }
+}
+
+#[derive(Debug, Clone)]
+/// A trie map keyed by the phonetic codes of words, which returns the words
+/// as they were pushed.
+///
+/// ```rust
+/// use trie_rs::map::PhoneticTrieBuilder;
+///
+/// let mut builder = PhoneticTrieBuilder::new();
+/// builder.push("Smith", 0);
+/// builder.push("Smyth", 1);
+/// builder.push("Jones", 2);
+/// let trie = builder.build();
+///
+/// let results: Vec<(&str, &u8)> = trie.phonetic_search("Smithe").collect();
+/// assert_eq!(results, [("Smith", &0), ("Smyth", &1)]);
+/// ```
+pub struct PhoneticTrie<Value, E = Soundex> {
+    trie: Trie<u8, Vec<(String, Value)>>,
+    encoder: E,
+}
+
+#[derive(Debug, Clone)]
+/// A trie builder for [PhoneticTrie].
+pub struct PhoneticTrieBuilder<Value, E = Soundex> {
+    entries: BTreeMap<String, Vec<(String, Value)>>,
+    encoder: E,
+}
+
+impl<Value> Default for PhoneticTrieBuilder<Value> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Value> PhoneticTrieBuilder<Value> {
+    /// Return a [PhoneticTrieBuilder] using [Soundex].
+    pub fn new() -> Self {
+        Self::with_encoder(Soundex)
+    }
+}
+
+impl<Value, E: PhoneticEncoder> PhoneticTrieBuilder<Value, E> {
+    /// Return a [PhoneticTrieBuilder] using `encoder`.
+    pub fn with_encoder(encoder: E) -> Self {
+        Self {
+            entries: BTreeMap::new(),
+            encoder,
+        }
+    }
+
+    /// Add a word and value. Words sharing a code are all kept, in push
+    /// order.
+    pub fn push(&mut self, word: impl Into<String>, value: Value) {
+        let word = word.into();
+        let code = self.encoder.encode(&word);
+        self.entries.entry(code).or_default().push((word, value));
+    }
+
+    /// Build a [PhoneticTrie].
+    pub fn build(self) -> PhoneticTrie<Value, E> {
+        let mut builder = TrieBuilder::new();
+        for (code, words) in self.entries {
+            builder.insert(code.into_bytes(), words);
+        }
+        PhoneticTrie {
+            trie: builder.build(),
+            encoder: self.encoder,
+        }
+    }
+}
+
+impl<Value, E: PhoneticEncoder> PhoneticTrie<Value, E> {
+    /// Return the words that sound like `word` and their values.
+    pub fn phonetic_search(&self, word: &str) -> impl Iterator<Item = (&str, &Value)> {
+        self.trie
+            .exact_match(self.encoder.encode(word))
+            .into_iter()
+            .flatten()
+            .map(|(word, value)| (word.as_str(), value))
+    }
+
+    /// Return the words whose code starts with the code of `word`
+    /// truncated to `len` characters, e.g. `1` for words with the same
+    /// initial under [Soundex].
+    pub fn phonetic_prefix_search(
+        &self,
+        word: &str,
+        len: usize,
+    ) -> impl Iterator<Item = (&str, &Value)> {
+        let mut code = self.encoder.encode(word).into_bytes();
+        code.truncate(len);
+        self.trie
+            .predictive_search::<Vec<u8>, _>(code)
+            .flat_map(|(_, words)| words)
+            .map(|(word, value)| (word.as_str(), value))
+    }
+
+    /// Return the encoder.
+    pub fn encoder(&self) -> &E {
+        &self.encoder
+    }
+}
+
+#[cfg(test)]
+mod phonetic_tests {
+    use crate::map::{PhoneticEncoder, PhoneticTrieBuilder, Soundex};
+
+    macro_rules! parameterized_tests {
+        ($($name:ident: $value:expr,)*) => {
+        $(
+            #[test]
+            fn $name() {
+                let (word, expected) = $value;
+                assert_eq!(Soundex.encode(word), expected);
+            }
+        )*
+        }
+    }
+
+    parameterized_tests! {
+        t1: ("Robert", "R163"),
+        t2: ("Rupert", "R163"),
+        t3: ("Tymczak", "T522"),
+        t4: ("Pfister", "P236"),
+        t5: ("Ashcraft", "A261"),
+        t6: ("Lee", "L000"),
+        t7: ("", ""),
+        t8: ("O'Hara", "O600"),
+    }
+
+    #[test]
+    fn search() {
+        let mut builder = PhoneticTrieBuilder::new();
+        for (i, name) in ["Robert", "Rupert", "Rubin", "Smith"].iter().enumerate() {
+            builder.push(*name, i);
+        }
+        let trie = builder.build();
+        let results: Vec<&str> = trie.phonetic_search("Robbert").map(|(w, _)| w).collect();
+        assert_eq!(results, ["Robert", "Rupert"]);
+        let results: Vec<&str> = trie
+            .phonetic_prefix_search("R", 1)
+            .map(|(w, _)| w)
+            .collect();
+        assert_eq!(results, ["Rubin", "Robert", "Rupert"]);
+        assert_eq!(trie.phonetic_search("Zed").count(), 0);
+    }
+}
@@ -0,0 +1,127 @@
+use super::Trie;
+use crate::try_collect::TryFromIterator;
+use louds_rs::LoudsNodeNum;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+impl<Label: Ord + Clone, Value> Trie<Label, Value> {
+    /// Return up to `k` keys starting with `prefix`, ranked by how many keys
+    /// start with each of them, themselves included, with their values and
+    /// those counts. Gives ranked suggestions when there are no weights to
+    /// rank by: keys that many others extend, like common stems, come first.
+    /// See [super::ScoreIndex] to rank by weights instead.
+    ///
+    /// Ties are broken by breadth-first order, so shorter keys come first.
+    /// The subtree below `prefix` is counted once per call.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter(
+    ///     ["car", "card", "cards", "care", "cat", "cats", "cow"].map(|k| (k, ())),
+    /// );
+    /// let results: Vec<(String, usize)> = trie
+    ///     .popular_completions("c", 3)
+    ///     .into_iter()
+    ///     .map(|(key, _, count)| (key, count))
+    ///     .collect();
+    /// assert_eq!(
+    ///     results,
+    ///     [("car".to_string(), 4), ("cat".to_string(), 2), ("card".to_string(), 2)]
+    /// );
+    /// ```
+    pub fn popular_completions<C, M>(
+        &self,
+        prefix: impl AsRef<[Label]>,
+        k: usize,
+    ) -> Vec<(C, &Value, usize)>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        let Some(start) = self.walk(prefix.as_ref()) else {
+            return Vec::new();
+        };
+        if k == 0 {
+            return Vec::new();
+        }
+        // The subtree in breadth-first order, each node with its parent's
+        // position in it; parents come before their children.
+        let mut nodes: Vec<(LoudsNodeNum, usize)> = vec![(start, 0)];
+        let mut i = 0;
+        while i < nodes.len() {
+            let node = nodes[i].0;
+            nodes.extend(self.children_node_nums(node).map(|child| (child, i)));
+            i += 1;
+        }
+        let mut counts: Vec<usize> = nodes
+            .iter()
+            .map(|(node, _)| usize::from(self.is_terminal(*node)))
+            .collect();
+        for i in (1..nodes.len()).rev() {
+            counts[nodes[i].1] += counts[i];
+        }
+        let mut children: Vec<Vec<usize>> = vec![Vec::new(); nodes.len()];
+        for (i, (_, parent)) in nodes.iter().enumerate().skip(1) {
+            children[*parent].push(i);
+        }
+
+        // Counts only shrink going down, so keys come out best first.
+        let mut heap = BinaryHeap::from([(counts[0], Reverse(0))]);
+        let mut results = Vec::new();
+        while let Some((count, Reverse(i))) = heap.pop() {
+            let node = nodes[i].0;
+            if let Some(value) = self.value(node) {
+                results.push((self.prefix_of(node), value, count));
+                if results.len() == k {
+                    break;
+                }
+            }
+            heap.extend(
+                children[i]
+                    .iter()
+                    .map(|&child| (counts[child], Reverse(child))),
+            );
+        }
+        results
+    }
+}
+
+#[cfg(test)]
+mod popular_tests {
+    use crate::map::Trie;
+
+    #[test]
+    fn ranks_by_subtree_size() {
+        let trie = Trie::from_iter([
+            ("a", 0),
+            ("ab", 1),
+            ("abc", 2),
+            ("abd", 3),
+            ("b", 4),
+            ("bc", 5),
+        ]);
+        let ranked = |prefix: &str, k| -> Vec<(String, usize)> {
+            trie.popular_completions(prefix, k)
+                .into_iter()
+                .map(|(key, _, count)| (key, count))
+                .collect()
+        };
+        assert_eq!(
+            ranked("", 10),
+            [
+                ("a".to_string(), 4),
+                ("ab".to_string(), 3),
+                ("b".to_string(), 2),
+                ("bc".to_string(), 1),
+                ("abc".to_string(), 1),
+                ("abd".to_string(), 1)
+            ]
+        );
+        assert_eq!(
+            ranked("ab", 2),
+            [("ab".to_string(), 3), ("abc".to_string(), 1)]
+        );
+        assert_eq!(ranked("ab", 0), []);
+        assert_eq!(ranked("x", 3), []);
+    }
+}
@@ -0,0 +1,188 @@
+use super::Trie;
+use crate::try_collect::{TryCollect, TryFromIterator};
+use louds_rs::LoudsNodeNum;
+
+impl<Label: Ord + Clone, Value> Trie<Label, Value> {
+    /// Return a key starting with `prefix` made by walking down from it,
+    /// picking among the children, and stopping at a key, with equal chance
+    /// at each node, e.g. to make up names from a trie of real ones. See
+    /// [Trie::random_walk_weighted] to favor busier branches.
+    ///
+    /// `rng(n)` is called for each choice among `n` and its result is taken
+    /// modulo `n`, so any source of random numbers will do. Return `None` if
+    /// `prefix` is not a prefix of any key, or the walk reaches `max_len`
+    /// labels without ending on a key.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter(["mara", "marek", "mira", "miro", "nadia"].map(|k| (k, ())));
+    /// let mut state = 7u64;
+    /// let mut rng = |_| {
+    ///     state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+    ///     (state >> 33) as usize
+    /// };
+    /// for _ in 0..10 {
+    ///     let name: String = trie.random_walk("m", &mut rng, 10).unwrap();
+    ///     assert!(trie.exact_match(&name).is_some() && name.starts_with('m'));
+    /// }
+    /// assert_eq!(trie.random_walk::<String, _>("x", &mut rng, 10), None);
+    /// assert_eq!(trie.random_walk::<String, _>("ma", &mut rng, 3), None);
+    /// ```
+    pub fn random_walk<C, M>(
+        &self,
+        prefix: impl AsRef<[Label]>,
+        mut rng: impl FnMut(usize) -> usize,
+        max_len: usize,
+    ) -> Option<C>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        let prefix = prefix.as_ref();
+        let mut node = self.walk(prefix)?;
+        let mut key = prefix.to_vec();
+        loop {
+            let stop = usize::from(self.is_terminal(node));
+            let children = if key.len() < max_len {
+                self.children_node_nums(node).len()
+            } else {
+                0
+            };
+            let choices = stop + children;
+            if choices == 0 {
+                return None;
+            }
+            let choice = rng(choices) % choices;
+            if choice < stop {
+                return Some(key.into_iter().try_collect().expect("Could not collect"));
+            }
+            node = self.children_node_nums(node).nth(choice - stop)?;
+            key.push(self.label(node).clone());
+        }
+    }
+
+    /// Like [Trie::random_walk], but pick each child by how many keys start
+    /// with it, so every key starting with `prefix` is equally likely. The
+    /// keys below `prefix` are counted once per call.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter(["ann", "anna", "anne", "bo"].map(|k| (k, ())));
+    /// // The first choice is among 4 keys: 3 start with "a".
+    /// let mut choices = Vec::new();
+    /// let name: Option<String> = trie.random_walk_weighted(
+    ///     "",
+    ///     |n| {
+    ///         choices.push(n);
+    ///         0
+    ///     },
+    ///     10,
+    /// );
+    /// assert_eq!(name.as_deref(), Some("ann"));
+    /// assert_eq!(choices, [4, 3, 3, 3]);
+    /// ```
+    pub fn random_walk_weighted<C, M>(
+        &self,
+        prefix: impl AsRef<[Label]>,
+        mut rng: impl FnMut(usize) -> usize,
+        max_len: usize,
+    ) -> Option<C>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        let prefix = prefix.as_ref();
+        let start = self.walk(prefix)?;
+        // The subtree in breadth-first order, with each node's first child's
+        // position in it and the number of keys at or below it.
+        let mut nodes: Vec<(LoudsNodeNum, usize, usize)> = vec![(start, 0, 0)];
+        let mut i = 0;
+        while i < nodes.len() {
+            let node = nodes[i].0;
+            nodes[i].1 = nodes.len();
+            nodes[i].2 = usize::from(self.is_terminal(node));
+            nodes.extend(self.children_node_nums(node).map(|child| (child, 0, 0)));
+            i += 1;
+        }
+        for i in (0..nodes.len()).rev() {
+            let (node, first, _) = nodes[i];
+            let below: usize = (first..first + self.children_node_nums(node).len())
+                .map(|child| nodes[child].2)
+                .sum();
+            nodes[i].2 += below;
+        }
+
+        let mut i = 0;
+        let mut key = prefix.to_vec();
+        loop {
+            let (node, first, count) = nodes[i];
+            let stop = usize::from(self.is_terminal(node));
+            let children = if key.len() < max_len {
+                self.children_node_nums(node).len()
+            } else {
+                0
+            };
+            let total = if children > 0 { count } else { stop };
+            if total == 0 {
+                return None;
+            }
+            let mut choice = rng(total) % total;
+            if choice < stop {
+                return Some(key.into_iter().try_collect().expect("Could not collect"));
+            }
+            choice -= stop;
+            i = first;
+            while choice >= nodes[i].2 {
+                choice -= nodes[i].2;
+                i += 1;
+            }
+            key.push(self.label(nodes[i].0).clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod random_walk_tests {
+    use crate::map::Trie;
+    use rand::{Rng, SeedableRng};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn walks_end_on_keys() {
+        let keys = ["a", "ab", "abc", "abd", "b", "bcd", "bce", "アップル"];
+        let trie = Trie::from_iter(keys.map(|k| (k, ())));
+        let mut rng = rand::rngs::StdRng::seed_from_u64(998);
+        let mut uniform = BTreeMap::new();
+        let mut weighted = BTreeMap::new();
+        for _ in 0..4000 {
+            let key: String = trie.random_walk("", |n| rng.gen_range(0, n), 20).unwrap();
+            *uniform.entry(key).or_insert(0) += 1;
+            let key: String = trie
+                .random_walk_weighted("", |n| rng.gen_range(0, n), 20)
+                .unwrap();
+            *weighted.entry(key).or_insert(0) += 1;
+        }
+        assert_eq!(uniform.len(), keys.len());
+        assert_eq!(weighted.len(), keys.len());
+        // The root has 3 children; "a" then stops with chance 1/2.
+        assert!((500..830).contains(&uniform["a"]), "{:?}", uniform);
+        assert!(
+            weighted.values().all(|n| (350..650).contains(n)),
+            "{:?}",
+            weighted
+        );
+
+        // Walks longer than max_len fail; the prefix counts toward it.
+        for _ in 0..100 {
+            let key: Option<String> = trie.random_walk("b", |n| rng.gen_range(0, n), 1);
+            assert_eq!(key.as_deref(), Some("b"));
+            let key: Option<String> = trie.random_walk_weighted("bc", |n| rng.gen_range(0, n), 2);
+            assert_eq!(key, None);
+            let key: Option<Vec<u8>> = trie.random_walk_weighted("abd", |_| usize::MAX, 0);
+            assert_eq!(key.as_deref(), Some(&b"abd"[..]));
+        }
+        let empty = Trie::<u8, ()>::from_iter(Vec::<(&str, ())>::new());
+        assert_eq!(empty.random_walk::<String, _>("", |_| 0, 10), None);
+        assert_eq!(empty.random_walk_weighted::<String, _>("", |_| 0, 10), None);
+    }
+}
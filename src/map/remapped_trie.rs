@@ -0,0 +1,298 @@
+use super::Trie;
+use crate::try_collect::{Collect, TryCollect, TryFromIterator};
+use louds_rs::LoudsNodeNum;
+
+#[cfg(feature = "mem_dbg")]
+use mem_dbg::MemDbg;
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A trie map that stores each distinct label once, in its alphabet, and
+/// labels nodes with their index into it. Queries and results still use
+/// `Label`.
+///
+/// It pays off for wide labels repeated on many nodes, e.g. word-level tries
+/// with `String` labels, where each node then holds a `u32` instead. The
+/// alphabet is sorted, so indices compare like the labels they stand for and
+/// results come in the same order as from the [Trie] it was made from.
+///
+/// ```rust
+/// use trie_rs::map::Trie;
+///
+/// let words = |s: &str| s.split(' ').map(String::from).collect::<Vec<_>>();
+/// let trie = Trie::from_iter([
+///     (words("to be or not to be"), 0),
+///     (words("to be continued"), 1),
+/// ]).remap_alphabet();
+/// assert_eq!(trie.alphabet(), ["be", "continued", "not", "or", "to"]);
+/// assert_eq!(trie.label_counts(), [2, 1, 1, 1, 2]);
+///
+/// assert_eq!(trie.exact_match(words("to be continued")), Some(&1));
+/// let results: Vec<(Vec<String>, &u8)> = trie.predictive_search(words("to be")).collect();
+/// assert_eq!(results[0].0, words("to be continued"));
+/// ```
+pub struct RemappedTrie<Label, Value> {
+    trie: Trie<u32, Value>,
+    /// Distinct labels in order.
+    alphabet: Vec<Label>,
+    /// Number of nodes labeled with each label of `alphabet`.
+    counts: Vec<usize>,
+}
+
+impl<Label: Ord + Clone, Value> Trie<Label, Value> {
+    /// Move the labels into an alphabet of distinct labels and relabel the
+    /// nodes with indices into it, keeping the trie's layout and values. See
+    /// [RemappedTrie].
+    ///
+    /// Label runs are dropped; build them again on [RemappedTrie::trie] if
+    /// needed.
+    ///
+    /// # Panics
+    /// If there are more than `u32::MAX` distinct labels.
+    pub fn remap_alphabet(self) -> RemappedTrie<Label, Value> {
+        let mut alphabet = self.trie_labels.clone();
+        alphabet.sort();
+        alphabet.dedup();
+        assert!(
+            alphabet.len() <= u32::MAX as usize,
+            "Too many distinct labels"
+        );
+        let mut counts = vec![0; alphabet.len()];
+        let trie_labels = self
+            .trie_labels
+            .iter()
+            .map(|label| {
+                let i = alphabet.binary_search(label).unwrap();
+                counts[i] += 1;
+                i as u32
+            })
+            .collect();
+        // Only the labels change, so the caches derived from the nodes carry
+        // over rather than being computed again by Trie::from_parts.
+        let trie = Trie {
+            louds: self.louds,
+            trie_labels,
+            values: self.values,
+            runs: None,
+            insertion_order: self.insertion_order,
+            key_lens: self.key_lens,
+            tiny: self.tiny,
+        };
+        RemappedTrie {
+            trie,
+            alphabet,
+            counts,
+        }
+    }
+}
+
+impl<Label: Ord + Clone, Value> RemappedTrie<Label, Value> {
+    /// Return the distinct labels, in order.
+    pub fn alphabet(&self) -> &[Label] {
+        &self.alphabet
+    }
+
+    /// Return how many nodes carry each label of [RemappedTrie::alphabet].
+    pub fn label_counts(&self) -> &[usize] {
+        &self.counts
+    }
+
+    /// Return the trie of label indices underneath.
+    pub fn trie(&self) -> &Trie<u32, Value> {
+        &self.trie
+    }
+
+    /// Return the trie of label indices underneath, e.g. to call
+    /// [Trie::build_label_runs] on it.
+    pub fn trie_mut(&mut self) -> &mut Trie<u32, Value> {
+        &mut self.trie
+    }
+
+    /// Return the label indices of `query`, or `None` if a label of it is not
+    /// in the alphabet, so nothing can match past it.
+    fn encode(&self, query: &[Label]) -> Option<Vec<u32>> {
+        query
+            .iter()
+            .map(|label| self.alphabet.binary_search(label).ok().map(|i| i as u32))
+            .collect()
+    }
+
+    fn decode<C, M>(&self, key: Vec<u32>) -> C
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        key.into_iter()
+            .map(|i| self.alphabet[i as usize].clone())
+            .try_collect()
+            .expect("Could not collect")
+    }
+
+    /// Return `Some(&Value)` if query is an exact match.
+    pub fn exact_match(&self, query: impl AsRef<[Label]>) -> Option<&Value> {
+        self.trie.exact_match(self.encode(query.as_ref())?)
+    }
+
+    /// Return true if `query` is a key. Labels are mapped one at a time, so
+    /// unlike [RemappedTrie::exact_match] this does not allocate. See
+    /// [Trie::contains_key].
+    pub fn contains_key(&self, query: impl AsRef<[Label]>) -> bool {
+        let mut node = LoudsNodeNum(1);
+        for label in query.as_ref() {
+            let child = match self.alphabet.binary_search(label) {
+                Ok(i) => self.trie.child(node, &(i as u32)),
+                Err(_) => None,
+            };
+            match child {
+                Some(child) => node = child,
+                None => return false,
+            }
+        }
+        self.trie.is_terminal(node)
+    }
+
+    /// Return true if `query` is a prefix.
+    pub fn is_prefix(&self, query: impl AsRef<[Label]>) -> bool {
+        match self.encode(query.as_ref()) {
+            Some(query) => self.trie.is_prefix(query),
+            None => false,
+        }
+    }
+
+    /// Return all entries and their values that match `query`.
+    pub fn predictive_search<'a, C, M>(
+        &'a self,
+        query: impl AsRef<[Label]>,
+    ) -> impl Iterator<Item = (C, &'a Value)> + 'a
+    where
+        C: TryFromIterator<Label, M> + 'a,
+        M: 'a,
+    {
+        self.encode(query.as_ref())
+            .into_iter()
+            .flat_map(move |query| self.trie.predictive_search::<Vec<u32>, Collect>(query))
+            .map(move |(key, value)| (self.decode(key), value))
+    }
+
+    /// Return the postfixes and values of all entries that match `query`.
+    pub fn postfix_search<'a, C, M>(
+        &'a self,
+        query: impl AsRef<[Label]>,
+    ) -> impl Iterator<Item = (C, &'a Value)> + 'a
+    where
+        C: TryFromIterator<Label, M> + 'a,
+        M: 'a,
+    {
+        self.encode(query.as_ref())
+            .into_iter()
+            .flat_map(move |query| self.trie.postfix_search::<Vec<u32>, Collect>(query))
+            .map(move |(key, value)| (self.decode(key), value))
+    }
+
+    /// Return all entries and their values, in lexicographic order.
+    pub fn iter<'a, C, M>(&'a self) -> impl Iterator<Item = (C, &'a Value)> + 'a
+    where
+        C: TryFromIterator<Label, M> + 'a,
+        M: 'a,
+    {
+        self.postfix_search([])
+    }
+
+    /// Return the common prefixes of `query`.
+    pub fn common_prefix_search<'a, C, M>(
+        &'a self,
+        query: impl AsRef<[Label]>,
+    ) -> impl Iterator<Item = (C, &'a Value)> + 'a
+    where
+        C: TryFromIterator<Label, M> + 'a,
+        M: 'a,
+    {
+        let query = query.as_ref();
+        // Labels up to the first one outside the alphabet may still match.
+        let known: Vec<u32> = query
+            .iter()
+            .map_while(|label| self.alphabet.binary_search(label).ok().map(|i| i as u32))
+            .collect();
+        self.trie
+            .common_prefix_search::<Vec<u32>, Collect>(known)
+            .map(move |(key, value)| (self.decode(key), value))
+    }
+
+    /// Return the longest shared prefix or terminal of `query`.
+    pub fn longest_prefix<C, M>(&self, query: impl AsRef<[Label]>) -> Option<C>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        self.trie
+            .longest_prefix::<Vec<u32>, Collect>(self.encode(query.as_ref())?)
+            .map(|key| self.decode(key))
+    }
+}
+
+#[cfg(test)]
+mod remapped_trie_tests {
+    use crate::map::Trie;
+
+    fn build_trie() -> Trie<u8, u8> {
+        Trie::from_iter([
+            ("a", 0),
+            ("app", 1),
+            ("apple", 2),
+            ("better", 3),
+            ("application", 4),
+            ("アップル🍎", 5),
+        ])
+    }
+
+    #[test]
+    fn same_results() {
+        let trie = build_trie();
+        let remapped = build_trie().remap_alphabet();
+        assert_eq!(
+            remapped.label_counts().iter().sum::<usize>(),
+            trie.trie_labels.len()
+        );
+        for query in [
+            "",
+            "a",
+            "app",
+            "appl",
+            "apple",
+            "b",
+            "z",
+            "アップ",
+            "applez",
+        ] {
+            assert_eq!(
+                remapped.exact_match(query),
+                trie.exact_match(query),
+                "{}",
+                query
+            );
+            assert_eq!(
+                remapped.is_prefix(query),
+                trie.is_prefix(query),
+                "{}",
+                query
+            );
+            let expected: Vec<(String, &u8)> = trie.predictive_search(query).collect();
+            let results: Vec<(String, &u8)> = remapped.predictive_search(query).collect();
+            assert_eq!(results, expected, "{}", query);
+            let expected: Vec<(String, &u8)> = trie.postfix_search(query).collect();
+            let results: Vec<(String, &u8)> = remapped.postfix_search(query).collect();
+            assert_eq!(results, expected, "{}", query);
+            let expected: Vec<(String, &u8)> = trie.common_prefix_search(query).collect();
+            let results: Vec<(String, &u8)> = remapped.common_prefix_search(query).collect();
+            assert_eq!(results, expected, "{}", query);
+            assert_eq!(
+                remapped.longest_prefix::<String, _>(query),
+                trie.longest_prefix::<String, _>(query),
+                "{}",
+                query
+            );
+        }
+        let expected: Vec<(String, &u8)> = trie.iter().collect();
+        let results: Vec<(String, &u8)> = remapped.iter().collect();
+        assert_eq!(results, expected);
+    }
+}
@@ -0,0 +1,79 @@
+use super::{Trie, TrieBuilder};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Error of [Trie::set_value] for a query that is not a key.
+pub struct NotAKey;
+
+impl fmt::Display for NotAKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "not a key of the trie")
+    }
+}
+
+impl std::error::Error for NotAKey {}
+
+impl<Label: Ord + Clone, Value: Default> TrieBuilder<Label, Value> {
+    /// Add a key with a default value, to be set once the trie is built with
+    /// [Trie::set_value]. Keys can then be loaded from one source and values
+    /// from another without joining the two first; a `Value` of
+    /// `Option<V>` tells reserved slots from filled ones.
+    ///
+    /// ```rust
+    /// use trie_rs::map::{NotAKey, TrieBuilder};
+    ///
+    /// let mut builder = TrieBuilder::new();
+    /// for key in "apple\napply\nbanana".lines() {
+    ///     builder.reserve(key);
+    /// }
+    /// let mut trie = builder.build();
+    /// for (key, price) in [("banana", 30), ("apple", 120)] {
+    ///     trie.set_value(key, Some(price)).unwrap();
+    /// }
+    /// assert_eq!(trie.set_value("cherry", Some(500)), Err(NotAKey));
+    /// assert_eq!(trie.exact_match("apple"), Some(&Some(120)));
+    /// assert_eq!(trie.exact_match("apply"), Some(&None));
+    /// ```
+    pub fn reserve<Arr: AsRef<[Label]>>(&mut self, key: Arr) {
+        self.push(key, Value::default());
+    }
+}
+
+impl<Label: Ord, Value> Trie<Label, Value> {
+    /// Set the value of `key`, which must already be a key of the trie, e.g.
+    /// one added by [TrieBuilder::reserve]. Keys cannot be added this way.
+    pub fn set_value(&mut self, key: impl AsRef<[Label]>, value: Value) -> Result<(), NotAKey> {
+        let slot = self.exact_match_mut(key).ok_or(NotAKey)?;
+        *slot = value;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod reserved_values_tests {
+    use crate::map::{NotAKey, TrieBuilder};
+
+    #[test]
+    fn two_phase_load() {
+        let mut builder = TrieBuilder::<char, u32>::new();
+        for key in ["a", "ab", "b"] {
+            builder.reserve(key.chars().collect::<Vec<_>>());
+        }
+        let mut trie = builder.build();
+        assert_eq!(trie.exact_match(['a', 'b']), Some(&0));
+        trie.set_value(['a', 'b'], 7).unwrap();
+        trie.set_value(['a', 'b'], 8).unwrap();
+        assert_eq!(trie.set_value([], 1), Err(NotAKey));
+        assert_eq!(trie.set_value(['a', 'c'], 1), Err(NotAKey));
+        let values: Vec<(String, &u32)> = trie.iter().collect();
+        assert_eq!(
+            values,
+            [
+                ("a".to_string(), &0),
+                ("ab".to_string(), &8),
+                ("b".to_string(), &0)
+            ]
+        );
+        assert_eq!(NotAKey.to_string(), "not a key of the trie");
+    }
+}
@@ -0,0 +1,144 @@
+use super::Trie;
+use louds_rs::LoudsNodeNum;
+use std::collections::VecDeque;
+use std::ops::Range;
+
+#[derive(Debug, Clone)]
+/// Iterates through the keys found anywhere in a text, with their spans. See
+/// [Trie::scan].
+///
+/// Spans index the text's labels, so for a `u8` trie scanning UTF-8 text they
+/// are byte offsets.
+pub struct Scan<'a, 't, Label, Value> {
+    trie: &'a Trie<Label, Value>,
+    text: &'t [Label],
+    start: usize,
+    overlapping: bool,
+    pending: VecDeque<(Range<usize>, &'a Value)>,
+}
+
+impl<'a, 't, Label: Ord, Value> Scan<'a, 't, Label, Value> {
+    pub(crate) fn new(trie: &'a Trie<Label, Value>, text: &'t [Label]) -> Self {
+        Self {
+            trie,
+            text,
+            start: 0,
+            overlapping: true,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Report only the longest key at the leftmost position, then continue
+    /// after it, so no two spans overlap.
+    ///
+    /// Call it before iterating.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("he", 0), ("hers", 1), ("she", 2)]);
+    /// let all: Vec<_> = trie.scan("ushers").map(|(span, _)| span).collect();
+    /// assert_eq!(all, [1..4, 2..4, 2..6]);
+    /// let longest: Vec<_> = trie.scan("ushers").non_overlapping().map(|(span, _)| span).collect();
+    /// assert_eq!(longest, [1..4]);
+    /// ```
+    pub fn non_overlapping(mut self) -> Self {
+        self.overlapping = false;
+        self
+    }
+
+    /// Queue the keys starting at `self.start`, shortest first.
+    fn match_at_start(&mut self) {
+        let mut node = LoudsNodeNum(1);
+        for (end, label) in self.text.iter().enumerate().skip(self.start) {
+            match self.trie.child(node, label) {
+                Some(child) => node = child,
+                None => break,
+            }
+            if let Some(value) = self.trie.value(node) {
+                if !self.overlapping {
+                    self.pending.clear();
+                }
+                self.pending.push_back((self.start..end + 1, value));
+            }
+        }
+    }
+}
+
+impl<'a, Label: Ord, Value> Iterator for Scan<'a, '_, Label, Value> {
+    type Item = (Range<usize>, &'a Value);
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pending.is_empty() && self.start < self.text.len() {
+            self.match_at_start();
+            self.start = match self.pending.back() {
+                Some((span, _)) if !self.overlapping => span.end,
+                _ => self.start + 1,
+            };
+        }
+        self.pending.pop_front()
+    }
+}
+
+impl<Label: Ord, Value> Trie<Label, Value> {
+    /// Return every occurrence of a key in `text` with its span, ordered by
+    /// start, then by length. See [Scan::non_overlapping] for leftmost-longest
+    /// matches instead.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("東京", 0), ("京都", 1)]);
+    /// let text = "東京都";
+    /// let hits: Vec<(&str, &u8)> = trie.scan(text).map(|(span, v)| (&text[span], v)).collect();
+    /// assert_eq!(hits, [("東京", &0), ("京都", &1)]);
+    /// ```
+    pub fn scan<'a, 't>(
+        &'a self,
+        text: &'t (impl AsRef<[Label]> + ?Sized),
+    ) -> Scan<'a, 't, Label, Value> {
+        Scan::new(self, text.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod scan_tests {
+    use crate::map::Trie;
+    use std::ops::Range;
+
+    fn build_trie() -> Trie<u8, u8> {
+        Trie::from_iter([("a", 0), ("ab", 1), ("abc", 2), ("bc", 3), ("c", 4)])
+    }
+
+    #[test]
+    fn overlapping() {
+        let trie = build_trie();
+        let spans: Vec<(Range<usize>, &u8)> = trie.scan("xabcab").collect();
+        assert_eq!(
+            spans,
+            [
+                (1..2, &0),
+                (1..3, &1),
+                (1..4, &2),
+                (2..4, &3),
+                (3..4, &4),
+                (4..5, &0),
+                (4..6, &1)
+            ]
+        );
+        assert_eq!(trie.scan("").count(), 0);
+        assert_eq!(trie.scan("xyz").count(), 0);
+    }
+
+    #[test]
+    fn non_overlapping() {
+        let trie = build_trie();
+        let spans: Vec<Range<usize>> = trie
+            .scan("xabcab")
+            .non_overlapping()
+            .map(|(s, _)| s)
+            .collect();
+        assert_eq!(spans, [1..4, 4..6]);
+        let spans: Vec<Range<usize>> = trie.scan("bcc").non_overlapping().map(|(s, _)| s).collect();
+        assert_eq!(spans, [0..2, 2..3]);
+    }
+}
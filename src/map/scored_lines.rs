@@ -0,0 +1,129 @@
+use super::TrieBuilder;
+use std::fmt;
+use std::io::{self, BufRead};
+use std::str::FromStr;
+
+#[derive(Debug)]
+/// Error of [TrieBuilder::push_scored_lines]. Lines are numbered from 1.
+pub enum ScoredLinesError<E> {
+    /// Reading failed, including on invalid UTF-8.
+    Io(io::Error),
+    /// The line has no separator.
+    MissingSeparator {
+        /// Line number.
+        line: usize,
+    },
+    /// The score after the separator could not be parsed.
+    InvalidScore {
+        /// Line number.
+        line: usize,
+        /// Error parsing the score.
+        error: E,
+    },
+}
+
+impl<E: fmt::Display> fmt::Display for ScoredLinesError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScoredLinesError::Io(e) => write!(f, "could not read scored lines: {}", e),
+            ScoredLinesError::MissingSeparator { line } => {
+                write!(f, "line {}: missing separator", line)
+            }
+            ScoredLinesError::InvalidScore { line, error } => {
+                write!(f, "line {}: invalid score: {}", line, error)
+            }
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> std::error::Error for ScoredLinesError<E> {}
+
+impl<Value: FromStr> TrieBuilder<u8, Value> {
+    /// Push one `word<separator>score` entry per line of `reader`, e.g. the
+    /// lines of a tab-separated frequency dictionary. Return the number of
+    /// entries pushed.
+    ///
+    /// The score follows the last separator, so words may contain it. A
+    /// trailing `\r` is ignored and blank lines are skipped. On error the
+    /// entries of the lines before it stay pushed.
+    ///
+    /// ```rust
+    /// use trie_rs::map::{ScoredLinesError, TrieBuilder};
+    ///
+    /// let mut builder = TrieBuilder::new();
+    /// let lines = "the\t23135851162\nof\t13151942776\n\nice cream\t1000\n";
+    /// assert_eq!(builder.push_scored_lines(lines.as_bytes(), '\t').unwrap(), 3);
+    /// let trie = builder.build();
+    /// assert_eq!(trie.exact_match("ice cream"), Some(&1000u64));
+    ///
+    /// let mut builder = TrieBuilder::<u8, u64>::new();
+    /// let err = builder.push_scored_lines("a\t1\nb\tmany\n".as_bytes(), '\t').unwrap_err();
+    /// assert!(matches!(err, ScoredLinesError::InvalidScore { line: 2, .. }));
+    /// ```
+    pub fn push_scored_lines(
+        &mut self,
+        reader: impl BufRead,
+        separator: char,
+    ) -> Result<usize, ScoredLinesError<Value::Err>> {
+        let mut pushed = 0;
+        for (i, line) in reader.lines().enumerate() {
+            let line = line.map_err(ScoredLinesError::Io)?;
+            let line = line.strip_suffix('\r').unwrap_or(&line);
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (word, score) = line
+                .rsplit_once(separator)
+                .ok_or(ScoredLinesError::MissingSeparator { line: i + 1 })?;
+            let score = score
+                .trim()
+                .parse()
+                .map_err(|error| ScoredLinesError::InvalidScore { line: i + 1, error })?;
+            self.push(word, score);
+            pushed += 1;
+        }
+        Ok(pushed)
+    }
+}
+
+#[cfg(test)]
+mod scored_lines_tests {
+    use crate::map::{ScoredLinesError, TrieBuilder};
+
+    #[test]
+    fn parses() {
+        let mut builder = TrieBuilder::new();
+        let lines = "apple 3\r\nan apple 0.5\n  \napple 4\n";
+        assert_eq!(builder.push_scored_lines(lines.as_bytes(), ' ').unwrap(), 3);
+        let trie = builder.build();
+        assert_eq!(trie.exact_match("apple"), Some(&4.0f32));
+        assert_eq!(trie.exact_match("an apple"), Some(&0.5));
+    }
+
+    #[test]
+    fn errors() {
+        let mut builder = TrieBuilder::<u8, u32>::new();
+        let err = builder
+            .push_scored_lines("a,1\nb\n".as_bytes(), ',')
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ScoredLinesError::MissingSeparator { line: 2 }
+        ));
+        assert_eq!(err.to_string(), "line 2: missing separator");
+
+        let err = builder
+            .push_scored_lines(&[b'a', 0xff, b',', b'1'][..], ',')
+            .unwrap_err();
+        assert!(matches!(err, ScoredLinesError::Io(_)));
+
+        let err = builder
+            .push_scored_lines("a,-1\n".as_bytes(), ',')
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1: invalid score: invalid digit found in string"
+        );
+        assert_eq!(builder.build().exact_match("a"), Some(&1));
+    }
+}
@@ -0,0 +1,141 @@
+use super::{NodeValues, Trie, TrieBuilder};
+use louds_rs::{Louds, LoudsNodeNum};
+use std::collections::VecDeque;
+
+impl<Label: Ord + Clone, Value> Trie<Label, Value> {
+    /// Split the trie by the first `depth` labels of its keys, e.g. to spread
+    /// a dictionary across machines. Return each prefix with a trie of the
+    /// keys that start with it, in lexicographic order of the prefixes.
+    ///
+    /// Keys shorter than `depth` get a shard of their own, keyed by the whole
+    /// key. A `depth` of 0 gives the whole trie as one shard.
+    ///
+    /// Shards keep full keys, so they answer queries like the original for
+    /// keys under their prefix. Each shard is copied node by node from the
+    /// trie, without collecting keys and building it again.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("a", 0), ("app", 1), ("apple", 2), ("bet", 3), ("by", 4)]);
+    /// let shards = trie.shard_by_prefix(2);
+    /// let prefixes: Vec<&[u8]> = shards.iter().map(|(prefix, _)| &prefix[..]).collect();
+    /// assert_eq!(prefixes, [&b"a"[..], b"ap", b"be", b"by"]);
+    /// assert_eq!(shards[1].1.exact_match("apple"), Some(&2));
+    /// assert_eq!(shards[1].1.exact_match("a"), None);
+    ///
+    /// let merged = Trie::merge_shards(shards.into_iter().map(|(_, shard)| shard));
+    /// assert_eq!(merged.iter::<String, _>().count(), 5);
+    /// ```
+    pub fn shard_by_prefix(mut self, depth: usize) -> Vec<(Vec<Label>, Trie<Label, Value>)> {
+        if depth == 0 {
+            return vec![(Vec::new(), self)];
+        }
+        let mut shards = Vec::new();
+        // Paths from the root, depth first so prefixes come out in order.
+        let mut stack = vec![vec![LoudsNodeNum(1)]];
+        while let Some(path) = stack.pop() {
+            let node = *path.last().unwrap();
+            let whole = path.len() - 1 == depth;
+            if whole || (node.0 > 1 && self.is_terminal(node)) {
+                shards.push(self.copy_shard(&path, whole));
+            }
+            if !whole {
+                let children: Vec<_> = self.children_node_nums(node).collect();
+                for child in children.into_iter().rev() {
+                    let mut path = path.clone();
+                    path.push(child);
+                    stack.push(path);
+                }
+            }
+        }
+        shards
+    }
+
+    /// Copy the nodes of `path` into a new trie, with the subtree below its
+    /// last node if `with_subtree`, moving only that node's and the subtree's
+    /// values over.
+    fn copy_shard(
+        &mut self,
+        path: &[LoudsNodeNum],
+        with_subtree: bool,
+    ) -> (Vec<Label>, Trie<Label, Value>) {
+        // The super root, then the root with its one child.
+        let mut louds_bits = vec![true, false, true, false];
+        let mut trie_labels = Vec::new();
+        let mut values = NodeValues::default();
+        let prefix: Vec<Label> = path[1..]
+            .iter()
+            .map(|node| self.label(*node).clone())
+            .collect();
+        // The path has one node per level, so it comes first breadth first.
+        for &node in &path[1..path.len() - 1] {
+            louds_bits.extend([true, false]);
+            trie_labels.push(self.label(node).clone());
+            values.push(None);
+        }
+        let mut queue = VecDeque::from([*path.last().unwrap()]);
+        while let Some(node) = queue.pop_front() {
+            trie_labels.push(self.label(node).clone());
+            values.push(self.values.take(node.0 as usize - 2));
+            if with_subtree {
+                for child in self.children_node_nums(node) {
+                    louds_bits.push(true);
+                    queue.push_back(child);
+                }
+            }
+            louds_bits.push(false);
+        }
+        let trie = Trie::from_parts(Louds::from(&louds_bits[..]), trie_labels, values, None);
+        (prefix, trie)
+    }
+
+    /// Merge tries, e.g. the shards from [Trie::shard_by_prefix], into one.
+    /// If a key is in several, the value from the last one is kept.
+    pub fn merge_shards(shards: impl IntoIterator<Item = Trie<Label, Value>>) -> Self {
+        let mut builder = TrieBuilder::new();
+        for shard in shards {
+            for (key, value) in shard.into_entries() {
+                builder.insert(key, value);
+            }
+        }
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+mod shards_tests {
+    use crate::map::Trie;
+
+    fn build_trie() -> Trie<u8, u8> {
+        Trie::from_iter([
+            ("a", 0),
+            ("app", 1),
+            ("apple", 2),
+            ("application", 3),
+            ("better", 4),
+            ("アップル🍎", 5),
+        ])
+    }
+
+    #[test]
+    fn shard_and_merge() {
+        for depth in 0..8 {
+            let shards = build_trie().shard_by_prefix(depth);
+            for (prefix, shard) in &shards {
+                assert_eq!(prefix.len().min(depth), prefix.len());
+                for (key, value) in shard.iter::<Vec<u8>, _>() {
+                    assert!(key.starts_with(prefix), "{:?}", depth);
+                    assert_eq!(build_trie().exact_match(&key), Some(value));
+                }
+                assert!(shard.canonical_eq(&shard.clone().rebuild()));
+            }
+            let merged = Trie::merge_shards(shards.into_iter().map(|(_, shard)| shard));
+            assert!(merged.canonical_eq(&build_trie()), "{}", depth);
+        }
+        let shards = build_trie().shard_by_prefix(1);
+        let prefixes: Vec<&[u8]> = shards.iter().map(|(p, _)| &p[..]).collect();
+        assert_eq!(prefixes, [&b"a"[..], b"b", &[0xe3]]);
+        assert_eq!(build_trie().shard_by_prefix(0).len(), 1);
+    }
+}
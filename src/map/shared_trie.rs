@@ -0,0 +1,309 @@
+use super::{NodeRef, Trie};
+use crate::try_collect::{TryCollect, TryFromIterator};
+use louds_rs::LoudsNodeNum;
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::sync::Arc;
+
+#[derive(Debug)]
+/// A [Trie] behind an [Arc], so cloning it is O(1).
+///
+/// Searches go through [Deref]. Mutation copies the trie first if any other
+/// clone still shares it.
+///
+/// ```rust
+/// use trie_rs::map::{SharedTrie, Trie};
+///
+/// let trie = SharedTrie::from(Trie::from_iter([("app", 1), ("apple", 2)]));
+/// let workers: Vec<_> = (0..4)
+///     .map(|_| {
+///         let trie = trie.clone();
+///         std::thread::spawn(move || *trie.exact_match("apple").unwrap())
+///     })
+///     .collect();
+/// for worker in workers {
+///     assert_eq!(worker.join().unwrap(), 2);
+/// }
+///
+/// let mut copy = trie.clone();
+/// *copy.exact_match_mut("app").unwrap() = 10;
+/// assert_eq!(trie.exact_match("app"), Some(&1));
+/// assert_eq!(copy.exact_match("app"), Some(&10));
+/// ```
+pub struct SharedTrie<Label, Value>(Arc<Trie<Label, Value>>);
+
+impl<Label, Value> Clone for SharedTrie<Label, Value> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<Label, Value> From<Trie<Label, Value>> for SharedTrie<Label, Value> {
+    fn from(trie: Trie<Label, Value>) -> Self {
+        Self(Arc::new(trie))
+    }
+}
+
+impl<Label, Value> Deref for SharedTrie<Label, Value> {
+    type Target = Trie<Label, Value>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<Label, Value> SharedTrie<Label, Value> {
+    /// Return true if both share the same trie.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl<Label: Ord + Clone, Value: Clone> SharedTrie<Label, Value> {
+    /// Return the trie for mutation, copying it first if it is shared.
+    pub fn make_mut(&mut self) -> &mut Trie<Label, Value> {
+        Arc::make_mut(&mut self.0)
+    }
+
+    /// Return `Some(&mut Value)` if query is an exact match, copying the trie
+    /// first if it is shared.
+    pub fn exact_match_mut(&mut self, query: impl AsRef<[Label]>) -> Option<&mut Value> {
+        self.make_mut().exact_match_mut(query)
+    }
+
+    /// Return the trie, copying it if it is still shared.
+    pub fn into_trie(self) -> Trie<Label, Value> {
+        Arc::try_unwrap(self.0).unwrap_or_else(|shared| (*shared).clone())
+    }
+}
+
+impl<Label: Ord + Clone, Value> SharedTrie<Label, Value> {
+    /// Like [Trie::predictive_search], but the iterator and its values hold
+    /// a clone of this handle instead of borrowing it, so they are `'static`
+    /// when the labels and values are, and may be kept in a future or sent to
+    /// another thread.
+    ///
+    /// ```rust
+    /// use trie_rs::map::{SharedTrie, Trie};
+    ///
+    /// let trie = SharedTrie::from(Trie::from_iter([("app", 1), ("apple", 2), ("bet", 3)]));
+    /// let results = trie.predictive_search_owned::<String, _>("app");
+    /// drop(trie);
+    /// let results: Vec<(String, u8)> = std::thread::spawn(move || {
+    ///     results.map(|(key, value)| (key, *value)).collect()
+    /// })
+    /// .join()
+    /// .unwrap();
+    /// assert_eq!(results, [("app".to_string(), 1), ("apple".to_string(), 2)]);
+    /// ```
+    pub fn predictive_search_owned<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> SharedSearchIter<Label, Value, C, M> {
+        let query = query.as_ref();
+        let stack = match self.walk(query) {
+            Some(node) => vec![(query.len(), node)],
+            None => Vec::new(),
+        };
+        SharedSearchIter {
+            trie: self.clone(),
+            stack,
+            path: query.to_vec(),
+            query_len: query.len(),
+            col: PhantomData,
+        }
+    }
+
+    /// Return all entries in lexicographic order, holding a clone of this
+    /// handle. See [SharedTrie::predictive_search_owned].
+    pub fn iter_owned<C, M>(&self) -> SharedSearchIter<Label, Value, C, M> {
+        self.predictive_search_owned([])
+    }
+
+    /// Like [Trie::node], but the node holds a clone of this handle instead
+    /// of borrowing it.
+    pub fn node_owned(&self, query: impl AsRef<[Label]>) -> Option<SharedNode<Label, Value>> {
+        let node = self.walk(query.as_ref())?;
+        Some(SharedNode {
+            trie: self.clone(),
+            node,
+        })
+    }
+}
+
+#[derive(Debug)]
+/// A value of a [SharedTrie] that holds the trie instead of borrowing it. It
+/// dereferences to the value.
+pub struct SharedValue<Label, Value> {
+    trie: SharedTrie<Label, Value>,
+    node: LoudsNodeNum,
+}
+
+impl<Label, Value> Clone for SharedValue<Label, Value> {
+    fn clone(&self) -> Self {
+        Self {
+            trie: self.trie.clone(),
+            node: self.node,
+        }
+    }
+}
+
+impl<Label: Ord, Value> Deref for SharedValue<Label, Value> {
+    type Target = Value;
+    fn deref(&self) -> &Value {
+        self.trie.value(self.node).unwrap()
+    }
+}
+
+impl<Label: Ord, Value> SharedValue<Label, Value> {
+    /// Return the node of the value's key.
+    pub fn node(&self) -> SharedNode<Label, Value> {
+        SharedNode {
+            trie: self.trie.clone(),
+            node: self.node,
+        }
+    }
+}
+
+#[derive(Debug)]
+/// A node of a [SharedTrie] that holds the trie instead of borrowing it. See
+/// [SharedTrie::node_owned].
+pub struct SharedNode<Label, Value> {
+    trie: SharedTrie<Label, Value>,
+    node: LoudsNodeNum,
+}
+
+impl<Label, Value> Clone for SharedNode<Label, Value> {
+    fn clone(&self) -> Self {
+        Self {
+            trie: self.trie.clone(),
+            node: self.node,
+        }
+    }
+}
+
+impl<Label: Ord, Value> SharedNode<Label, Value> {
+    /// Return a [NodeRef] borrowing this handle, to read the node.
+    pub fn node_ref(&self) -> NodeRef<'_, Label, Value> {
+        NodeRef::new(&self.trie, self.node)
+    }
+
+    /// Return the value if this node is the end of a key.
+    pub fn value(&self) -> Option<SharedValue<Label, Value>> {
+        self.trie.is_terminal(self.node).then(|| SharedValue {
+            trie: self.trie.clone(),
+            node: self.node,
+        })
+    }
+
+    /// Return the children in label order.
+    pub fn children(&self) -> impl Iterator<Item = SharedNode<Label, Value>> + '_ {
+        self.trie
+            .children_node_nums(self.node)
+            .map(move |node| SharedNode {
+                trie: self.trie.clone(),
+                node,
+            })
+    }
+}
+
+/// Iterates through the entries of a [SharedTrie] that match a query, in
+/// lexicographic order, holding the trie instead of borrowing it. See
+/// [SharedTrie::predictive_search_owned].
+pub struct SharedSearchIter<Label, Value, C, M> {
+    trie: SharedTrie<Label, Value>,
+    stack: Vec<(usize, LoudsNodeNum)>,
+    path: Vec<Label>,
+    query_len: usize,
+    col: PhantomData<(C, M)>,
+}
+
+impl<Label: Ord + Clone, Value, C, M> Iterator for SharedSearchIter<Label, Value, C, M>
+where
+    C: TryFromIterator<Label, M>,
+{
+    type Item = (C, SharedValue<Label, Value>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let trie = &self.trie;
+        while let Some(node) = trie.dfs_step(&mut self.stack, &mut self.path, self.query_len) {
+            if trie.is_terminal(node) {
+                let key = self
+                    .path
+                    .iter()
+                    .cloned()
+                    .try_collect()
+                    .expect("Could not collect");
+                let value = SharedValue {
+                    trie: trie.clone(),
+                    node,
+                };
+                return Some((key, value));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod shared_trie_tests {
+    use crate::map::{SharedTrie, Trie};
+
+    #[test]
+    fn copy_on_write() {
+        let mut a = SharedTrie::from(Trie::from_iter([("a", 0), ("ab", 1)]));
+        let b = a.clone();
+        assert!(a.ptr_eq(&b));
+
+        // Unshared mutation does not copy.
+        let mut c = SharedTrie::from(Trie::from_iter([("c", 0)]));
+        let before: *const Trie<u8, u8> = &*c;
+        *c.exact_match_mut("c").unwrap() = 5;
+        assert!(std::ptr::eq(before, &*c));
+
+        *a.exact_match_mut("ab").unwrap() = 2;
+        assert!(!a.ptr_eq(&b));
+        assert_eq!(b.exact_match("ab"), Some(&1));
+        assert_eq!(a.exact_match("ab"), Some(&2));
+        let results: Vec<(String, &u8)> = a.predictive_search("a").collect();
+        assert_eq!(results.len(), 2);
+        assert_eq!(b.into_trie().exact_match("ab"), Some(&1));
+    }
+
+    #[test]
+    fn owned_results() {
+        fn is_static<T: Send + 'static>(value: T) -> T {
+            value
+        }
+        let trie = SharedTrie::from(Trie::from_iter([
+            ("a", 0),
+            ("app", 1),
+            ("apple", 2),
+            ("application", 3),
+            ("better", 4),
+            ("アップル🍎", 5),
+        ]));
+        for query in ["", "a", "app", "appl", "better", "x", "アップル🍎🍏"] {
+            let owned: Vec<(String, u8)> = is_static(trie.predictive_search_owned(query))
+                .map(|(key, value)| (key, *value))
+                .collect();
+            let borrowed: Vec<(String, u8)> = trie
+                .predictive_search(query)
+                .map(|(key, value): (String, &u8)| (key, *value))
+                .collect();
+            assert_eq!(owned, borrowed, "{}", query);
+        }
+        assert_eq!(trie.iter_owned::<String, _>().count(), 6);
+
+        let node = is_static(trie.node_owned("app").unwrap());
+        drop(trie);
+        assert_eq!(node.value().map(|value| *value), Some(1));
+        let children: Vec<char> = node
+            .children()
+            .map(|child| *child.node_ref().label().unwrap() as char)
+            .collect();
+        assert_eq!(children, ['l']);
+        let value = node.children().next().unwrap().children().nth(1).unwrap();
+        assert_eq!(value.node_ref().prefix::<String, _>(), "appli");
+        assert!(value.value().is_none());
+    }
+}
@@ -0,0 +1,366 @@
+use super::updatable_trie::assert_non_empty;
+use super::{Trie, TrieBuilder, UpdatableSearchIter};
+use crate::try_collect::{Collect, TryFromIterator};
+use std::collections::BTreeMap;
+use std::sync::{Arc, PoisonError, RwLock};
+
+/// One version of a [SnapshotTrie]: a shared trie and the updates since it
+/// was built.
+#[derive(Debug)]
+struct Version<Label, Value> {
+    base: Arc<Trie<Label, Value>>,
+    /// `None` marks a key removed from `base`.
+    overlay: BTreeMap<Vec<Label>, Option<Arc<Value>>>,
+}
+
+impl<Label: Clone, Value> Clone for Version<Label, Value> {
+    fn clone(&self) -> Self {
+        Self {
+            base: Arc::clone(&self.base),
+            overlay: self.overlay.clone(),
+        }
+    }
+}
+
+#[derive(Debug)]
+/// An updatable trie that may be read, updated and compacted from several
+/// threads at once, whose readers each see a consistent [Snapshot].
+///
+/// Like [super::UpdatableTrie], updates go into an ordered overlay over an
+/// immutable trie. Every update makes a new version that shares the trie with
+/// the previous one, and a snapshot holds on to the version it was taken
+/// from, so a long export keeps seeing the same entries however many inserts
+/// or compactions happen meanwhile. [SnapshotTrie::compact] builds the new
+/// trie without holding the lock, so updates are not blocked by it.
+///
+/// ```rust
+/// use trie_rs::map::{SnapshotTrie, Trie};
+///
+/// let trie = SnapshotTrie::from(Trie::from_iter([("app", 1), ("apple", 2)]));
+/// let snapshot = trie.snapshot();
+/// let mut export = snapshot.iter::<String, _>();
+/// assert_eq!(export.next(), Some(("app".to_string(), &1)));
+///
+/// trie.insert("application", 3);
+/// trie.remove("apple");
+/// trie.compact();
+///
+/// // The export goes on as of when the snapshot was taken.
+/// assert_eq!(export.next(), Some(("apple".to_string(), &2)));
+/// assert_eq!(export.next(), None);
+/// let snapshot = trie.snapshot();
+/// let results: Vec<(String, &u8)> = snapshot.iter().collect();
+/// assert_eq!(results, [("app".to_string(), &1), ("application".to_string(), &3)]);
+/// ```
+pub struct SnapshotTrie<Label, Value> {
+    current: RwLock<Arc<Version<Label, Value>>>,
+}
+
+#[derive(Debug)]
+/// The entries of a [SnapshotTrie] at one point in time. Later updates and
+/// compactions do not affect it.
+pub struct Snapshot<Label, Value>(Arc<Version<Label, Value>>);
+
+impl<Label, Value> Clone for Snapshot<Label, Value> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<Label: Ord + Clone, Value> Default for SnapshotTrie<Label, Value> {
+    fn default() -> Self {
+        Self::from(TrieBuilder::new().build())
+    }
+}
+
+impl<Label, Value> From<Trie<Label, Value>> for SnapshotTrie<Label, Value> {
+    fn from(base: Trie<Label, Value>) -> Self {
+        Self {
+            current: RwLock::new(Arc::new(Version {
+                base: Arc::new(base),
+                overlay: BTreeMap::new(),
+            })),
+        }
+    }
+}
+
+impl<Label: Ord + Clone, Value> SnapshotTrie<Label, Value> {
+    /// Return an empty [SnapshotTrie].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the current entries.
+    pub fn snapshot(&self) -> Snapshot<Label, Value> {
+        // A panicking writer leaves the previous version in place.
+        let current = self.current.read().unwrap_or_else(PoisonError::into_inner);
+        Snapshot(Arc::clone(&current))
+    }
+
+    /// Apply `update` to a new version of the overlay, copying the overlay
+    /// only if a snapshot still holds the current one.
+    fn update<T>(&self, update: impl FnOnce(&mut Version<Label, Value>) -> T) -> T {
+        let mut current = self.current.write().unwrap_or_else(PoisonError::into_inner);
+        update(Arc::make_mut(&mut current))
+    }
+
+    /// Insert `key` with `value`, replacing any previous value.
+    ///
+    /// # Panics
+    /// If `key` is empty, like [super::UpdatableTrie::insert].
+    pub fn insert(&self, key: impl AsRef<[Label]>, value: Value) {
+        let key = key.as_ref();
+        assert_non_empty(key);
+        let key = key.to_vec();
+        self.update(|version| version.overlay.insert(key, Some(Arc::new(value))));
+    }
+
+    /// Remove `key`. Return true if it was present.
+    pub fn remove(&self, key: impl AsRef<[Label]>) -> bool {
+        let key = key.as_ref();
+        self.update(|version| {
+            let present = Snapshot::exact_match_in(version, key).is_some();
+            if version.base.exact_match(key).is_some() {
+                version.overlay.insert(key.to_vec(), None);
+            } else {
+                version.overlay.remove(key);
+            }
+            present
+        })
+    }
+
+    /// Return true if `query` is currently a key, without allocating. See
+    /// [Snapshot::contains_key].
+    pub fn contains_key(&self, query: impl AsRef<[Label]>) -> bool {
+        self.snapshot().contains_key(query)
+    }
+
+    /// Return the number of pending inserts and removals.
+    pub fn overlay_len(&self) -> usize {
+        self.snapshot().overlay_len()
+    }
+
+    /// Fold the overlay into a newly built trie.
+    ///
+    /// The trie is built from a snapshot without holding the lock. Updates
+    /// made meanwhile stay in the overlay of the result. If another
+    /// compaction replaces the trie first, this one starts over from a fresh
+    /// snapshot.
+    pub fn compact(&self)
+    where
+        Value: Clone,
+    {
+        while !self.compact_from(self.snapshot()) {}
+    }
+
+    /// Fold the overlay of `snapshot` into a newly built trie, keeping the
+    /// updates made since. Return false, changing nothing, if the trie of
+    /// `snapshot` has been replaced meanwhile.
+    fn compact_from(&self, snapshot: Snapshot<Label, Value>) -> bool
+    where
+        Value: Clone,
+    {
+        if snapshot.0.overlay.is_empty() {
+            return true;
+        }
+        let mut builder = TrieBuilder::new();
+        for (key, value) in snapshot.iter::<Vec<Label>, Collect>() {
+            builder.insert(key, value.clone());
+        }
+        let base = Arc::new(builder.build());
+
+        let mut current = self.current.write().unwrap_or_else(PoisonError::into_inner);
+        // The overlay below is only relative to the trie the snapshot saw.
+        if !Arc::ptr_eq(&current.base, &snapshot.0.base) {
+            return false;
+        }
+        // Updates already folded in are those left as the snapshot saw them.
+        let mut overlay: BTreeMap<_, _> = current
+            .overlay
+            .iter()
+            .filter(|(key, value)| {
+                let folded = match (snapshot.0.overlay.get(*key), value) {
+                    (Some(None), None) => true,
+                    (Some(Some(old)), Some(new)) => Arc::ptr_eq(old, new),
+                    _ => false,
+                };
+                !folded
+            })
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        // An insert folded in and then removed meanwhile left no tombstone,
+        // as the old trie did not have the key; the new one does.
+        for (key, value) in &snapshot.0.overlay {
+            if value.is_some()
+                && !current.overlay.contains_key(key)
+                && current.base.exact_match(key).is_none()
+            {
+                overlay.insert(key.clone(), None);
+            }
+        }
+        *current = Arc::new(Version { base, overlay });
+        true
+    }
+}
+
+impl<Label: Ord + Clone, Value> Snapshot<Label, Value> {
+    fn exact_match_in<'a>(
+        version: &'a Version<Label, Value>,
+        query: &[Label],
+    ) -> Option<&'a Value> {
+        match version.overlay.get(query) {
+            Some(value) => value.as_deref(),
+            None => version.base.exact_match(query),
+        }
+    }
+
+    /// Return `Some(&Value)` if query is an exact match.
+    pub fn exact_match(&self, query: impl AsRef<[Label]>) -> Option<&Value> {
+        Self::exact_match_in(&self.0, query.as_ref())
+    }
+
+    /// Return true if `query` is a key, without allocating. See
+    /// [Trie::contains_key].
+    pub fn contains_key(&self, query: impl AsRef<[Label]>) -> bool {
+        let query = query.as_ref();
+        match self.0.overlay.get(query) {
+            Some(value) => value.is_some(),
+            None => self.0.base.contains_key(query),
+        }
+    }
+
+    /// Return all entries and their values that match `query`, in
+    /// lexicographic order.
+    pub fn predictive_search<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> UpdatableSearchIter<'_, Label, Value, C, M, Arc<Value>>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        UpdatableSearchIter::new(&self.0.base, &self.0.overlay, query.as_ref())
+    }
+
+    /// Return all entries and their values in lexicographic order.
+    pub fn iter<C, M>(&self) -> UpdatableSearchIter<'_, Label, Value, C, M, Arc<Value>>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        self.predictive_search([])
+    }
+
+    /// Return the number of pending inserts and removals as of this snapshot.
+    pub fn overlay_len(&self) -> usize {
+        self.0.overlay.len()
+    }
+}
+
+#[cfg(test)]
+mod snapshot_trie_tests {
+    use crate::map::{SnapshotTrie, Trie};
+    use std::sync::Arc;
+
+    #[test]
+    fn concurrent_updates() {
+        let trie = Arc::new(SnapshotTrie::from(Trie::from_iter(
+            (0..100u32).map(|i| (format!("key{:03}", i), i)),
+        )));
+        let snapshot = trie.snapshot();
+        let writer = {
+            let trie = Arc::clone(&trie);
+            std::thread::spawn(move || {
+                for i in 0..100u32 {
+                    trie.insert(format!("new{:03}", i), i);
+                    trie.remove(format!("key{:03}", i));
+                    if i % 10 == 9 {
+                        trie.compact();
+                    }
+                }
+            })
+        };
+        let keys: Vec<String> = snapshot.iter().map(|(key, _)| key).collect();
+        writer.join().unwrap();
+        assert_eq!(keys.len(), 100);
+        assert!(keys.iter().all(|key| key.starts_with("key")));
+
+        let now = trie.snapshot();
+        assert_eq!(now.overlay_len(), 0);
+        assert_eq!(now.iter::<String, _>().count(), 100);
+        assert_eq!(now.exact_match("new042"), Some(&42));
+        assert_eq!(now.exact_match("key042"), None);
+    }
+
+    #[test]
+    fn updates_during_compaction_survive() {
+        let trie = SnapshotTrie::from(Trie::from_iter([("a", 0), ("b", 1)]));
+        trie.insert("c", 2);
+        trie.remove("a");
+        let snapshot = trie.snapshot();
+        // Made while the trie is built from the snapshot.
+        trie.insert("c", 3);
+        trie.insert("d", 4);
+        assert!(trie.compact_from(snapshot.clone()));
+        assert_eq!(trie.overlay_len(), 2);
+        let now = trie.snapshot();
+        assert_eq!(now.0.base.exact_match("c"), Some(&2));
+        let results: Vec<(String, &u8)> = now.iter().collect();
+        assert_eq!(
+            results,
+            [
+                ("b".to_string(), &1),
+                ("c".to_string(), &3),
+                ("d".to_string(), &4)
+            ]
+        );
+        assert_eq!(snapshot.exact_match("c"), Some(&2));
+        assert_eq!(snapshot.exact_match("a"), None);
+    }
+
+    #[test]
+    fn removals_during_compaction_survive() {
+        let trie = SnapshotTrie::from(Trie::from_iter([("a", 0), ("b", 1)]));
+        trie.insert("c", 2);
+        let snapshot = trie.snapshot();
+        // Made while the trie is built from the snapshot.
+        assert!(trie.remove("c"));
+        assert!(trie.compact_from(snapshot));
+        assert!(!trie.contains_key("c"));
+        let now = trie.snapshot();
+        let results: Vec<(String, &u8)> = now.iter().collect();
+        assert_eq!(results, [("a".to_string(), &0), ("b".to_string(), &1)]);
+        trie.compact();
+        assert_eq!(trie.overlay_len(), 0);
+        assert!(!trie.contains_key("c"));
+    }
+
+    #[test]
+    fn overlapping_compactions() {
+        let trie = SnapshotTrie::from(Trie::from_iter([("a", 0)]));
+        trie.insert("b", 1);
+        // A compaction still building from the first trie...
+        let stale = trie.snapshot();
+        // ...while another one finishes, and a later one after an insert.
+        trie.compact();
+        trie.insert("c", 2);
+        trie.compact();
+        assert!(!trie.compact_from(stale));
+        let now = trie.snapshot();
+        let results: Vec<(String, &u8)> = now.iter().collect();
+        assert_eq!(
+            results,
+            [
+                ("a".to_string(), &0),
+                ("b".to_string(), &1),
+                ("c".to_string(), &2)
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot insert an empty key")]
+    fn rejects_empty_keys() {
+        let trie = SnapshotTrie::from(Trie::from_iter([("a", 0)]));
+        trie.insert("", 1);
+        trie.compact();
+    }
+}
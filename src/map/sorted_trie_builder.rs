@@ -0,0 +1,242 @@
+use super::{NodeValues, Trie};
+use louds_rs::{Louds, LoudsNodeNum};
+
+#[cfg(feature = "mem_dbg")]
+use mem_dbg::MemDbg;
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A builder for keys that arrive in strictly increasing order, e.g. from a
+/// sorted file or a log-structured index.
+///
+/// LOUDS numbers nodes level by level, and with sorted keys each new node is
+/// the last one of its level so far. So nodes are appended to per-level
+/// arrays directly, without the pointer-based trie [TrieBuilder] goes
+/// through, and [SortedTrieBuilder::build] only concatenates the levels.
+///
+/// A built [Trie] can be turned back into a [SortedTrieBuilder] to append
+/// keys greater than all of its own, in time linear in its size.
+///
+/// ```rust
+/// use trie_rs::map::SortedTrieBuilder;
+///
+/// let mut builder = SortedTrieBuilder::new();
+/// builder.push("app", 0).unwrap();
+/// builder.push("apple", 1).unwrap();
+/// assert_eq!(builder.push("ant", 2), Err(2));
+/// let trie = builder.build();
+///
+/// let mut builder = SortedTrieBuilder::from(trie);
+/// builder.push("banana", 3).unwrap();
+/// let trie = builder.build();
+/// assert_eq!(trie.exact_match("apple"), Some(&1));
+/// assert_eq!(trie.exact_match("banana"), Some(&3));
+/// ```
+///
+/// [TrieBuilder]: super::TrieBuilder
+pub struct SortedTrieBuilder<Label, Value> {
+    /// Number of children of the root.
+    root_children: usize,
+    /// Nodes by depth - 1, in order.
+    levels: Vec<Level<Label, Value>>,
+    /// Length of the last key pushed. Its nodes are the last of the first
+    /// `last_len` levels.
+    last_len: usize,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Level<Label, Value> {
+    labels: Vec<Label>,
+    values: Vec<Option<Value>>,
+    children: Vec<usize>,
+}
+
+impl<Label, Value> Level<Label, Value> {
+    fn new() -> Self {
+        Self {
+            labels: Vec::new(),
+            values: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+}
+
+impl<Label: Ord, Value> Default for SortedTrieBuilder<Label, Value> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Label: Ord, Value> SortedTrieBuilder<Label, Value> {
+    /// Return an empty [SortedTrieBuilder].
+    pub fn new() -> Self {
+        Self {
+            root_children: 0,
+            levels: Vec::new(),
+            last_len: 0,
+        }
+    }
+
+    /// Add an entry and value. Return `Err(value)` if `entry` is empty or not
+    /// greater than every entry pushed before.
+    pub fn push(&mut self, entry: impl AsRef<[Label]>, value: Value) -> Result<(), Value>
+    where
+        Label: Clone,
+    {
+        let entry = entry.as_ref();
+        let shared = entry
+            .iter()
+            .zip(&self.levels[..self.last_len])
+            .take_while(|(label, level)| level.labels.last() == Some(label))
+            .count();
+        if shared == entry.len()
+            || (shared < self.last_len
+                && entry[shared] < *self.levels[shared].labels.last().unwrap())
+        {
+            return Err(value);
+        }
+        for (depth, label) in entry.iter().enumerate().skip(shared) {
+            match depth.checked_sub(1) {
+                Some(parent) => *self.levels[parent].children.last_mut().unwrap() += 1,
+                None => self.root_children += 1,
+            }
+            if self.levels.len() == depth {
+                self.levels.push(Level::new());
+            }
+            let level = &mut self.levels[depth];
+            level.labels.push(label.clone());
+            level.values.push(None);
+            level.children.push(0);
+        }
+        *self.levels[entry.len() - 1].values.last_mut().unwrap() = Some(value);
+        self.last_len = entry.len();
+        Ok(())
+    }
+
+    /// Build a [Trie].
+    pub fn build(self) -> Trie<Label, Value> {
+        let mut louds_bits: Vec<bool> = vec![true, false];
+        louds_bits.extend((0..self.root_children).map(|_| true));
+        louds_bits.push(false);
+        let mut trie_labels = Vec::new();
+        let mut values = NodeValues::default();
+        for level in self.levels {
+            for count in level.children {
+                louds_bits.extend((0..count).map(|_| true));
+                louds_bits.push(false);
+            }
+            trie_labels.extend(level.labels);
+            for value in level.values {
+                values.push(value);
+            }
+        }
+        Trie::from_parts(Louds::from(&louds_bits[..]), trie_labels, values, None)
+    }
+}
+
+impl<Label: Ord, Value> From<Trie<Label, Value>> for SortedTrieBuilder<Label, Value> {
+    /// Resume appending to `trie`. Label runs and insertion order are dropped.
+    fn from(mut trie: Trie<Label, Value>) -> Self {
+        let mut builder = Self::new();
+        builder.root_children = trie.children_node_nums(LoudsNodeNum(1)).len();
+        // depths[LoudsNodeNum - 1]
+        let mut depths = vec![0; trie.trie_labels.len() + 1];
+        let children: Vec<usize> = (2..=trie.trie_labels.len() as u64 + 1)
+            .map(|node| trie.children_node_nums(LoudsNodeNum(node)).len())
+            .collect();
+        for node in 2..=trie.trie_labels.len() as u64 + 1 {
+            let parent = trie.parent(LoudsNodeNum(node)).unwrap();
+            depths[node as usize - 1] = depths[parent.0 as usize - 1] + 1;
+        }
+        let mut last = LoudsNodeNum(1);
+        while let Some(child) = trie.children_node_nums(last).next_back() {
+            last = child;
+            builder.last_len += 1;
+        }
+        let labels = std::mem::take(&mut trie.trie_labels);
+        for (i, (label, children)) in labels.into_iter().zip(children).enumerate() {
+            let depth = depths[i + 1] - 1;
+            if builder.levels.len() == depth {
+                builder.levels.push(Level::new());
+            }
+            let level = &mut builder.levels[depth];
+            level.labels.push(label);
+            level.values.push(trie.values.take(i));
+            level.children.push(children);
+        }
+        builder
+    }
+}
+
+#[cfg(test)]
+mod sorted_trie_builder_tests {
+    use crate::map::{SortedTrieBuilder, Trie};
+
+    #[test]
+    fn same_as_trie_builder() {
+        let keys = [
+            "a",
+            "app",
+            "apple",
+            "application",
+            "apply",
+            "better",
+            "bet",
+            "ア",
+            "アップル🍎",
+        ];
+        let mut sorted: Vec<(&str, usize)> = keys.iter().copied().zip(0..).collect();
+        sorted.sort();
+        let mut builder = SortedTrieBuilder::new();
+        for (key, value) in &sorted {
+            builder.push(key, *value).unwrap();
+        }
+        let trie = builder.build();
+        let expected = Trie::from_iter(sorted.iter().copied());
+        assert!(trie.canonical_eq(&expected));
+        assert_eq!(trie.key_len_histogram(), expected.key_len_histogram());
+    }
+
+    #[test]
+    fn resume() {
+        let keys = ["a", "ab", "abc", "abd", "b", "ba", "c"];
+        for split in 0..=keys.len() {
+            let mut builder = SortedTrieBuilder::new();
+            for key in &keys[..split] {
+                builder.push(key, *key).unwrap();
+            }
+            let mut builder = SortedTrieBuilder::from(builder.build());
+            for key in &keys[split..] {
+                builder.push(key, *key).unwrap();
+            }
+            let expected = Trie::from_iter(keys.iter().map(|key| (key, *key)));
+            assert!(builder.build().canonical_eq(&expected), "{}", split);
+        }
+    }
+
+    #[test]
+    fn rejects_unsorted() {
+        let mut builder = SortedTrieBuilder::new();
+        assert_eq!(builder.push("", 0), Err(0));
+        builder.push("b", 1).unwrap();
+        assert_eq!(builder.push("b", 2), Err(2));
+        assert_eq!(builder.push("a", 3), Err(3));
+        builder.push("bc", 4).unwrap();
+        assert_eq!(builder.push("bb", 5), Err(5));
+        assert_eq!(builder.push("b", 6), Err(6));
+        builder.push("c", 7).unwrap();
+        let trie = builder.build();
+        let results: Vec<(String, &i32)> = trie.iter().collect();
+        assert_eq!(
+            results,
+            [
+                ("b".to_string(), &1),
+                ("bc".to_string(), &4),
+                ("c".to_string(), &7)
+            ]
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use super::NodeRef;
+use std::hash::{Hash, Hasher};
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+#[derive(Debug, Clone)]
+/// A 64-bit FNV-1a [Hasher] whose output depends only on the data hashed, not
+/// on the process, platform or Rust version, so hashes may be stored and
+/// compared across runs. Integers are hashed as little-endian bytes.
+///
+/// The output for a given label type is only as stable as its [Hash] impl;
+/// those of integers, `char` and `str` are.
+pub struct StableHasher(u64);
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self(FNV_OFFSET)
+    }
+}
+
+impl StableHasher {
+    /// Return the hash of a path of labels, as [NodeRef::stable_hash] does
+    /// for the node at the end of it.
+    pub fn hash_path<'a, Label: Hash + 'a>(path: impl IntoIterator<Item = &'a Label>) -> u64 {
+        let mut hasher = Self::default();
+        let mut len: u64 = 0;
+        for label in path {
+            label.hash(&mut hasher);
+            len += 1;
+        }
+        // Separates paths whose labels hash to the same concatenated bytes.
+        hasher.write_u64(len);
+        hasher.finish()
+    }
+}
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        // The same on 32- and 64-bit targets.
+        self.write_u64(i as u64)
+    }
+}
+
+impl<Label: Ord + Hash, Value> NodeRef<'_, Label, Value> {
+    /// Return a hash of the labels from the root to this node, which stays
+    /// the same across runs and builds of the same keys, e.g. to key a cache
+    /// of per-prefix results on disk. See [StableHasher].
+    ///
+    /// ```rust
+    /// use trie_rs::map::{StableHasher, Trie};
+    ///
+    /// let trie = Trie::from_iter([("apple", 0), ("apricot", 1)]);
+    /// let other = Trie::from_iter([("ape", 2), ("apple", 3)]);
+    /// let hash = trie.node("ap").unwrap().stable_hash();
+    /// assert_eq!(other.node("ap").unwrap().stable_hash(), hash);
+    /// assert_eq!(StableHasher::hash_path(b"ap"), hash);
+    /// assert_ne!(trie.node("a").unwrap().stable_hash(), hash);
+    /// ```
+    pub fn stable_hash(&self) -> u64 {
+        let mut path: Vec<&Label> = self
+            .trie()
+            .child_to_ancestors(self.louds_node_num())
+            .map(|node| self.trie().label(node))
+            .collect();
+        path.reverse();
+        StableHasher::hash_path(path)
+    }
+}
+
+#[cfg(test)]
+mod stable_hash_tests {
+    use super::StableHasher;
+    use crate::map::Trie;
+
+    #[test]
+    fn fixed_values() {
+        // Changing these breaks caches keyed by them.
+        assert_eq!(StableHasher::hash_path(&[] as &[u8]), 0xa8c7_f832_281a_39c5);
+        let trie = Trie::from_iter([("ab", 0), ("b", 1)]);
+        assert_eq!(
+            trie.node("").unwrap().stable_hash(),
+            StableHasher::hash_path(&[] as &[u8])
+        );
+        assert_eq!(
+            trie.node("ab").unwrap().stable_hash(),
+            0x9691_26f9_d51e_b568
+        );
+        let chars = Trie::from_iter([(['a', 'b'], 0)]);
+        assert_ne!(
+            chars.node(['a', 'b']).unwrap().stable_hash(),
+            StableHasher::hash_path(b"ab")
+        );
+    }
+}
@@ -0,0 +1,105 @@
+//! Direct key comparisons for tries with very few keys.
+//!
+//! Every LOUDS child lookup costs a select and a rank, which dominates
+//! queries against tries of a handful of short keys, e.g. one per enum
+//! variant. For those the path of node numbers to each key is kept, and a
+//! query is compared against each key's labels without touching the LOUDS.
+use super::label_runs::Descent;
+use super::Trie;
+use louds_rs::LoudsNodeNum;
+
+#[cfg(feature = "mem_dbg")]
+use mem_dbg::MemDbg;
+
+/// Tries with at most this many keys keep [TinyPaths].
+const MAX_KEYS: usize = 4;
+/// ...and at most this many labels over all keys.
+const MAX_LABELS: usize = 64;
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct TinyPaths {
+    /// For each key, the nodes from below the root down to its terminal.
+    paths: Vec<Vec<LoudsNodeNum>>,
+}
+
+impl<Label: Ord, Value> Trie<Label, Value> {
+    /// Return the paths to all keys if the trie is small enough.
+    pub(crate) fn tiny_paths(&self) -> Option<TinyPaths> {
+        let keys = self.key_count();
+        let labels: usize = self.key_lens.iter().map(|(len, count)| len * count).sum();
+        if keys > MAX_KEYS || labels > MAX_LABELS {
+            return None;
+        }
+        let mut paths = Vec::new();
+        let mut stack = vec![(LoudsNodeNum(1), Vec::new())];
+        while let Some((node, path)) = stack.pop() {
+            for child in self.children_node_nums(node) {
+                let mut child_path = path.clone();
+                child_path.push(child);
+                stack.push((child, child_path));
+            }
+            if self.is_terminal(node) {
+                paths.push(path);
+            }
+        }
+        Some(TinyPaths { paths })
+    }
+
+    /// Descend along a non-empty `query` by comparing it against each key.
+    pub(crate) fn descend_tiny(&self, tiny: &TinyPaths, query: &[Label]) -> Descent {
+        let mut longer = false;
+        for path in &tiny.paths {
+            if path.len() < query.len()
+                || !query
+                    .iter()
+                    .zip(path)
+                    .all(|(label, node)| self.label(*node) == label)
+            {
+                continue;
+            }
+            if path.len() == query.len() {
+                return Descent::Node(path[path.len() - 1]);
+            }
+            longer = true;
+        }
+        if longer {
+            // No key ends here, but some continue.
+            Descent::InRun
+        } else {
+            Descent::Miss
+        }
+    }
+}
+
+#[cfg(test)]
+mod tiny_paths_tests {
+    use crate::map::Trie;
+
+    #[test]
+    fn tiny() {
+        let trie = Trie::from_iter([("on", 0), ("off", 1), ("o", 2)]);
+        assert!(trie.tiny.is_some());
+        assert_eq!(trie.exact_match("o"), Some(&2));
+        assert_eq!(trie.exact_match("off"), Some(&1));
+        assert_eq!(trie.exact_match("of"), None);
+        assert_eq!(trie.exact_match("offf"), None);
+        assert_eq!(trie.exact_match(""), None);
+        assert!(trie.is_prefix("o"));
+        assert!(trie.is_prefix("of"));
+        assert!(!trie.is_prefix("on"));
+        assert!(!trie.is_prefix("x"));
+        assert!(trie.is_prefix(""));
+    }
+
+    #[test]
+    fn too_large() {
+        let trie = Trie::from_iter([("a", 0), ("b", 1), ("c", 2), ("d", 3), ("e", 4)]);
+        assert!(trie.tiny.is_none());
+        let long = "x".repeat(65);
+        let trie = Trie::from_iter([(long.as_str(), 0)]);
+        assert!(trie.tiny.is_none());
+        assert_eq!(trie.exact_match(&long), Some(&0));
+    }
+}
@@ -0,0 +1,302 @@
+use super::Trie;
+use crate::try_collect::LossyIterator;
+use std::str;
+
+#[derive(Debug)]
+/// Queries a byte trie with `char`s, which are encoded as UTF-8. Obtained from
+/// [Trie::by_chars].
+pub struct CharQueries<'a, Value>(&'a Trie<u8, Value>);
+
+#[derive(Debug)]
+/// Queries a `char` trie with UTF-8 bytes, which may end partway through a
+/// `char`. Obtained from [Trie::by_bytes].
+pub struct ByteQueries<'a, Value>(&'a Trie<char, Value>);
+
+impl<Value> Clone for CharQueries<'_, Value> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Value> Copy for CharQueries<'_, Value> {}
+
+impl<Value> Clone for ByteQueries<'_, Value> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Value> Copy for ByteQueries<'_, Value> {}
+
+fn encode(query: &[char]) -> Vec<u8> {
+    query.iter().collect::<String>().into_bytes()
+}
+
+impl<Value> Trie<u8, Value> {
+    /// Query this byte trie with `char`s.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("café", 0), ("cafés", 1)]);
+    /// let chars = trie.by_chars();
+    /// assert_eq!(chars.exact_match(&['c', 'a', 'f', 'é']), Some(&0));
+    /// assert!(chars.is_prefix(&['c', 'a']));
+    /// ```
+    pub fn by_chars(&self) -> CharQueries<'_, Value> {
+        CharQueries(self)
+    }
+}
+
+impl<Value> Trie<char, Value> {
+    /// Query this `char` trie with UTF-8 bytes.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie: Trie<char, u8> = Trie::from_iter([("café".chars().collect::<Vec<_>>(), 0)]);
+    /// let bytes = trie.by_bytes();
+    /// assert_eq!(bytes.exact_match("café"), Some(&0));
+    /// // The first byte of 'é' is a prefix.
+    /// assert!(bytes.is_prefix(&"café".as_bytes()[..4]));
+    /// ```
+    pub fn by_bytes(&self) -> ByteQueries<'_, Value> {
+        ByteQueries(self)
+    }
+}
+
+impl<'a, Value> CharQueries<'a, Value> {
+    /// Return `Some(&Value)` if query is an exact match.
+    pub fn exact_match(&self, query: impl AsRef<[char]>) -> Option<&'a Value> {
+        self.0.exact_match(encode(query.as_ref()))
+    }
+
+    /// Return true if `query` is a prefix.
+    pub fn is_prefix(&self, query: impl AsRef<[char]>) -> bool {
+        self.0.is_prefix(encode(query.as_ref()))
+    }
+
+    /// Return all entries and their values that match `query`.
+    ///
+    /// Keys that are not valid UTF-8 are collected lossily, see
+    /// [LossyIterator::lossy].
+    pub fn predictive_search(
+        &self,
+        query: impl AsRef<[char]>,
+    ) -> impl Iterator<Item = (String, &'a Value)> + 'a {
+        self.0
+            .predictive_search::<Result<String, _>, _>(encode(query.as_ref()))
+            .lossy()
+    }
+
+    /// Return the postfixes of all entries that match `query` and their
+    /// values. As `query` ends on a `char` boundary, so do the postfixes of
+    /// UTF-8 keys.
+    pub fn postfix_search(
+        &self,
+        query: impl AsRef<[char]>,
+    ) -> impl Iterator<Item = (String, &'a Value)> + 'a {
+        self.0
+            .postfix_search::<Result<String, _>, _>(encode(query.as_ref()))
+            .lossy()
+    }
+
+    /// Return the common prefixes of `query` and their values.
+    pub fn common_prefix_search(
+        &self,
+        query: impl AsRef<[char]>,
+    ) -> impl Iterator<Item = (String, &'a Value)> + 'a {
+        self.0
+            .common_prefix_search::<Result<String, _>, _>(encode(query.as_ref()))
+            .lossy()
+    }
+
+    /// Like [Trie::longest_prefix], but never end partway through a `char`:
+    /// an extension that would is cut back to the last `char` boundary.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("café", 0), ("cafê", 1)]);
+    /// // 'é' and 'ê' share their first byte.
+    /// assert_eq!(trie.longest_prefix::<Vec<u8>, _>("caf"), Some(b"caf\xc3".to_vec()));
+    /// assert_eq!(trie.by_chars().longest_prefix(['c']), Some("caf".to_string()));
+    /// ```
+    pub fn longest_prefix(&self, query: impl AsRef<[char]>) -> Option<String> {
+        let mut bytes: Vec<u8> = self.0.longest_prefix(encode(query.as_ref()))?;
+        if let Err(e) = str::from_utf8(&bytes) {
+            bytes.truncate(e.valid_up_to());
+        }
+        Some(String::from_utf8(bytes).unwrap())
+    }
+
+    /// Return the byte trie.
+    pub fn trie(&self) -> &'a Trie<u8, Value> {
+        self.0
+    }
+}
+
+/// Split `query` into its complete `char`s and the bytes of a trailing
+/// incomplete one. Return `None` if `query` is not valid UTF-8 otherwise.
+fn decode(query: &[u8]) -> Option<(Vec<char>, &[u8])> {
+    let (valid, partial) = match str::from_utf8(query) {
+        Ok(s) => (s, &query[query.len()..]),
+        // `error_len` is `None` when the input ends partway through a char.
+        Err(e) if e.error_len().is_none() => (
+            str::from_utf8(&query[..e.valid_up_to()]).unwrap(),
+            &query[e.valid_up_to()..],
+        ),
+        Err(_) => return None,
+    };
+    Some((valid.chars().collect(), partial))
+}
+
+impl<'a, Value> ByteQueries<'a, Value> {
+    /// Return `Some(&Value)` if query is an exact match. Queries that are not
+    /// valid UTF-8 never match.
+    pub fn exact_match(&self, query: impl AsRef<[u8]>) -> Option<&'a Value> {
+        let (chars, partial) = decode(query.as_ref())?;
+        if partial.is_empty() {
+            self.0.exact_match(chars)
+        } else {
+            None
+        }
+    }
+
+    /// Return true if `query` is a prefix of the UTF-8 encoding of a key,
+    /// including when it ends partway through a `char`.
+    pub fn is_prefix(&self, query: impl AsRef<[u8]>) -> bool {
+        match decode(query.as_ref()) {
+            Some((chars, [])) => self.0.is_prefix(chars),
+            Some((chars, partial)) => self.completions(&chars, partial).next().is_some(),
+            None => false,
+        }
+    }
+
+    /// Return all entries whose UTF-8 encoding starts with `query`, and their
+    /// values.
+    pub fn predictive_search(
+        &self,
+        query: impl AsRef<[u8]>,
+    ) -> impl Iterator<Item = (String, &'a Value)> + 'a {
+        let trie = self.0;
+        let queries: Vec<Vec<char>> = match decode(query.as_ref()) {
+            Some((chars, [])) => vec![chars],
+            Some((chars, partial)) => self
+                .completions(&chars, partial)
+                .map(|c| {
+                    let mut query = chars.clone();
+                    query.push(c);
+                    query
+                })
+                .collect(),
+            None => vec![],
+        };
+        queries
+            .into_iter()
+            .flat_map(move |query| trie.predictive_search(query))
+    }
+
+    /// Return the `char` trie.
+    pub fn trie(&self) -> &'a Trie<char, Value> {
+        self.0
+    }
+
+    /// Return the `char`s after `chars` whose encoding starts with `partial`.
+    fn completions<'p>(&self, chars: &[char], partial: &'p [u8]) -> impl Iterator<Item = char> + 'p
+    where
+        'a: 'p,
+    {
+        self.0.children(chars).filter_map(move |child| {
+            let c = *child.label()?;
+            let mut buf = [0; 4];
+            c.encode_utf8(&mut buf)
+                .as_bytes()
+                .starts_with(partial)
+                .then_some(c)
+        })
+    }
+}
+
+#[cfg(test)]
+mod token_views_tests {
+    use crate::map::Trie;
+
+    fn chars(s: &str) -> Vec<char> {
+        s.chars().collect()
+    }
+
+    #[test]
+    fn char_queries() {
+        let trie = Trie::from_iter([("a", 0), ("アップル", 1), ("アップル🍎", 2)]);
+        let by_chars = trie.by_chars();
+        assert_eq!(by_chars.exact_match(chars("アップル")), Some(&1));
+        assert_eq!(by_chars.exact_match(chars("アップ")), None);
+        assert!(by_chars.is_prefix(chars("アップル")));
+        assert!(!by_chars.is_prefix(chars("アップル🍎")));
+        let results: Vec<(String, &u8)> = by_chars.predictive_search(chars("ア")).collect();
+        assert_eq!(
+            results,
+            [("アップル".to_string(), &1), ("アップル🍎".to_string(), &2)]
+        );
+    }
+
+    #[test]
+    fn char_boundaries() {
+        let trie = Trie::from_iter([("日本", 0), ("日本語", 1), ("日本酒", 2)]);
+        let by_chars = trie.by_chars();
+        // "語" and "酒" share their first two bytes.
+        assert_eq!(
+            by_chars.longest_prefix(chars("日")),
+            Some("日本".to_string())
+        );
+        assert_eq!(
+            by_chars.longest_prefix(chars("日本語")),
+            Some("日本語".to_string())
+        );
+        assert_eq!(by_chars.longest_prefix(chars("本")), None);
+        let postfixes: Vec<String> = by_chars
+            .postfix_search(chars("日本"))
+            .map(|(postfix, _)| postfix)
+            .collect();
+        assert_eq!(postfixes, ["語", "酒"]);
+        let prefixes: Vec<(String, &u8)> =
+            by_chars.common_prefix_search(chars("日本語です")).collect();
+        assert_eq!(
+            prefixes,
+            [("日本".to_string(), &0), ("日本語".to_string(), &1)]
+        );
+
+        let invalid: Trie<u8, u8> = Trie::from_iter([(vec![b'a', 0xff], 0)]);
+        let results: Vec<(String, &u8)> = invalid.by_chars().predictive_search(['a']).collect();
+        assert_eq!(results, [("a\u{fffd}".to_string(), &0)]);
+    }
+
+    #[test]
+    fn byte_queries() {
+        let trie: Trie<char, u8> = Trie::from_iter([
+            (chars("é"), 0),
+            (chars("ét"), 1),
+            (chars("ê"), 2),
+            (chars("e"), 3),
+        ]);
+        let by_bytes = trie.by_bytes();
+        let e_acute = "é".as_bytes();
+        assert_eq!(by_bytes.exact_match(e_acute), Some(&0));
+        assert_eq!(by_bytes.exact_match(&e_acute[..1]), None);
+        assert_eq!(by_bytes.exact_match([0xff]), None);
+        assert!(by_bytes.is_prefix(&e_acute[..1]));
+        assert!(by_bytes.is_prefix(e_acute));
+        assert!(!by_bytes.is_prefix("ê"));
+        assert!(!by_bytes.is_prefix([0xe3]));
+        assert!(!by_bytes.is_prefix([0xff]));
+
+        let keys = |query: &[u8]| -> Vec<String> {
+            by_bytes.predictive_search(query).map(|(k, _)| k).collect()
+        };
+        assert_eq!(keys(&e_acute[..1]), ["é", "ét", "ê"]);
+        assert_eq!(keys(e_acute), ["é", "ét"]);
+        assert_eq!(keys(b""), ["e", "é", "ét", "ê"]);
+        assert!(keys(&[0xe3]).is_empty());
+    }
+}
@@ -0,0 +1,256 @@
+use super::{NodeRef, Trie};
+use crate::try_collect::TryFromIterator;
+use louds_rs::LoudsNodeNum;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+#[cfg(feature = "mem_dbg")]
+use mem_dbg::MemDbg;
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The best score below each node of a [Trie], for finding the highest-scored
+/// completions of a prefix without visiting every one of them.
+///
+/// Like [super::NodeData], it is filled from one trie and must only be used
+/// with that trie.
+///
+/// ```rust
+/// use trie_rs::map::{ScoreIndex, Trie};
+///
+/// let trie = Trie::from_iter([("app", 3), ("apple", 10), ("apply", 7), ("bee", 20)]);
+/// let index = ScoreIndex::new(&trie, |v| *v);
+/// let results: Vec<(String, i64)> = index
+///     .top_k(&trie, "app", 2)
+///     .into_iter()
+///     .map(|(k, _, s)| (k, s))
+///     .collect();
+/// assert_eq!(results, [("apple".to_string(), 10), ("apply".to_string(), 7)]);
+/// ```
+pub struct ScoreIndex {
+    /// Score of each terminal node, indexed by `LoudsNodeNum - 1`.
+    scores: Vec<Option<i64>>,
+    /// Best score at or below each node, indexed by `LoudsNodeNum - 1`.
+    best: Vec<Option<i64>>,
+}
+
+impl ScoreIndex {
+    /// Score every key of `trie` with `score`.
+    pub fn new<Label: Ord, Value>(
+        trie: &Trie<Label, Value>,
+        score: impl Fn(&Value) -> i64,
+    ) -> Self {
+        let node_count = trie.trie_labels.len() + 1;
+        let scores: Vec<Option<i64>> = (1..=node_count as u64)
+            .map(|node| trie.value(LoudsNodeNum(node)).map(&score))
+            .collect();
+        let mut best = scores.clone();
+        // Children are numbered after their parents, so one backward pass
+        // sees every subtree before its root.
+        for node in (2..=node_count as u64).rev() {
+            let parent = trie.parent(LoudsNodeNum(node)).unwrap().0 as usize - 1;
+            best[parent] = best[parent].max(best[node as usize - 1]);
+        }
+        Self { scores, best }
+    }
+
+    /// Return up to `k` keys starting with `query` with their values and
+    /// scores, highest score first.
+    pub fn top_k<'a, Label: Ord + Clone, Value, C, M>(
+        &self,
+        trie: &'a Trie<Label, Value>,
+        query: impl AsRef<[Label]>,
+        k: usize,
+    ) -> Vec<(C, &'a Value, i64)>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        self.top_k_with_bias(trie, query, k, 0, |_| 0)
+    }
+
+    /// Like [ScoreIndex::top_k], but add `bias(node)` to the score of every
+    /// key below each node visited below `query`, e.g. to boost completions
+    /// the user chose before or demote those they dismissed.
+    ///
+    /// The bias is applied while searching, so subtrees it pushes below the
+    /// `k`th result are never visited. A subtree is bounded by its best score
+    /// plus the bias so far plus `max_boost`, which must be at least the sum
+    /// of the positive biases on the path to any key. Results are exact as
+    /// long as it is; pass 0 if `bias` never returns more than 0. A larger
+    /// `max_boost` visits more subtrees.
+    ///
+    /// # Panics
+    /// In debug builds, if the positive biases on a path add up to more than
+    /// `max_boost`.
+    ///
+    /// ```rust
+    /// use trie_rs::map::{ScoreIndex, Trie};
+    ///
+    /// let trie = Trie::from_iter([("apple", 10), ("apply", 7), ("apt", 5)]);
+    /// let index = ScoreIndex::new(&trie, |v| *v);
+    /// let chosen = trie.node("apt").unwrap();
+    /// let boost = 8;
+    /// let results: Vec<(String, &i64, i64)> = index.top_k_with_bias(&trie, "ap", 2, boost, |node| {
+    ///     if node == chosen { boost } else { 0 }
+    /// });
+    /// assert_eq!(results, [("apt".to_string(), &5, 13), ("apple".to_string(), &10, 10)]);
+    /// ```
+    pub fn top_k_with_bias<'a, Label: Ord + Clone, Value, C, M>(
+        &self,
+        trie: &'a Trie<Label, Value>,
+        query: impl AsRef<[Label]>,
+        k: usize,
+        max_boost: i64,
+        mut bias: impl FnMut(NodeRef<'a, Label, Value>) -> i64,
+    ) -> Vec<(C, &'a Value, i64)>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        debug_assert!(max_boost >= 0, "max_boost must not be negative");
+        let mut results = Vec::new();
+        let Some(start) = trie.node_num(query.as_ref()) else {
+            return results;
+        };
+        // (score bound, is a finished key, earlier node first, bias so far,
+        // positive bias so far)
+        let mut heap = BinaryHeap::new();
+        if let Some(best) = self.best(start) {
+            heap.push((best + max_boost, false, Reverse(start.0), 0, 0));
+        }
+        while let Some((score, is_key, Reverse(node), path_bias, path_boost)) = heap.pop() {
+            if results.len() == k {
+                break;
+            }
+            let node = LoudsNodeNum(node);
+            if is_key {
+                let value = trie.value(node).unwrap();
+                results.push((trie.prefix_of(node), value, score));
+                continue;
+            }
+            if let Some(own) = self.scores[node.0 as usize - 1] {
+                heap.push((
+                    own + path_bias,
+                    true,
+                    Reverse(node.0),
+                    path_bias,
+                    path_boost,
+                ));
+            }
+            for child in trie.children_node_nums(node) {
+                if let Some(best) = self.best(child) {
+                    let own_bias = bias(NodeRef::new(trie, child));
+                    let child_bias = path_bias + own_bias;
+                    let child_boost = path_boost + own_bias.max(0);
+                    debug_assert!(
+                        child_boost <= max_boost,
+                        "positive bias of {} exceeds max_boost of {}",
+                        child_boost,
+                        max_boost
+                    );
+                    let bound = best + child_bias + max_boost;
+                    heap.push((bound, false, Reverse(child.0), child_bias, child_boost));
+                }
+            }
+        }
+        results
+    }
+
+    fn best(&self, node: LoudsNodeNum) -> Option<i64> {
+        self.best[node.0 as usize - 1]
+    }
+}
+
+#[cfg(test)]
+mod top_k_tests {
+    use crate::map::{ScoreIndex, Trie};
+
+    fn build_trie() -> Trie<u8, i64> {
+        Trie::from_iter([
+            ("a", 1),
+            ("app", 4),
+            ("apple", 9),
+            ("application", 2),
+            ("better", 6),
+            ("bet", 8),
+        ])
+    }
+
+    #[test]
+    fn top_k() {
+        let trie = build_trie();
+        let index = ScoreIndex::new(&trie, |v| *v);
+        let keys = |query: &str, k| -> Vec<String> {
+            index
+                .top_k(&trie, query, k)
+                .into_iter()
+                .map(|(key, _, _)| key)
+                .collect()
+        };
+        assert_eq!(keys("", 3), ["apple", "bet", "better"]);
+        assert_eq!(keys("a", 10), ["apple", "app", "application", "a"]);
+        assert_eq!(keys("app", 0), Vec::<String>::new());
+        assert_eq!(keys("z", 3), Vec::<String>::new());
+    }
+
+    #[test]
+    fn bias_prunes() {
+        let trie = build_trie();
+        let index = ScoreIndex::new(&trie, |v| *v);
+        let b = trie.node("b").unwrap();
+        let mut visited_below_b = 0;
+        let results: Vec<(String, &i64, i64)> = index.top_k_with_bias(&trie, "", 2, 0, |node| {
+            if node == b {
+                -100
+            } else {
+                if node.prefix::<Vec<u8>, _>().starts_with(b"b") {
+                    visited_below_b += 1;
+                }
+                0
+            }
+        });
+        let keys: Vec<&str> = results.iter().map(|(k, _, _)| k.as_str()).collect();
+        assert_eq!(keys, ["apple", "app"]);
+        assert_eq!(visited_below_b, 0);
+    }
+
+    #[test]
+    fn boost_wins() {
+        let trie = build_trie();
+        let index = ScoreIndex::new(&trie, |v| *v);
+        let a = trie.node("a").unwrap();
+        let application = trie.node("application").unwrap();
+        let keys = |max_boost| -> Vec<(String, i64)> {
+            index
+                .top_k_with_bias(&trie, "", 2, max_boost, |node| {
+                    // Two boosts on one path, 3 + 8.
+                    if node == a {
+                        3
+                    } else if node == application {
+                        8
+                    } else {
+                        0
+                    }
+                })
+                .into_iter()
+                .map(|(key, _, score)| (key, score))
+                .collect()
+        };
+        // "application" scores 2 + 3 + 8 and beats "apple" at 9 + 3, though
+        // its subtree's best score is the lowest.
+        assert_eq!(
+            keys(11),
+            [("application".to_string(), 13), ("apple".to_string(), 12)]
+        );
+        assert_eq!(keys(20), keys(11));
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds max_boost")]
+    #[cfg(debug_assertions)]
+    fn boost_beyond_max_panics() {
+        let trie = build_trie();
+        let index = ScoreIndex::new(&trie, |v| *v);
+        index.top_k_with_bias::<_, _, String, _>(&trie, "", 1, 1, |_| 2);
+    }
+}
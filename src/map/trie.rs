@@ -1,10 +1,16 @@
 //! A trie map stores a value with each word or key.
-use super::Trie;
-use crate::inc_search::IncSearch;
-use crate::iter::{PostfixIter, PrefixIter, SearchIter};
+use super::label_runs::Descent;
+use super::{ChildNodeRefs, NodeRef, NodeValues, PrefixNodes, Trie};
+use crate::inc_search::{Answer, IncSearch};
+use crate::iter::{
+    FuzzyIter, GapIter, InsertionOrderIter, IterMut, PostfixIter, PrefixIter, PrefixOffsetIter,
+    RangeIter, SearchIter,
+};
+use crate::raw::RawTrie;
 use crate::try_collect::{TryCollect, TryFromIterator};
-use louds_rs::{AncestorNodeIter, ChildNodeIter, LoudsNodeNum};
+use louds_rs::{AncestorNodeIter, ChildNodeIter, Louds, LoudsNodeNum};
 use std::iter::FromIterator;
+use std::ops::RangeBounds;
 
 impl<Label: Ord, Value> Trie<Label, Value> {
     /// Return `Some(&Value)` if query is an exact match.
@@ -16,25 +22,10 @@ impl<Label: Ord, Value> Trie<Label, Value> {
     /// Return `Node` if query is an exact match.
     #[inline]
     fn exact_match_node(&self, query: impl AsRef<[Label]>) -> Option<LoudsNodeNum> {
-        let mut cur_node_num = LoudsNodeNum(1);
-
-        for (i, chr) in query.as_ref().iter().enumerate() {
-            let children_node_nums: Vec<LoudsNodeNum> =
-                self.children_node_nums(cur_node_num).collect();
-            let res = self.bin_search_by_children_labels(chr, &children_node_nums[..]);
-
-            match res {
-                Ok(j) => {
-                    let child_node_num = children_node_nums[j];
-                    if i == query.as_ref().len() - 1 && self.is_terminal(child_node_num) {
-                        return Some(child_node_num);
-                    }
-                    cur_node_num = child_node_num;
-                }
-                Err(_) => return None,
-            }
+        match self.descend(query.as_ref()) {
+            Descent::Node(node_num) if self.is_terminal(node_num) => Some(node_num),
+            _ => None,
         }
-        None
     }
 
     /// Return `Some(&mut value)` if query is an exact match.
@@ -49,23 +40,511 @@ impl<Label: Ord, Value> Trie<Label, Value> {
         IncSearch::new(self)
     }
 
+    /// Return the node reached by `query`, or `None` if `query` is not a
+    /// prefix of any key. The empty query returns the root. The descent does
+    /// not allocate.
+    pub fn node(&self, query: impl AsRef<[Label]>) -> Option<NodeRef<'_, Label, Value>> {
+        self.node_num(query.as_ref())
+            .map(|node_num| NodeRef::new(self, node_num))
+    }
+
+    /// Return the children of the node reached by `query` in label order.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("ab", 0), ("ac", 1), ("b", 2)]);
+    /// let labels: Vec<u8> = trie.children("a").map(|c| *c.label().unwrap()).collect();
+    /// assert_eq!(labels, b"bc");
+    /// assert_eq!(trie.children("z").count(), 0);
+    /// ```
+    pub fn children(&self, query: impl AsRef<[Label]>) -> ChildNodeRefs<'_, Label, Value> {
+        match self.node(query) {
+            Some(node) => node.children(),
+            None => ChildNodeRefs::empty(self),
+        }
+    }
+
+    /// Return the label and value of each child of the node reached by
+    /// `query` that ends a key, in label order.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("a", 0), ("ab", 1), ("abc", 2), ("ad", 3), ("ae", 4)]);
+    /// let next: Vec<(&u8, &i32)> = trie.children_values("a").collect();
+    /// assert_eq!(next, [(&b'b', &1), (&b'd', &3), (&b'e', &4)]);
+    /// ```
+    pub fn children_values(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> impl Iterator<Item = (&Label, &Value)> + '_ {
+        self.children(query)
+            .filter_map(|child| Some((child.label()?, child.value()?)))
+    }
+
+    /// Return the nodes of the keys that are prefixes of `query`, shortest
+    /// first, like [Trie::common_prefix_search] without collecting labels.
+    /// Each step descends only as far as the next key.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("a", 0), ("app", 1), ("apple", 2)]);
+    /// let matches: Vec<(usize, &i32)> = trie
+    ///     .prefixes_of_nodes("applet")
+    ///     .map(|node| (node.label_len(), node.value().unwrap()))
+    ///     .collect();
+    /// assert_eq!(matches, [(1, &0), (3, &1), (5, &2)]);
+    /// ```
+    pub fn prefixes_of_nodes<Q: AsRef<[Label]>>(
+        &self,
+        query: Q,
+    ) -> PrefixNodes<'_, Label, Value, Q> {
+        PrefixNodes::new(self, query)
+    }
+
+    /// Return the nodes of all keys starting with `query`, in the order of
+    /// [Trie::predictive_search], without collecting their labels. Call
+    /// [NodeRef::prefix] for the keys actually needed.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("app", 0), ("apple", 1), ("application", 2), ("bee", 3)]);
+    /// let first: Vec<(String, &i32)> = trie
+    ///     .predictive_search_nodes("app")
+    ///     .skip(1)
+    ///     .take(1)
+    ///     .map(|node| (node.prefix(), node.value().unwrap()))
+    ///     .collect();
+    /// assert_eq!(first, [("apple".to_string(), &1)]);
+    /// ```
+    pub fn predictive_search_nodes(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> impl Iterator<Item = NodeRef<'_, Label, Value>> + '_ {
+        let mut stack: Vec<LoudsNodeNum> = self.node_num(query.as_ref()).into_iter().collect();
+        std::iter::from_fn(move || {
+            while let Some(node_num) = stack.pop() {
+                stack.extend(self.children_node_nums(node_num).rev());
+                if self.is_terminal(node_num) {
+                    return Some(NodeRef::new(self, node_num));
+                }
+            }
+            None
+        })
+    }
+
+    /// Return the nodes of all keys in lexicographic order, without
+    /// collecting their labels. See [Trie::predictive_search_nodes].
+    pub fn iter_nodes(&self) -> impl Iterator<Item = NodeRef<'_, Label, Value>> + '_ {
+        self.predictive_search_nodes([])
+    }
+
+    /// Return true if both tries contain the same keys, ignoring their values.
+    ///
+    /// The tries are walked in lockstep, so no keys are collected.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let a = Trie::from_iter([("app", 1), ("apple", 2)]);
+    /// let b = Trie::from_iter([("apple", "x"), ("app", "y")]);
+    /// assert!(a.keys_eq(&b));
+    /// ```
+    pub fn keys_eq<V>(&self, other: &Trie<Label, V>) -> bool {
+        let mut stack = vec![(LoudsNodeNum(1), LoudsNodeNum(1))];
+        while let Some((node, other_node)) = stack.pop() {
+            if self.is_terminal(node) != other.is_terminal(other_node) {
+                return false;
+            }
+            let mut children = self.children_node_nums(node);
+            let mut other_children = other.children_node_nums(other_node);
+            loop {
+                match (children.next(), other_children.next()) {
+                    (Some(child), Some(other_child)) => {
+                        if self.label(child) != other.label(other_child) {
+                            return false;
+                        }
+                        stack.push((child, other_child));
+                    }
+                    (None, None) => break,
+                    _ => return false,
+                }
+            }
+        }
+        true
+    }
+
+    /// Return true if both tries have the same layout: the same nodes in the
+    /// same order with the same labels and values.
+    ///
+    /// [TrieBuilder::build] lays a trie out from its final entries alone, so
+    /// tries built from the same entries pushed in any order are
+    /// `canonical_eq`. Label runs and insertion order are not compared.
+    ///
+    /// [TrieBuilder::build]: super::TrieBuilder::build
+    pub fn canonical_eq(&self, other: &Self) -> bool
+    where
+        Value: PartialEq,
+    {
+        self.trie_labels.len() == other.trie_labels.len()
+            && self
+                .trie_labels
+                .iter()
+                .zip(&other.trie_labels)
+                .all(|(a, b)| a == b)
+            && (0..self.values.len()).all(|i| self.values.get(i) == other.values.get(i))
+            && (1..=self.trie_labels.len() as u64 + 1).all(|node| {
+                self.children_node_nums(LoudsNodeNum(node)).len()
+                    == other.children_node_nums(LoudsNodeNum(node)).len()
+            })
+    }
+
+    /// Return true if every key of `queries` is a key of this trie.
+    ///
+    /// Both tries are walked together, so shared prefixes of the queries are
+    /// only matched once.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let config = Trie::from_iter([("net.ipv4", 1), ("net.ipv6", 0), ("fs.inotify", 8)]);
+    /// let required = Trie::from_iter([("net.ipv4", ()), ("net.ipv6", ())]);
+    /// assert!(config.contains_all(&required));
+    /// ```
+    pub fn contains_all<V>(&self, queries: &Trie<Label, V>) -> bool {
+        let mut stack = vec![(LoudsNodeNum(1), LoudsNodeNum(1))];
+        while let Some((node, query_node)) = stack.pop() {
+            if queries.is_terminal(query_node) && !self.is_terminal(node) {
+                return false;
+            }
+            let mut matched = 0;
+            let query_children = queries.children_node_nums(query_node).len();
+            self.join_children(node, queries, query_node, |pair| {
+                matched += 1;
+                stack.push(pair);
+            });
+            if matched < query_children {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Return true if any key of `queries` is a key of this trie.
+    ///
+    /// Like [Trie::contains_all], both tries are walked together.
+    pub fn contains_any<V>(&self, queries: &Trie<Label, V>) -> bool {
+        let mut stack = vec![(LoudsNodeNum(1), LoudsNodeNum(1))];
+        while let Some((node, query_node)) = stack.pop() {
+            if queries.is_terminal(query_node) && self.is_terminal(node) {
+                return true;
+            }
+            self.join_children(node, queries, query_node, |pair| stack.push(pair));
+        }
+        false
+    }
+
+    /// Call `f` with each pair of children of `node` and `other_node` that
+    /// share a label.
+    fn join_children<V>(
+        &self,
+        node: LoudsNodeNum,
+        other: &Trie<Label, V>,
+        other_node: LoudsNodeNum,
+        mut f: impl FnMut((LoudsNodeNum, LoudsNodeNum)),
+    ) {
+        use std::cmp::Ordering;
+        let mut children = self.children_node_nums(node).peekable();
+        let mut other_children = other.children_node_nums(other_node).peekable();
+        while let (Some(&child), Some(&other_child)) = (children.peek(), other_children.peek()) {
+            match self.label(child).cmp(other.label(other_child)) {
+                Ordering::Less => {
+                    children.next();
+                }
+                Ordering::Greater => {
+                    other_children.next();
+                }
+                Ordering::Equal => {
+                    f((child, other_child));
+                    children.next();
+                    other_children.next();
+                }
+            }
+        }
+    }
+
+    /// Return the length of the longest key, or `None` if there are no keys.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("a", 0), ("app", 1), ("apple", 2), ("bee", 3)]);
+    /// assert_eq!(trie.max_key_len(), Some(5));
+    /// assert_eq!(trie.min_key_len(), Some(1));
+    /// assert_eq!(trie.key_len_histogram(), [0, 1, 0, 2, 0, 1]);
+    /// ```
+    pub fn max_key_len(&self) -> Option<usize> {
+        self.key_lens.last().map(|(len, _)| *len)
+    }
+
+    /// Return the length of the shortest key, or `None` if there are no keys.
+    pub fn min_key_len(&self) -> Option<usize> {
+        self.key_lens.first().map(|(len, _)| *len)
+    }
+
+    /// Return the number of keys of each length, indexed by length, up to
+    /// [Trie::max_key_len]. Only the lengths that occur are stored when the
+    /// trie is built; the histogram is laid out on each call.
+    pub fn key_len_histogram(&self) -> Vec<usize> {
+        let mut histogram = vec![0; self.max_key_len().map_or(0, |max| max + 1)];
+        for &(len, count) in &self.key_lens {
+            histogram[len] = count;
+        }
+        histogram
+    }
+
+    /// Return the number of keys.
+    pub(crate) fn key_count(&self) -> usize {
+        self.key_lens.iter().map(|(_, count)| count).sum()
+    }
+
+    /// Assemble a trie from its nodes, filling in the caches derived from
+    /// them: the key length counts and tiny paths. Label runs are left to
+    /// [Trie::build_label_runs].
+    pub(crate) fn from_parts(
+        louds: Louds,
+        trie_labels: Vec<Label>,
+        values: NodeValues<Value>,
+        insertion_order: Option<Vec<LoudsNodeNum>>,
+    ) -> Self {
+        let mut trie = Trie {
+            louds,
+            trie_labels,
+            values,
+            runs: None,
+            insertion_order,
+            key_lens: Vec::new(),
+            tiny: None,
+        };
+        trie.key_lens = trie.count_key_lens();
+        trie.tiny = trie.tiny_paths();
+        trie
+    }
+
+    /// Count the keys of each length, walking the nodes in breadth-first
+    /// order, which visits them by increasing depth.
+    pub(crate) fn count_key_lens(&self) -> Vec<(usize, usize)> {
+        let node_count = self.trie_labels.len() + 1;
+        let mut depths = vec![0; node_count];
+        let mut key_lens: Vec<(usize, usize)> = Vec::new();
+        for node in 1..=node_count as u64 {
+            let depth = depths[node as usize - 1];
+            if self.is_terminal(LoudsNodeNum(node)) {
+                match key_lens.last_mut() {
+                    Some((len, count)) if *len == depth => *count += 1,
+                    _ => key_lens.push((depth, 1)),
+                }
+            }
+            for child in self.children_node_nums(LoudsNodeNum(node)) {
+                depths[child.0 as usize - 1] = depth + 1;
+            }
+        }
+        key_lens
+    }
+
+    /// Return a read-only view of the nodes of this trie. See [crate::raw].
+    pub fn raw(&self) -> RawTrie<'_, Label, Value> {
+        RawTrie::new(self)
+    }
+
     /// Return true if `query` is a prefix.
     ///
     /// Note: A prefix may be an exact match or not, and an exact match may be a
     /// prefix or not.
+    ///
+    /// Labels are matched one by one, so in a `u8` trie of UTF-8 keys a query
+    /// ending partway through a `char` is a prefix too. See
+    /// [Trie::is_prefix_aligned] to rule that out.
     pub fn is_prefix(&self, query: impl AsRef<[Label]>) -> bool {
-        let mut cur_node_num = LoudsNodeNum(1);
+        match self.descend(query.as_ref()) {
+            // Are there more nodes after our query?
+            Descent::Node(node_num) => self.has_children_node_nums(node_num),
+            Descent::InRun => true,
+            Descent::Miss => false,
+        }
+    }
 
-        for chr in query.as_ref().iter() {
-            let children_node_nums: Vec<_> = self.children_node_nums(cur_node_num).collect();
-            let res = self.bin_search_by_children_labels(chr, &children_node_nums[..]);
-            match res {
-                Ok(j) => cur_node_num = children_node_nums[j],
-                Err(_) => return false,
+    /// Return true if some key starts with `query`, i.e. if
+    /// [Trie::predictive_search] would find anything, without setting up an
+    /// iterator. Unlike [Trie::is_prefix], `query` itself counts.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("app", 0), ("apple", 1)]);
+    /// assert!(trie.has_completions("ap"));
+    /// assert!(trie.has_completions("apple"));
+    /// assert!(!trie.is_prefix("apple"));
+    /// assert!(!trie.has_completions("b"));
+    /// ```
+    pub fn has_completions(&self, query: impl AsRef<[Label]>) -> bool {
+        match self.descend(query.as_ref()) {
+            Descent::Node(node_num) => {
+                self.is_terminal(node_num) || self.has_children_node_nums(node_num)
             }
+            Descent::InRun => true,
+            Descent::Miss => false,
         }
-        // Are there more nodes after our query?
-        self.has_children_node_nums(cur_node_num)
+    }
+
+    /// Return whether `query` is a key, a prefix of a key other than itself,
+    /// or both, like [NodeRef::answer], or `None` if it is neither.
+    ///
+    /// Unlike [Trie::node], this does not allocate.
+    pub fn answer(&self, query: impl AsRef<[Label]>) -> Option<Answer> {
+        let node = self.walk(query.as_ref())?;
+        Answer::new(self.has_children_node_nums(node), self.is_terminal(node))
+    }
+
+    /// Return true if `query` is a key.
+    ///
+    /// Unlike [Trie::exact_match], this never allocates: children are
+    /// searched in place and no labels are collected or cloned. Every trie
+    /// type's `contains_key`, and the set's `contains`, keeps to this.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("app", 0), ("apple", 1)]);
+    /// assert!(trie.contains_key("app"));
+    /// assert!(!trie.contains_key("appl"));
+    /// ```
+    pub fn contains_key(&self, query: impl AsRef<[Label]>) -> bool {
+        matches!(self.walk(query.as_ref()), Some(node) if self.is_terminal(node))
+    }
+
+    /// Return true if every nonempty proper prefix of `query` is a key, e.g.
+    /// to check that the parents of a path are registered. Stops at the first
+    /// prefix that is not, and never allocates. `query` itself need not be a
+    /// key.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("a", 0), ("ab", 1), ("abcd", 2)]);
+    /// assert!(trie.all_prefixes_exist("abc"));
+    /// assert!(!trie.all_prefixes_exist("abcde"));
+    /// assert!(trie.all_prefixes_exist("x"));
+    /// ```
+    pub fn all_prefixes_exist(&self, query: impl AsRef<[Label]>) -> bool {
+        let query = query.as_ref();
+        let Some((_, prefix)) = query.split_last() else {
+            return true;
+        };
+        let mut node = LoudsNodeNum(1);
+        for label in prefix {
+            match self.child(node, label) {
+                Some(child) if self.is_terminal(child) => node = child,
+                _ => return false,
+            }
+        }
+        true
+    }
+
+    /// Return the node reached by `query` without allocating.
+    pub(crate) fn walk<'q>(
+        &self,
+        query: impl IntoIterator<Item = &'q Label>,
+    ) -> Option<LoudsNodeNum>
+    where
+        Label: 'q,
+    {
+        let mut node = LoudsNodeNum(1);
+        for label in query {
+            node = self.child(node, label)?;
+        }
+        Some(node)
+    }
+
+    /// Take one step of a depth-first walk in lexicographic order: pop the
+    /// next `(depth, node)` off `stack`, leave its key in `path`, push its
+    /// children and return it. The first `query_len` labels of `path` are the
+    /// query the walk started from, and are kept.
+    pub(crate) fn dfs_step(
+        &self,
+        stack: &mut Vec<(usize, LoudsNodeNum)>,
+        path: &mut Vec<Label>,
+        query_len: usize,
+    ) -> Option<LoudsNodeNum>
+    where
+        Label: Clone,
+    {
+        let (depth, node) = stack.pop()?;
+        if depth > query_len {
+            path.truncate(depth - 1);
+            path.push(self.label(node).clone());
+        }
+        let children = self.children_node_nums(node);
+        stack.extend(children.rev().map(|child| (depth + 1, child)));
+        Some(node)
+    }
+
+    /// Return [Trie::answer] for each of `queries`, e.g. to sort a stream of
+    /// tokens into hits and misses without allocating per token.
+    ///
+    /// ```rust
+    /// use trie_rs::inc_search::Answer;
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("spam", 0), ("spammer", 1)]);
+    /// let answers: Vec<Option<Answer>> = trie.classify(["spa", "spam", "spammer", "ham"]).collect();
+    /// assert_eq!(
+    ///     answers,
+    ///     [Some(Answer::Prefix), Some(Answer::PrefixAndMatch), Some(Answer::Match), None]
+    /// );
+    /// ```
+    pub fn classify<'a, Q: AsRef<[Label]>>(
+        &'a self,
+        queries: impl IntoIterator<Item = Q> + 'a,
+    ) -> impl Iterator<Item = Option<Answer>> + 'a {
+        queries.into_iter().map(move |query| self.answer(query))
+    }
+
+    /// Return [Trie::answer] for each nonempty prefix of `query`, shortest
+    /// first, in one descent instead of one lookup per prefix, e.g. to
+    /// underline a word as it is typed. Once a prefix answers `None`, so do
+    /// all longer ones.
+    ///
+    /// ```rust
+    /// use trie_rs::inc_search::Answer;
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("spa", 0), ("spam", 1)]);
+    /// assert_eq!(
+    ///     trie.classify_path("spamx"),
+    ///     [
+    ///         Some(Answer::Prefix),
+    ///         Some(Answer::Prefix),
+    ///         Some(Answer::PrefixAndMatch),
+    ///         Some(Answer::Match),
+    ///         None
+    ///     ]
+    /// );
+    /// ```
+    pub fn classify_path(&self, query: impl AsRef<[Label]>) -> Vec<Option<Answer>> {
+        let query = query.as_ref();
+        let mut answers = Vec::with_capacity(query.len());
+        let mut node = Some(LoudsNodeNum(1));
+        for label in query {
+            node = node.and_then(|node| self.child(node, label));
+            answers.push(node.and_then(|node| {
+                Answer::new(self.has_children_node_nums(node), self.is_terminal(node))
+            }));
+        }
+        answers
     }
 
     /// Return all entries and their values that match `query`.
@@ -80,6 +559,45 @@ impl<Label: Ord, Value> Trie<Label, Value> {
         SearchIter::new(self, query)
     }
 
+    /// Return all entries within `max_edits` insertions, deletions, or
+    /// substitutions of `query`, with their edit distance, in lexicographic
+    /// order. See [FuzzyIter::transpositions] to also count swaps as one edit.
+    pub fn fuzzy_search<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+        max_edits: usize,
+    ) -> FuzzyIter<'_, Label, Value, C, M>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        FuzzyIter::new(self, query, max_edits)
+    }
+
+    /// Return all entries that start with the labels of `query` in order,
+    /// with up to `max_gap` other labels skipped before each query label but
+    /// the first, in lexicographic order. See [GapIter::gaps_at] to choose
+    /// where skips are allowed.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("git checkout", 0), ("git commit", 1), ("grep", 2)]);
+    /// let results: Vec<(String, &u8)> = trie.gap_search("gco", 4).collect();
+    /// assert_eq!(results, [("git checkout".to_string(), &0), ("git commit".to_string(), &1)]);
+    /// ```
+    pub fn gap_search<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+        max_gap: usize,
+    ) -> GapIter<'_, Label, Value, C, M>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        GapIter::new(self, query, max_gap)
+    }
+
     /// Return the postfixes and values of all entries that match `query`.
     pub fn postfix_search<C, M>(
         &self,
@@ -89,29 +607,19 @@ impl<Label: Ord, Value> Trie<Label, Value> {
         C: TryFromIterator<Label, M>,
         Label: Clone,
     {
-        let mut cur_node_num = LoudsNodeNum(1);
-
-        // Consumes query (prefix)
-        for chr in query.as_ref() {
-            let children_node_nums: Vec<_> = self.children_node_nums(cur_node_num).collect();
-            let res = self.bin_search_by_children_labels(chr, &children_node_nums[..]);
-            match res {
-                Ok(i) => cur_node_num = children_node_nums[i],
-                Err(_) => {
-                    return PostfixIter::empty(self);
-                }
-            }
+        match self.walk(query.as_ref()) {
+            Some(node_num) => PostfixIter::new(self, node_num),
+            None => PostfixIter::empty(self),
         }
-
-        PostfixIter::new(self, cur_node_num)
     }
 
     /// Returns an iterator across all keys in the trie.
     ///
     /// # Examples
     /// In the following example we illustrate how to iterate over all keys in the trie.
-    /// Note that the order of the keys is not guaranteed, as they will be returned in
-    /// lexicographical order.
+    /// The keys are always returned in lexicographical order of their labels,
+    /// regardless of the order they were pushed in. See
+    /// [iter_insertion_order](Trie::iter_insertion_order) for push order.
     ///
     /// ```rust
     /// use trie_rs::map::Trie;
@@ -127,6 +635,81 @@ impl<Label: Ord, Value> Trie<Label, Value> {
         self.postfix_search([])
     }
 
+    /// Return all entries in lexicographic order with mutable values, to
+    /// update them in one pass instead of an [Trie::exact_match_mut] per key.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let mut trie = Trie::from_iter([("a", 2.0), ("app", 6.0), ("be", 2.0)]);
+    /// let total: f64 = trie.iter::<String, _>().map(|(_, v)| v).sum();
+    /// for (_, frequency) in trie.iter_mut::<String, _>() {
+    ///     *frequency /= total;
+    /// }
+    /// assert_eq!(trie.exact_match("app"), Some(&0.6));
+    /// ```
+    pub fn iter_mut<C, M>(&mut self) -> IterMut<'_, Label, Value, C, M>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        IterMut::new(&self.louds, &self.trie_labels, self.values.iter_mut())
+    }
+
+    /// Return the entries whose keys are within `range`, in lexicographic
+    /// order. Each end may be included, excluded or unbounded; pass a pair of
+    /// [Bound](std::ops::Bound)s to choose.
+    ///
+    /// Keys are compared with the bounds as they are, so keys with labels at
+    /// their maximum, e.g. `u8::MAX`, are neither skipped nor need a
+    /// successor to be excluded.
+    ///
+    /// ```rust
+    /// use std::ops::Bound::{Excluded, Included, Unbounded};
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([(&[0x01][..], 0), (&[0xff], 1), (&[0xff, 0xff], 2)]);
+    /// let results: Vec<(Vec<u8>, &u8)> = trie.range((Excluded(&[0xff][..]), Unbounded)).collect();
+    /// assert_eq!(results, [(vec![0xff, 0xff], &2)]);
+    /// let results: Vec<(Vec<u8>, &u8)> = trie.range((Included(&[0x01][..]), Included(&[0xff][..]))).collect();
+    /// assert_eq!(results, [(vec![0x01], &0), (vec![0xff], &1)]);
+    /// ```
+    pub fn range<C, M>(&self, range: impl RangeBounds<[Label]>) -> RangeIter<'_, Label, Value, C, M>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        RangeIter::new(self, range.start_bound(), range.end_bound())
+    }
+
+    /// Returns an iterator across all entries in the order they were pushed,
+    /// or `None` unless the trie was built after
+    /// [TrieBuilder::keep_insertion_order](crate::map::TrieBuilder::keep_insertion_order).
+    ///
+    /// ```rust
+    /// use trie_rs::map::TrieBuilder;
+    ///
+    /// let mut builder = TrieBuilder::new();
+    /// builder.keep_insertion_order();
+    /// builder.push("git status", 0);
+    /// builder.push("cargo test", 1);
+    /// builder.push("git add", 2);
+    /// builder.push("git status", 3);
+    /// let trie = builder.build();
+    ///
+    /// let results: Vec<(String, &u8)> = trie.iter_insertion_order().unwrap().collect();
+    /// assert_eq!(results, [("cargo test".to_string(), &1), ("git add".to_string(), &2), ("git status".to_string(), &3)]);
+    /// ```
+    pub fn iter_insertion_order<C, M>(&self) -> Option<InsertionOrderIter<'_, Label, Value, C, M>>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        self.insertion_order
+            .as_ref()
+            .map(|nodes| InsertionOrderIter::new(self, nodes))
+    }
+
     /// Return the common prefixes of `query`.
     pub fn common_prefix_search<C, M>(
         &self,
@@ -139,28 +722,118 @@ impl<Label: Ord, Value> Trie<Label, Value> {
         PrefixIter::new(self, query)
     }
 
+    /// Return the common prefixes of `query` along with the offset into
+    /// `query` where each one ends.
+    ///
+    /// The offset counts `Label`s, so it may be used to advance a cursor into
+    /// the query without recomputing the length of the collected key.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie: Trie<char, u8> = Trie::from_iter([("す".chars().collect::<Vec<_>>(), 0), ("すし".chars().collect(), 1)]);
+    /// let query: Vec<char> = "すしや".chars().collect();
+    /// let results: Vec<(String, usize, &u8)> = trie.common_prefix_search_with_offsets(&query).collect();
+    /// assert_eq!(results, [("す".to_string(), 1, &0), ("すし".to_string(), 2, &1)]);
+    /// ```
+    pub fn common_prefix_search_with_offsets<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> PrefixOffsetIter<'_, Label, Value, C, M>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        PrefixOffsetIter::new(self, query)
+    }
+
+    /// Return the length and value of the longest key that is a prefix of
+    /// `query`, `query` included, in one descent without allocating; the last
+    /// result [Trie::common_prefix_search] would yield. Tokenizers and routers
+    /// may advance by the length. The match is exact when the length equals
+    /// `query`'s, so this also suits "most specific setting wins" lookups.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("/", 0), ("/api", 1), ("/api/users", 2)]);
+    /// assert_eq!(trie.longest_match("/api/users/7"), Some((10, &2)));
+    /// assert_eq!(trie.longest_match("/api/user"), Some((4, &1)));
+    /// assert_eq!(trie.longest_match("/"), Some((1, &0)));
+    /// assert_eq!(trie.longest_match("api"), None);
+    /// ```
+    pub fn longest_match(&self, query: impl AsRef<[Label]>) -> Option<(usize, &Value)> {
+        let mut node = LoudsNodeNum(1);
+        let mut found = None;
+        for (i, label) in query.as_ref().iter().enumerate() {
+            match self.child(node, label) {
+                Some(child) => node = child,
+                None => break,
+            }
+            if let Some(value) = self.value(node) {
+                found = Some((i + 1, value));
+            }
+        }
+        found
+    }
+
     /// Return the longest shared prefix or terminal of `query`.
     pub fn longest_prefix<C, M>(&self, query: impl AsRef<[Label]>) -> Option<C>
     where
         C: TryFromIterator<Label, M>,
         Label: Clone,
     {
+        self.longest_prefix_node_nums(query.as_ref()).map(|buffer| {
+            buffer
+                .into_iter()
+                .map(|x| self.label(x).clone())
+                .try_collect()
+                .expect("Could not collect")
+        })
+    }
+
+    /// Like [Trie::longest_prefix], but split the result into the number of
+    /// `Label`s matched from `query` and the labels the trie extended it by.
+    ///
+    /// Tab completion may use the split to tell the user's input from the
+    /// unique continuation it inserts.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("application", 0), ("apply", 1), ("bee", 2)]);
+    /// assert_eq!(trie.longest_prefix_with_extension::<String, _>("ap"), Some((2, "pl".to_string())));
+    /// assert_eq!(trie.longest_prefix_with_extension::<String, _>("bee"), Some((3, "".to_string())));
+    /// assert_eq!(trie.longest_prefix_with_extension::<String, _>("c"), None);
+    /// ```
+    pub fn longest_prefix_with_extension<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> Option<(usize, C)>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        let matched_len = query.as_ref().len();
+        self.longest_prefix_node_nums(query.as_ref()).map(|buffer| {
+            let extension = buffer[matched_len..]
+                .iter()
+                .map(|x| self.label(*x).clone())
+                .try_collect()
+                .expect("Could not collect");
+            (matched_len, extension)
+        })
+    }
+
+    /// Return the nodes of [Trie::longest_prefix] from the root down.
+    fn longest_prefix_node_nums(&self, query: &[Label]) -> Option<Vec<LoudsNodeNum>> {
         let mut cur_node_num = LoudsNodeNum(1);
         let mut buffer = Vec::new();
 
         // Consumes query (prefix)
-        for chr in query.as_ref() {
-            let children_node_nums: Vec<_> = self.children_node_nums(cur_node_num).collect();
-            let res = self.bin_search_by_children_labels(chr, &children_node_nums[..]);
-            match res {
-                Ok(i) => {
-                    cur_node_num = children_node_nums[i];
-                    buffer.push(cur_node_num);
-                }
-                Err(_) => {
-                    return None;
-                }
-            }
+        for chr in query {
+            cur_node_num = self.child(cur_node_num, chr)?;
+            buffer.push(cur_node_num);
         }
 
         // Walk the trie as long as there is only one path and it isn't a terminal value.
@@ -176,17 +849,25 @@ impl<Label: Ord, Value> Trie<Label, Value> {
                 _ => break,
             }
         }
-        if buffer.is_empty() {
-            None
-        } else {
-            Some(
-                buffer
-                    .into_iter()
-                    .map(|x| self.label(x).clone())
-                    .try_collect()
-                    .expect("Could not collect"),
-            )
-        }
+        (!buffer.is_empty()).then_some(buffer)
+    }
+
+    pub(crate) fn node_num(&self, query: &[Label]) -> Option<LoudsNodeNum> {
+        self.walk(query)
+    }
+
+    /// Return the labels from the root to `node_num`.
+    pub(crate) fn prefix_of<C, M>(&self, node_num: LoudsNodeNum) -> C
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        let mut v: Vec<Label> = self
+            .child_to_ancestors(node_num)
+            .map(|node| self.label(node).clone())
+            .collect();
+        v.reverse();
+        v.into_iter().try_collect().expect("Could not collect")
     }
 
     pub(crate) fn has_children_node_nums(&self, node_num: LoudsNodeNum) -> bool {
@@ -196,25 +877,41 @@ impl<Label: Ord, Value> Trie<Label, Value> {
             .is_some()
     }
 
-    pub(crate) fn children_node_nums(&self, node_num: LoudsNodeNum) -> ChildNodeIter {
+    pub(crate) fn children_node_nums(&self, node_num: LoudsNodeNum) -> ChildNodeIter<'_> {
         self.louds.parent_to_children_nodes(node_num)
     }
 
-    pub(crate) fn bin_search_by_children_labels(
-        &self,
-        query: &Label,
-        children_node_nums: &[LoudsNodeNum],
-    ) -> Result<usize, usize> {
-        children_node_nums.binary_search_by(|child_node_num| self.label(*child_node_num).cmp(query))
+    /// Return the child of `node_num` labeled `label`. Children have
+    /// consecutive node numbers, so their labels are searched in place.
+    pub(crate) fn child(&self, node_num: LoudsNodeNum, label: &Label) -> Option<LoudsNodeNum> {
+        let (first, labels) = self.children_span(node_num)?;
+        let i = labels.binary_search(label).ok()?;
+        Some(LoudsNodeNum(first.0 + i as u64))
+    }
+
+    /// Return the labels of the children of `node_num` in order, which are
+    /// stored next to each other.
+    pub(crate) fn child_labels(&self, node_num: LoudsNodeNum) -> &[Label] {
+        self.children_span(node_num)
+            .map_or(&[], |(_, labels)| labels)
+    }
+
+    /// Return the first child of `node_num` and the labels of all its
+    /// children, or `None` if it has none.
+    fn children_span(&self, node_num: LoudsNodeNum) -> Option<(LoudsNodeNum, &[Label])> {
+        let mut children = self.children_node_nums(node_num);
+        let first = children.next()?;
+        let start = (first.0 - 2) as usize;
+        Some((first, &self.trie_labels[start..start + children.len() + 1]))
     }
 
     pub(crate) fn label(&self, node_num: LoudsNodeNum) -> &Label {
-        &self.trie_labels[(node_num.0 - 2) as usize].label
+        &self.trie_labels[(node_num.0 - 2) as usize]
     }
 
     pub(crate) fn is_terminal(&self, node_num: LoudsNodeNum) -> bool {
         if node_num.0 >= 2 {
-            self.trie_labels[(node_num.0 - 2) as usize].value.is_some()
+            self.values.is_some((node_num.0 - 2) as usize)
         } else {
             false
         }
@@ -222,17 +919,26 @@ impl<Label: Ord, Value> Trie<Label, Value> {
 
     pub(crate) fn value(&self, node_num: LoudsNodeNum) -> Option<&Value> {
         if node_num.0 >= 2 {
-            self.trie_labels[(node_num.0 - 2) as usize].value.as_ref()
+            self.values.get((node_num.0 - 2) as usize)
         } else {
             None
         }
     }
 
     pub(crate) fn value_mut(&mut self, node_num: LoudsNodeNum) -> Option<&mut Value> {
-        self.trie_labels[(node_num.0 - 2) as usize].value.as_mut()
+        self.values.get_mut((node_num.0 - 2) as usize)
     }
 
-    pub(crate) fn child_to_ancestors(&self, node_num: LoudsNodeNum) -> AncestorNodeIter {
+    pub(crate) fn parent(&self, node_num: LoudsNodeNum) -> Option<LoudsNodeNum> {
+        if node_num.0 > 1 {
+            let index = self.louds.node_num_to_index(node_num);
+            Some(self.louds.child_to_parent(index))
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn child_to_ancestors(&self, node_num: LoudsNodeNum) -> AncestorNodeIter<'_> {
         self.louds.child_to_ancestors(node_num)
     }
 }
@@ -332,6 +1038,191 @@ mod search_tests {
         assert_eq!(trie.exact_match("application"), Some(&4));
     }
 
+    #[test]
+    fn keys_eq() {
+        let trie = build_trie();
+        let same: Trie<u8, String> = Trie::from_iter(
+            ["better", "アップル🍎", "application", "apple", "app", "a"]
+                .iter()
+                .map(|k| (k, k.to_string())),
+        );
+        assert!(trie.keys_eq(&same));
+        assert!(same.keys_eq(&trie));
+
+        let missing = Trie::from_iter([("a", 0), ("app", 1), ("apple", 2), ("better", 3)]);
+        assert!(!trie.keys_eq(&missing));
+        assert!(!missing.keys_eq(&trie));
+
+        let prefix_only = Trie::from_iter([("a", 0), ("appl", 1), ("apple", 2)]);
+        let terminal = Trie::from_iter([("a", 0), ("app", 1), ("apple", 2)]);
+        assert!(!prefix_only.keys_eq(&terminal));
+    }
+
+    #[test]
+    fn contains_all_and_any() {
+        let trie = build_trie();
+        let queries = |keys: &[&str]| Trie::from_iter(keys.iter().map(|k| (k, ())));
+        assert!(trie.contains_all(&queries(&["app", "apple", "アップル🍎"])));
+        assert!(trie.contains_all(&queries(&[])));
+        assert!(!trie.contains_all(&queries(&["app", "appl"])));
+        assert!(!trie.contains_all(&queries(&["app", "zoo"])));
+        assert!(trie.contains_any(&queries(&["appl", "zoo", "better"])));
+        assert!(!trie.contains_any(&queries(&["appl", "zoo", "bet"])));
+        assert!(!trie.contains_any(&queries(&[])));
+    }
+
+    #[test]
+    fn children_values() {
+        let trie = build_trie();
+        let values: Vec<(&u8, &u8)> = trie.children_values("").collect();
+        assert_eq!(values, [(&b'a', &0)]);
+        let values: Vec<(&u8, &u8)> = trie.children_values("appl").collect();
+        assert_eq!(values, [(&b'e', &2)]);
+        assert_eq!(trie.children_values("ap").count(), 1);
+        assert_eq!(trie.children_values("zoo").count(), 0);
+    }
+
+    #[test]
+    fn prefixes_of_nodes() {
+        let trie = build_trie();
+        let labels: Vec<Option<&u8>> = trie
+            .prefixes_of_nodes("application")
+            .map(|node| node.label())
+            .collect();
+        assert_eq!(labels, [Some(&b'a'), Some(&b'p'), Some(&b'n')]);
+        assert_eq!(trie.prefixes_of_nodes("apz").count(), 1);
+        assert_eq!(trie.prefixes_of_nodes("").count(), 0);
+        assert_eq!(trie.prefixes_of_nodes("b").count(), 0);
+    }
+
+    #[test]
+    fn result_collect() {
+        use crate::try_collect::LossyIterator;
+        let trie = build_trie();
+        // Splits the 3-byte "ア".
+        let postfixes: Vec<Result<String, _>> = trie
+            .postfix_search(&"\u{30a2}".as_bytes()[..1])
+            .map(|(k, _)| k)
+            .collect();
+        assert_eq!(postfixes.len(), 1);
+        assert!(postfixes[0].is_err());
+        let postfixes: Vec<(String, &u8)> = trie
+            .postfix_search::<Result<String, _>, _>(&"\u{30a2}".as_bytes()[..2])
+            .lossy()
+            .collect();
+        assert_eq!(postfixes, [("\u{fffd}ップル🍎".to_string(), &5)]);
+        let prefixes: Vec<(Result<String, _>, &u8)> = trie.common_prefix_search("apple").collect();
+        assert!(prefixes.iter().all(|(k, _)| k.is_ok()));
+    }
+
+    #[test]
+    fn has_completions() {
+        let trie = build_trie();
+        for query in ["", "a", "ap", "app", "application", "アップル🍎"] {
+            assert!(trie.has_completions(query), "{}", query);
+            assert!(trie.predictive_search::<Vec<u8>, _>(query).next().is_some());
+        }
+        for query in ["b_", "applications", "z"] {
+            assert!(!trie.has_completions(query), "{}", query);
+        }
+        let empty: Trie<u8, u8> = TrieBuilder::new().build();
+        assert!(!empty.has_completions(""));
+    }
+
+    #[test]
+    fn classify() {
+        let mut trie = build_trie();
+        let queries = ["", "a", "ap", "app", "application", "アップル🍎", "b_", "z"];
+        let expected: Vec<_> = queries
+            .iter()
+            .map(|query| trie.node(query).and_then(|node| node.answer()))
+            .collect();
+        let answers: Vec<_> = trie.classify(queries).collect();
+        assert_eq!(answers, expected);
+        trie.build_label_runs();
+        assert_eq!(trie.classify(queries).collect::<Vec<_>>(), expected);
+
+        for query in queries {
+            let query = query.as_bytes();
+            let expected: Vec<_> = (1..=query.len())
+                .map(|len| trie.answer(&query[..len]))
+                .collect();
+            assert_eq!(trie.classify_path(query), expected);
+        }
+    }
+
+    #[test]
+    fn all_prefixes_exist() {
+        let trie = Trie::from_iter([("/", 0), ("/a", 1), ("/a/", 2), ("/a/b", 3), ("/c", 4)]);
+        for query in ["", "/", "/a", "/a/", "/a/b", "/a/bc", "/c", "/x", "//"] {
+            assert!(trie.all_prefixes_exist(query), "{}", query);
+        }
+        for query in ["/a/b/c", "/cd/e", "x/"] {
+            assert!(!trie.all_prefixes_exist(query), "{}", query);
+        }
+        let expected =
+            |query: &str| (1..query.len()).all(|len| trie.exact_match(&query[..len]).is_some());
+        for query in ["/a/b/c", "/a/bc", "/cd/e"] {
+            assert_eq!(trie.all_prefixes_exist(query), expected(query));
+        }
+    }
+
+    #[test]
+    fn nodes() {
+        let trie = build_trie();
+        let keys: Vec<String> = trie.iter_nodes().map(|node| node.prefix()).collect();
+        let expected: Vec<String> = trie.iter().map(|(k, _)| k).collect();
+        assert_eq!(keys, expected);
+        let values: Vec<&u8> = trie
+            .predictive_search_nodes("app")
+            .map(|node| node.value().unwrap())
+            .collect();
+        assert_eq!(values, [&1, &2, &4]);
+        assert_eq!(trie.predictive_search_nodes("apz").count(), 0);
+        assert_eq!(trie.predictive_search_nodes("apple").count(), 1);
+    }
+
+    #[test]
+    fn key_lens() {
+        let trie = build_trie();
+        assert_eq!(trie.max_key_len(), Some("アップル🍎".len()));
+        assert_eq!(trie.min_key_len(), Some(1));
+        let histogram = trie.key_len_histogram();
+        assert_eq!(histogram.iter().sum::<usize>(), 6);
+        assert_eq!(histogram[3], 1);
+        assert_eq!(histogram[6], 1);
+
+        // Only the lengths that occur are stored.
+        let long = Trie::from_iter([(vec![b'a'; 10_000], 0), (vec![b'b'], 1)]);
+        assert_eq!(long.key_lens.len(), 2);
+        assert_eq!(long.max_key_len(), Some(10_000));
+        assert_eq!(long.key_len_histogram().len(), 10_001);
+
+        let empty: Trie<u8, u8> = TrieBuilder::new().build();
+        assert_eq!(empty.max_key_len(), None);
+        assert_eq!(empty.min_key_len(), None);
+        assert!(empty.key_len_histogram().is_empty());
+    }
+
+    #[test]
+    fn iter_insertion_order() {
+        let trie = build_trie();
+        assert!(trie.iter_insertion_order::<String, _>().is_none());
+
+        let mut builder = TrieBuilder::new();
+        builder.keep_insertion_order();
+        for (k, v) in [("b", 0), ("a", 1), ("ab", 2), ("b", 3), ("c", 4)] {
+            builder.push(k, v);
+        }
+        let trie = builder.build();
+        let results: Vec<(String, &u8)> = trie.iter_insertion_order().unwrap().collect();
+        let expected: Vec<(String, &u8)> = [("a", 1), ("ab", 2), ("b", 3), ("c", 4)]
+            .iter()
+            .map(|(k, v)| (k.to_string(), v))
+            .collect();
+        assert_eq!(results, expected);
+    }
+
     #[test]
     fn use_empty_queries() {
         let trie = build_trie();
@@ -453,6 +1344,80 @@ mod search_tests {
         }
     }
 
+    mod longest_prefix_with_extension_tests {
+        macro_rules! parameterized_tests {
+            ($($name:ident: $value:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let (query, expected) = $value;
+                    let trie = super::build_trie();
+                    let result: Option<(usize, String)> = trie.longest_prefix_with_extension(query);
+                    let expected = expected.map(|(len, ext): (usize, &str)| (len, ext.to_string()));
+                    assert_eq!(result, expected);
+                }
+            )*
+            }
+        }
+
+        parameterized_tests! {
+            t1: ("a", Some((1, ""))),
+            t2: ("ap", Some((2, "p"))),
+            t3: ("appli", Some((5, "cation"))),
+            t4: ("b", Some((1, "etter"))),
+            t5: ("appler", None),
+            t6: ("", None),
+        }
+    }
+
+    mod longest_match_tests {
+        macro_rules! parameterized_tests {
+            ($($name:ident: $value:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let (query, expected) = $value;
+                    let trie = super::build_trie();
+                    let result = trie.longest_match(query);
+                    assert_eq!(result, expected);
+                }
+            )*
+            }
+        }
+
+        parameterized_tests! {
+            t1: ("a", Some((1, &0))),
+            t2: ("apple", Some((5, &2))),
+            t3: ("appl", Some((3, &1))),
+            t4: ("applesDONTEXIST", Some((5, &2))),
+            t5: ("bet", None),
+            t6: ("", None),
+            t7: ("アップル🍎🍎", Some(("アップル🍎".len(), &5))),
+        }
+
+        #[test]
+        fn longest_match_is_last_common_prefix() {
+            let trie = super::build_trie();
+            for query in [
+                "",
+                "a",
+                "ap",
+                "app",
+                "apple",
+                "applesauce",
+                "bett",
+                "better",
+                "アップル🍎🍎",
+            ] {
+                let expected = trie
+                    .common_prefix_search::<Vec<u8>, _>(query)
+                    .last()
+                    .map(|(key, value)| (key.len(), value));
+                assert_eq!(trie.longest_match(query), expected, "{}", query);
+            }
+        }
+    }
+
     mod predictive_search_tests {
         macro_rules! parameterized_tests {
             ($($name:ident: $value:expr,)*) => {
@@ -508,6 +1473,30 @@ mod search_tests {
         }
     }
 
+    mod common_prefix_search_with_offsets_tests {
+        macro_rules! parameterized_tests {
+            ($($name:ident: $value:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let (query, expected_results) = $value;
+                    let trie = super::build_trie();
+                    let results: Vec<(String, usize, &u8)> = trie.common_prefix_search_with_offsets(query).collect();
+                    let expected_results: Vec<(String, usize, &u8)> = expected_results.iter().map(|s| (s.0.to_string(), s.1, &s.2)).collect();
+                    assert_eq!(results, expected_results);
+                }
+            )*
+            }
+        }
+
+        parameterized_tests! {
+            t1: ("a", vec![("a", 1, 0)]),
+            t2: ("appler", vec![("a", 1, 0), ("app", 3, 1), ("apple", 5, 2)]),
+            t3: ("bette", Vec::<(&str, usize, u8)>::new()),
+            t4: ("アップル🍎🍏", vec![("アップル🍎", 16, 5)]),
+        }
+    }
+
     mod postfix_search_tests {
         macro_rules! parameterized_tests {
             ($($name:ident: $value:expr,)*) => {
@@ -1,7 +1,6 @@
 use crate::internal_data_structure::naive_trie::NaiveTrie;
-use crate::map::TrieLabel;
-use crate::map::{Trie, TrieBuilder};
-use louds_rs::Louds;
+use crate::map::{NodeValues, Trie, TrieBuilder};
+use louds_rs::{Louds, LoudsNodeNum};
 
 impl<Label: Ord, Value> Default for TrieBuilder<Label, Value> {
     fn default() -> Self {
@@ -13,10 +12,25 @@ impl<Label: Ord, Value> TrieBuilder<Label, Value> {
     /// Return a [TrieBuilder].
     pub fn new() -> Self {
         let naive_trie = NaiveTrie::make_root();
-        Self { naive_trie }
+        Self {
+            naive_trie,
+            keep_insertion_order: false,
+        }
+    }
+
+    /// Remember the order entries are pushed in, so the built trie can be
+    /// iterated in that order with [Trie::iter_insertion_order].
+    ///
+    /// A key pushed more than once takes the position of its last push, i.e.
+    /// the push whose value is kept.
+    pub fn keep_insertion_order(&mut self) {
+        self.keep_insertion_order = true;
     }
 
     /// Add a cloneable entry and value.
+    ///
+    /// # Panics
+    /// If the entry is empty. Use [TrieBuilder::try_push] for untrusted input.
     pub fn push<Arr: AsRef<[Label]>>(&mut self, entry: Arr, value: Value)
     where
         Label: Clone,
@@ -25,23 +39,36 @@ impl<Label: Ord, Value> TrieBuilder<Label, Value> {
     }
 
     /// Add an entry and value.
+    ///
+    /// # Panics
+    /// If the entry is empty. Use [TrieBuilder::try_insert] for untrusted
+    /// input.
     pub fn insert<Arr: IntoIterator<Item = Label>>(&mut self, entry: Arr, value: Value) {
         self.naive_trie.push(entry.into_iter(), value);
     }
 
     /// Build a [Trie].
+    ///
+    /// The result depends only on the entries, not on the order they were
+    /// pushed in: a key pushed more than once keeps the value of its last
+    /// push, and otherwise any two push orders of the same entries build
+    /// tries that are [Trie::canonical_eq].
     pub fn build(self) -> Trie<Label, Value> {
         let mut louds_bits: Vec<bool> = vec![true, false];
-        let mut trie_labels: Vec<TrieLabel<Label, Value>> = vec![];
+        let mut trie_labels: Vec<Label> = vec![];
+        let mut values = NodeValues::default();
+        let mut terminals: Vec<(usize, LoudsNodeNum)> = vec![];
         for node in self.naive_trie.into_iter() {
             match node {
                 NaiveTrie::Root(_) => {}
                 NaiveTrie::IntermOrLeaf(n) => {
                     louds_bits.push(true);
-                    trie_labels.push(TrieLabel {
-                        label: n.label,
-                        value: n.value,
-                    });
+                    if self.keep_insertion_order && n.value.is_some() {
+                        let node_num = LoudsNodeNum(trie_labels.len() as u64 + 2);
+                        terminals.push((n.seq, node_num));
+                    }
+                    trie_labels.push(n.label);
+                    values.push(n.value);
                 }
                 NaiveTrie::PhantomSibling => {
                     louds_bits.push(false);
@@ -49,7 +76,199 @@ impl<Label: Ord, Value> TrieBuilder<Label, Value> {
             }
         }
         let louds = Louds::from(&louds_bits[..]);
+        let insertion_order = self.keep_insertion_order.then(|| {
+            terminals.sort_by_key(|(seq, _)| *seq);
+            terminals
+                .into_iter()
+                .map(|(_, node_num)| node_num)
+                .collect()
+        });
+
+        Trie::from_parts(louds, trie_labels, values, insertion_order)
+    }
+}
+
+impl<Label: Ord, Value> Trie<Label, Value> {
+    /// Lay the trie out anew, dropping branches that no longer lead to a key,
+    /// e.g. after values were removed from a long-lived trie.
+    ///
+    /// Nodes are streamed in breadth-first order straight into the new LOUDS,
+    /// without going through a [TrieBuilder]. Label runs are dropped; call
+    /// [Trie::build_label_runs] again if needed. Insertion order is kept.
+    pub fn rebuild(self) -> Self {
+        let node_count = self.trie_labels.len() + 1;
+        // live[LoudsNodeNum - 1]: whether a key ends at or below the node.
+        let mut live: Vec<bool> = (1..=node_count as u64)
+            .map(|node| self.is_terminal(LoudsNodeNum(node)))
+            .collect();
+        // Children are numbered after their parents.
+        for node in (2..=node_count as u64).rev() {
+            if live[node as usize - 1] {
+                let parent = self.parent(LoudsNodeNum(node)).unwrap();
+                live[parent.0 as usize - 1] = true;
+            }
+        }
+        live[0] = true;
+
+        // Live nodes keep their breadth-first order, so a node's new number
+        // is its rank among them.
+        let mut new_node_nums = vec![None; node_count];
+        let mut louds_bits: Vec<bool> = vec![true, false];
+        let mut next = 1;
+        for node in 1..=node_count as u64 {
+            if !live[node as usize - 1] {
+                continue;
+            }
+            new_node_nums[node as usize - 1] = Some(LoudsNodeNum(next));
+            next += 1;
+            for child in self.children_node_nums(LoudsNodeNum(node)) {
+                if live[child.0 as usize - 1] {
+                    louds_bits.push(true);
+                }
+            }
+            louds_bits.push(false);
+        }
+        let louds = Louds::from(&louds_bits[..]);
+        // Removed keys may leave live nodes, which are no longer in order.
+        let values = &self.values;
+        let insertion_order = self.insertion_order.map(|nodes| {
+            nodes
+                .into_iter()
+                .filter(|node| values.is_some(node.0 as usize - 2))
+                .filter_map(|node| new_node_nums[node.0 as usize - 1])
+                .collect()
+        });
+        let trie_labels = self
+            .trie_labels
+            .into_iter()
+            .zip(&live[1..])
+            .filter_map(|(label, live)| live.then_some(label))
+            .collect();
+        let values = self.values.retain(live[1..].iter().copied());
+
+        Trie::from_parts(louds, trie_labels, values, insertion_order)
+    }
+}
+
+#[cfg(test)]
+mod canonical_tests {
+    use crate::map::{Trie, TrieBuilder};
+    use rand::{seq::SliceRandom, SeedableRng};
+
+    const KEYS: [&str; 10] = [
+        "a",
+        "app",
+        "apple",
+        "application",
+        "apply",
+        "better",
+        "bet",
+        "b",
+        "アップル🍎",
+        "アップ",
+    ];
+
+    fn build(entries: &[(&str, usize)]) -> Trie<u8, usize> {
+        let mut builder = TrieBuilder::new();
+        for (key, value) in entries {
+            builder.push(key, *value);
+        }
+        builder.build()
+    }
+
+    #[test]
+    fn any_push_order() {
+        let entries: Vec<(&str, usize)> = KEYS.iter().copied().zip(0..).collect();
+        let expected = build(&entries);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(934);
+        for _ in 0..100 {
+            let mut shuffled = entries.clone();
+            shuffled.shuffle(&mut rng);
+            assert!(build(&shuffled).canonical_eq(&expected));
+        }
+    }
+
+    #[test]
+    fn sorted_and_reversed() {
+        let mut entries: Vec<(&str, usize)> = KEYS.iter().copied().zip(0..).collect();
+        entries.sort();
+        let sorted = build(&entries);
+        entries.reverse();
+        assert!(build(&entries).canonical_eq(&sorted));
+        let keys: Vec<String> = sorted.iter().map(|(k, _)| k).collect();
+        let mut expected: Vec<&str> = KEYS.to_vec();
+        expected.sort();
+        assert_eq!(keys, expected);
+    }
+
+    #[test]
+    fn duplicates_keep_last_value() {
+        let entries: Vec<(&str, usize)> = KEYS.iter().copied().zip(0..).collect();
+        let expected = build(&entries);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(934);
+        for _ in 0..100 {
+            // Push stale values for some keys before the final ones.
+            let mut stale: Vec<(&str, usize)> = entries
+                .choose_multiple(&mut rng, 4)
+                .map(|(key, value)| (*key, value + 100))
+                .collect();
+            let mut shuffled = entries.clone();
+            shuffled.shuffle(&mut rng);
+            stale.append(&mut shuffled);
+            assert!(build(&stale).canonical_eq(&expected));
+        }
+    }
+
+    #[test]
+    fn differences() {
+        let trie = build(&[("ab", 0), ("c", 1)]);
+        assert!(!trie.canonical_eq(&build(&[("ab", 0), ("c", 2)])));
+        assert!(!trie.canonical_eq(&build(&[("ab", 0), ("d", 1)])));
+        assert!(!trie.canonical_eq(&build(&[("ab", 0)])));
+        // Same labels and values in the same order, but "x" hangs off "c".
+        let ax = build(&[("a", 0), ("ax", 0), ("c", 0)]);
+        let cx = build(&[("a", 0), ("c", 0), ("cx", 0)]);
+        assert!(!ax.canonical_eq(&cx));
+    }
+}
+
+#[cfg(test)]
+mod rebuild_tests {
+    use crate::map::{Trie, TrieBuilder};
+
+    #[test]
+    fn drops_dead_branches() {
+        let mut builder = TrieBuilder::new();
+        builder.keep_insertion_order();
+        for (k, v) in [("apple", 0), ("b", 1), ("app", 2), ("bee", 3), ("beer", 4)] {
+            builder.push(k, v);
+        }
+        let mut trie = builder.build();
+        for key in ["apple", "beer"] {
+            let node = trie.node(key).unwrap().node_num();
+            trie.values.take(node.0 as usize - 2);
+        }
+        let trie = trie.rebuild();
+        assert_eq!(trie.trie_labels.len(), 6);
+        assert!(!trie.is_prefix("app"));
+        assert!(trie.node("bee").unwrap().children().next().is_none());
+        let expected = Trie::from_iter([("b", 1), ("app", 2), ("bee", 3)]);
+        assert!(trie.keys_eq(&expected));
+        let results: Vec<(String, &u8)> = trie.iter().collect();
+        let expected: Vec<(String, &u8)> = expected.iter().collect();
+        assert_eq!(results, expected);
+        let order: Vec<String> = trie
+            .iter_insertion_order()
+            .unwrap()
+            .map(|(k, _)| k)
+            .collect();
+        assert_eq!(order, ["b", "app", "bee"]);
+    }
 
-        Trie { louds, trie_labels }
+    #[test]
+    fn empty() {
+        let trie: Trie<u8, u8> = TrieBuilder::new().build().rebuild();
+        assert_eq!(trie.iter::<Vec<u8>, _>().count(), 0);
+        assert!(trie.node("").is_some());
     }
 }
@@ -0,0 +1,113 @@
+use super::NodeRef;
+use crate::try_collect::Collect;
+
+/// A boxed node behind a [TrieRef].
+pub type DynTrieRef<'a, Label, Value> = Box<dyn TrieRef<'a, Label, Value> + 'a>;
+
+/// An object-safe view of a trie node, so nodes can cross a `dyn` boundary,
+/// e.g. into plugins, without their concrete type. Implemented by [NodeRef],
+/// which also covers nodes of a [crate::Trie] with `Value = ()`.
+///
+/// Navigation and search results are boxed.
+///
+/// ```rust
+/// use trie_rs::map::{Trie, TrieRef};
+///
+/// fn describe(node: &dyn TrieRef<'_, u8, u8>) -> String {
+///     let mut key = Vec::new();
+///     node.write_prefix_into(&mut key);
+///     format!("{} with {} children", String::from_utf8(key).unwrap(), node.children().count())
+/// }
+///
+/// let trie = Trie::from_iter([("app", 0), ("apple", 1), ("apply", 2)]);
+/// let node = trie.node("appl").unwrap();
+/// assert_eq!(describe(&node), "appl with 2 children");
+/// let keys: Vec<Vec<u8>> = node.entries().map(|(key, _)| key).collect();
+/// assert_eq!(keys, [b"apple".to_vec(), b"apply".to_vec()]);
+/// ```
+pub trait TrieRef<'a, Label: 'a, Value: 'a> {
+    /// Return the label on the edge into this node, or `None` for the root.
+    fn label(&self) -> Option<&'a Label>;
+
+    /// Return the value if this node is the end of a key.
+    fn value(&self) -> Option<&'a Value>;
+
+    /// Return true if this node is the end of a key.
+    fn is_terminal(&self) -> bool {
+        self.value().is_some()
+    }
+
+    /// Return the number of labels from the root to this node.
+    fn label_len(&self) -> usize;
+
+    /// Append the labels from the root to this node to `buf`.
+    fn write_prefix_into(&self, buf: &mut Vec<Label>);
+
+    /// Return the children of this node in label order.
+    fn children(&self) -> Box<dyn Iterator<Item = DynTrieRef<'a, Label, Value>> + 'a>;
+
+    /// Return the parent of this node, or `None` for the root.
+    fn parent(&self) -> Option<DynTrieRef<'a, Label, Value>>;
+
+    /// Return the keys at or below this node and their values, in
+    /// lexicographic order.
+    fn entries(&self) -> Box<dyn Iterator<Item = (Vec<Label>, &'a Value)> + 'a>;
+}
+
+impl<'a, Label: Ord + Clone, Value> TrieRef<'a, Label, Value> for NodeRef<'a, Label, Value> {
+    fn label(&self) -> Option<&'a Label> {
+        NodeRef::label(self)
+    }
+
+    fn value(&self) -> Option<&'a Value> {
+        NodeRef::value(self)
+    }
+
+    fn label_len(&self) -> usize {
+        NodeRef::label_len(self)
+    }
+
+    fn write_prefix_into(&self, buf: &mut Vec<Label>) {
+        self.write_label_into(buf)
+    }
+
+    fn children(&self) -> Box<dyn Iterator<Item = DynTrieRef<'a, Label, Value>> + 'a> {
+        Box::new(NodeRef::children(self).map(|child| Box::new(child) as DynTrieRef<'a, _, _>))
+    }
+
+    fn parent(&self) -> Option<DynTrieRef<'a, Label, Value>> {
+        NodeRef::parent(self).map(|parent| Box::new(parent) as DynTrieRef<'a, _, _>)
+    }
+
+    fn entries(&self) -> Box<dyn Iterator<Item = (Vec<Label>, &'a Value)> + 'a> {
+        let prefix: Vec<Label> = self.prefix();
+        Box::new(self.trie().predictive_search::<Vec<Label>, Collect>(prefix))
+    }
+}
+
+#[cfg(test)]
+mod trie_ref_tests {
+    use crate::map::DynTrieRef;
+    use crate::Trie;
+
+    fn count_terminals(node: DynTrieRef<'_, char, ()>) -> usize {
+        usize::from(node.is_terminal()) + node.children().map(count_terminals).sum::<usize>()
+    }
+
+    #[test]
+    fn dyn_nodes() {
+        let trie = Trie::from_iter(["ab", "abc", "b"].map(|s| s.chars().collect::<Vec<_>>()));
+        let root: DynTrieRef<'_, char, ()> = Box::new(trie.0.node([]).unwrap());
+        assert_eq!(root.label(), None);
+        assert!(root.parent().is_none());
+        assert_eq!(count_terminals(root), 3);
+
+        let node: DynTrieRef<'_, char, ()> = Box::new(trie.0.node(['a', 'b']).unwrap());
+        assert!(node.is_terminal());
+        assert_eq!(node.label_len(), 2);
+        let parent = node.parent().unwrap();
+        assert_eq!(parent.label(), Some(&'a'));
+        assert!(!parent.is_terminal());
+        assert_eq!(node.entries().count(), 2);
+    }
+}
@@ -0,0 +1,329 @@
+//! Query variants that return collection errors instead of panicking.
+//!
+//! The other queries panic only when a key cannot be collected into the
+//! requested type, e.g. non-UTF-8 bytes into a [String]. These variants
+//! collect into `Result<C, C::Error>` instead, so on a trie built by this
+//! crate they do not panic on any query, and they use no `unsafe` code.
+//!
+//! The guarantee covers these methods only: the `try_` variant of every query
+//! of [Trie] that collects keys, and of the set [crate::Trie]
+//! `try_predictive_search`, `try_postfix_search`, `try_iter`,
+//! `try_common_prefix_search` and `try_longest_prefix`. [Trie::scan] and the
+//! queries returning values or [super::NodeRef]s collect no keys. Everything
+//! else that collects keys may panic: the other queries of the set
+//! [crate::Trie], the other tries of [super], [super::ScoreIndex] and
+//! [super::NodeRef::prefix]. Those taking a collector can be asked for
+//! `Result<C, C::Error>` with the marker
+//! [`ResultCollect<M>`](crate::try_collect::ResultCollect).
+
+use super::Trie;
+use crate::iter::{
+    FuzzyIter, GapIter, InsertionOrderIter, IterMut, PostfixIter, PrefixIter, PrefixOffsetIter,
+    RangeIter, SearchIter,
+};
+use crate::try_collect::{ResultCollect, TryCollect, TryFromIterator};
+use std::ops::RangeBounds;
+
+type TryInsertionOrderIter<'a, Label, Value, C, M> = InsertionOrderIter<
+    'a,
+    Label,
+    Value,
+    Result<C, <C as TryFromIterator<Label, M>>::Error>,
+    ResultCollect<M>,
+>;
+
+type TryPopularCompletions<'a, Label, Value, C, M> = Vec<(
+    Result<C, <C as TryFromIterator<Label, M>>::Error>,
+    &'a Value,
+    usize,
+)>;
+
+impl<Label: Ord + Clone, Value> Trie<Label, Value> {
+    /// Like [Trie::predictive_search], but never panics: a key that cannot be
+    /// collected into `C` is returned as an `Err`.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([(&b"ab"[..], 0), (&[b'a', 0xff], 1)]);
+    /// let results: Vec<_> = trie.try_predictive_search::<String, _>("a").collect();
+    /// assert_eq!(results[0].0.as_deref(), Ok("ab"));
+    /// assert!(results[1].0.is_err());
+    /// ```
+    pub fn try_predictive_search<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> SearchIter<'_, Label, Value, Result<C, C::Error>, ResultCollect<M>>
+    where
+        C: TryFromIterator<Label, M> + Clone,
+        C::Error: Clone,
+    {
+        self.predictive_search(query)
+    }
+
+    /// Like [Trie::postfix_search], but never panics: a postfix that cannot
+    /// be collected into `C`, e.g. one that starts partway through a UTF-8
+    /// `char`, is returned as an `Err`.
+    pub fn try_postfix_search<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> PostfixIter<'_, Label, Value, Result<C, C::Error>, ResultCollect<M>>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        self.postfix_search(query)
+    }
+
+    /// Like [Trie::iter], but never panics: a key that cannot be collected
+    /// into `C` is returned as an `Err`.
+    pub fn try_iter<C, M>(
+        &self,
+    ) -> PostfixIter<'_, Label, Value, Result<C, C::Error>, ResultCollect<M>>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        self.iter()
+    }
+
+    /// Like [Trie::fuzzy_search], but never panics: a key that cannot be
+    /// collected into `C` is returned as an `Err`.
+    pub fn try_fuzzy_search<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+        max_edits: usize,
+    ) -> FuzzyIter<'_, Label, Value, Result<C, C::Error>, ResultCollect<M>>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        self.fuzzy_search(query, max_edits)
+    }
+
+    /// Like [Trie::gap_search], but never panics: a key that cannot be
+    /// collected into `C` is returned as an `Err`.
+    pub fn try_gap_search<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+        max_gap: usize,
+    ) -> GapIter<'_, Label, Value, Result<C, C::Error>, ResultCollect<M>>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        self.gap_search(query, max_gap)
+    }
+
+    /// Like [Trie::range], but never panics: a key that cannot be collected
+    /// into `C` is returned as an `Err`.
+    pub fn try_range<C, M>(
+        &self,
+        range: impl RangeBounds<[Label]>,
+    ) -> RangeIter<'_, Label, Value, Result<C, C::Error>, ResultCollect<M>>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        self.range(range)
+    }
+
+    /// Like [Trie::iter_insertion_order], but never panics: a key that cannot
+    /// be collected into `C` is returned as an `Err`.
+    pub fn try_iter_insertion_order<C, M>(
+        &self,
+    ) -> Option<TryInsertionOrderIter<'_, Label, Value, C, M>>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        self.iter_insertion_order()
+    }
+
+    /// Like [Trie::common_prefix_search], but never panics: a prefix that
+    /// cannot be collected into `C` is returned as an `Err`.
+    pub fn try_common_prefix_search<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> PrefixIter<'_, Label, Value, Result<C, C::Error>, ResultCollect<M>>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        self.common_prefix_search(query)
+    }
+
+    /// Like [Trie::longest_prefix], but never panics: return an `Err` if the
+    /// longest prefix cannot be collected into `C`.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([(&[0xe3, 0x81, 0x82][..], 0), (&[0xe3, 0x81, 0x84], 1)]);
+    /// // The longest prefix of both keys is two bytes of a three-byte char.
+    /// assert!(trie.try_longest_prefix::<String, _>([0xe3]).is_err());
+    /// assert_eq!(trie.try_longest_prefix::<String, _>("z"), Ok(None));
+    /// ```
+    pub fn try_longest_prefix<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> Result<Option<C>, C::Error>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        self.longest_prefix::<Vec<Label>, _>(query)
+            .map(|prefix| prefix.into_iter().try_collect())
+            .transpose()
+    }
+
+    /// Like [Trie::iter_mut], but never panics: a key that cannot be collected
+    /// into `C` is returned as an `Err`.
+    pub fn try_iter_mut<C, M>(
+        &mut self,
+    ) -> IterMut<'_, Label, Value, Result<C, C::Error>, ResultCollect<M>>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        self.iter_mut()
+    }
+
+    /// Like [Trie::common_prefix_search_with_offsets], but never panics: a
+    /// prefix that cannot be collected into `C` is returned as an `Err`.
+    pub fn try_common_prefix_search_with_offsets<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> PrefixOffsetIter<'_, Label, Value, Result<C, C::Error>, ResultCollect<M>>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        self.common_prefix_search_with_offsets(query)
+    }
+
+    /// Like [Trie::longest_prefix_with_extension], but never panics: an
+    /// extension that cannot be collected into `C` is returned as an `Err`.
+    pub fn try_longest_prefix_with_extension<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> Option<(usize, Result<C, C::Error>)>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        self.longest_prefix_with_extension(query)
+    }
+
+    /// Like [Trie::popular_completions], but never panics: a key that cannot
+    /// be collected into `C` is returned as an `Err`.
+    pub fn try_popular_completions<C, M>(
+        &self,
+        prefix: impl AsRef<[Label]>,
+        k: usize,
+    ) -> TryPopularCompletions<'_, Label, Value, C, M>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        self.popular_completions(prefix, k)
+    }
+
+    /// Like [Trie::random_walk], but never panics: a key that cannot be
+    /// collected into `C` is returned as an `Err`.
+    pub fn try_random_walk<C, M>(
+        &self,
+        prefix: impl AsRef<[Label]>,
+        rng: impl FnMut(usize) -> usize,
+        max_len: usize,
+    ) -> Option<Result<C, C::Error>>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        self.random_walk(prefix, rng, max_len)
+    }
+
+    /// Like [Trie::random_walk_weighted], but never panics: a key that cannot
+    /// be collected into `C` is returned as an `Err`.
+    pub fn try_random_walk_weighted<C, M>(
+        &self,
+        prefix: impl AsRef<[Label]>,
+        rng: impl FnMut(usize) -> usize,
+        max_len: usize,
+    ) -> Option<Result<C, C::Error>>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        self.random_walk_weighted(prefix, rng, max_len)
+    }
+}
+
+#[cfg(test)]
+mod try_queries_tests {
+    use crate::map::Trie;
+
+    #[test]
+    fn invalid_utf8() {
+        let trie = Trie::from_iter([(&[b'a', 0xc3, 0xa9][..], 0), (&[b'a', 0xff], 1)]);
+        let postfixes: Vec<_> = trie.try_postfix_search::<String, _>([b'a', 0xc3]).collect();
+        assert_eq!(postfixes.len(), 1);
+        assert!(postfixes[0].0.is_err());
+        let keys: Vec<bool> = trie
+            .try_iter::<String, _>()
+            .map(|(k, _)| k.is_ok())
+            .collect();
+        assert_eq!(keys, [true, false]);
+        let prefixes: Vec<_> = trie
+            .try_common_prefix_search::<String, _>([b'a', 0xff])
+            .collect();
+        assert!(prefixes[0].0.is_err());
+        assert_eq!(
+            trie.try_longest_prefix::<String, _>("a"),
+            Ok(Some("a".to_string()))
+        );
+        assert_eq!(
+            trie.try_longest_prefix::<Vec<u8>, _>([b'a', 0xc3]),
+            Ok(Some(vec![b'a', 0xc3, 0xa9]))
+        );
+    }
+
+    #[test]
+    fn other_searches() {
+        use std::ops::Bound::{Included, Unbounded};
+
+        let mut builder = crate::map::TrieBuilder::new();
+        builder.keep_insertion_order();
+        builder.push([b'a', 0xff], 0);
+        builder.push("ab", 1);
+        let trie = builder.build();
+        let ok = |results: Vec<(Result<String, _>, &u8)>| -> Vec<bool> {
+            results.into_iter().map(|(k, _)| k.is_ok()).collect()
+        };
+        let fuzzy = trie.try_fuzzy_search("ac", 1).map(|(k, _, v)| (k, v));
+        assert_eq!(ok(fuzzy.collect()), [true, false]);
+        assert_eq!(ok(trie.try_gap_search("a", 1).collect()), [true, false]);
+        let range = (Included(&b"a"[..]), Unbounded);
+        assert_eq!(ok(trie.try_range(range).collect()), [true, false]);
+        let order = trie.try_iter_insertion_order().unwrap().collect();
+        assert_eq!(ok(order), [false, true]);
+    }
+
+    #[test]
+    fn remaining_queries() {
+        let mut trie = Trie::from_iter([(&[b'a', 0xff][..], 0), (&b"ab"[..], 1)]);
+        let ok: Vec<bool> = trie
+            .try_iter_mut::<String, _>()
+            .map(|(k, v)| {
+                *v += 1;
+                k.is_ok()
+            })
+            .collect();
+        assert_eq!(ok, [true, false]);
+        assert_eq!(trie.exact_match([b'a', 0xff]), Some(&1));
+        let offsets: Vec<_> = trie
+            .try_common_prefix_search_with_offsets::<String, _>([b'a', 0xff])
+            .collect();
+        assert_eq!(offsets.len(), 1);
+        assert!(offsets[0].0.is_err());
+        let (matched, extension) = trie
+            .try_longest_prefix_with_extension::<String, _>([b'a', 0xff])
+            .unwrap();
+        assert_eq!(matched, 2);
+        assert_eq!(extension, Ok(String::new()));
+        let popular = trie.try_popular_completions::<String, _>("a", 2);
+        let ok: Vec<bool> = popular.iter().map(|(k, _, _)| k.is_ok()).collect();
+        assert_eq!(ok, [true, false]);
+        let walk = trie.try_random_walk::<String, _>([b'a', 0xff], |_| 0, 2);
+        assert!(walk.unwrap().is_err());
+        let walk = trie.try_random_walk_weighted::<String, _>("ab", |_| 0, 2);
+        assert_eq!(walk, Some(Ok("ab".to_string())));
+    }
+}
@@ -0,0 +1,671 @@
+use super::{Trie, TrieBuilder};
+use crate::iter::SearchIter;
+use crate::try_collect::{Collect, TryCollect, TryFromIterator};
+use louds_rs::LoudsNodeNum;
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::collections::btree_map::{self, BTreeMap};
+use std::collections::BTreeSet;
+use std::iter::Peekable;
+use std::marker::PhantomData;
+use std::ops::Bound;
+
+#[derive(Debug, Clone)]
+/// A [Trie] that accepts inserts and removals after it is built.
+///
+/// Updates go into a small ordered overlay which is consulted before the
+/// immutable trie on every query. Once the overlay outgrows an eighth of the
+/// keys underneath, [UpdatableTrie::insert] and [UpdatableTrie::remove] fold
+/// it into a freshly built trie, so an update costs amortized constant
+/// rebuilding however large the trie. [UpdatableTrie::compact] folds it in
+/// right away.
+///
+/// Folding keeps the insertion order and label runs of the trie underneath:
+/// inserted keys come last in [Trie::iter_insertion_order], in the order of
+/// their last insert.
+///
+/// ```rust
+/// use trie_rs::map::{Trie, UpdatableTrie};
+///
+/// let mut trie = UpdatableTrie::from(Trie::from_iter([("app", 1), ("apple", 2)]));
+/// trie.insert("application", 3);
+/// trie.remove("apple");
+/// assert_eq!(trie.exact_match("application"), Some(&3));
+/// assert_eq!(trie.exact_match("apple"), None);
+///
+/// trie.compact();
+/// assert_eq!(trie.overlay_len(), 0);
+/// let results: Vec<(String, &u8)> = trie.predictive_search("app").collect();
+/// assert_eq!(results, [("app".to_string(), &1), ("application".to_string(), &3)]);
+/// ```
+pub struct UpdatableTrie<Label, Value> {
+    base: Trie<Label, Value>,
+    /// `None` marks a key removed from `base`.
+    overlay: BTreeMap<Vec<Label>, Option<Value>>,
+    /// Keys inserted into the overlay, in order, if `base` keeps insertion
+    /// order.
+    inserted: Vec<Vec<Label>>,
+}
+
+impl<Label: Ord + Clone, Value> Default for UpdatableTrie<Label, Value> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Label: Ord + Clone, Value> From<Trie<Label, Value>> for UpdatableTrie<Label, Value> {
+    fn from(base: Trie<Label, Value>) -> Self {
+        Self {
+            base,
+            overlay: BTreeMap::new(),
+            inserted: Vec::new(),
+        }
+    }
+}
+
+/// The fewest updates an [UpdatableTrie] buffers before folding them in.
+const MIN_COMPACTION: usize = 64;
+
+/// Reject the empty key, which no compaction could fold into a trie. Also
+/// used by [super::SnapshotTrie].
+pub(super) fn assert_non_empty<Label>(key: &[Label]) {
+    assert!(
+        !key.is_empty(),
+        "Cannot insert an empty key: the root holds no value"
+    );
+}
+
+impl<Label: Ord + Clone, Value> UpdatableTrie<Label, Value> {
+    /// Return an empty [UpdatableTrie].
+    pub fn new() -> Self {
+        Self::from(TrieBuilder::new().build())
+    }
+
+    /// Insert `key` with `value`, replacing any previous value.
+    ///
+    /// # Panics
+    /// If `key` is empty, like [TrieBuilder::push]. The trie is left as it
+    /// was.
+    pub fn insert(&mut self, key: impl AsRef<[Label]>, value: Value) {
+        let key = key.as_ref();
+        assert_non_empty(key);
+        let key = key.to_vec();
+        self.log_insert(&key);
+        self.overlay.insert(key, Some(value));
+        self.compact_if_large();
+    }
+
+    /// Remember that `key` was inserted, if the trie keeps insertion order.
+    fn log_insert(&mut self, key: &[Label]) {
+        if self.base.insertion_order.is_some() {
+            self.inserted.push(key.to_vec());
+        }
+    }
+
+    /// Remove `key`. Return true if it was present.
+    pub fn remove(&mut self, key: impl AsRef<[Label]>) -> bool {
+        let key = key.as_ref();
+        let present = self.exact_match(key).is_some();
+        if self.base.exact_match(key).is_some() {
+            self.overlay.insert(key.to_vec(), None);
+        } else {
+            self.overlay.remove(key);
+        }
+        self.compact_if_large();
+        present
+    }
+
+    /// Fold the overlay in once it holds more than an eighth of the keys
+    /// underneath, and more than [MIN_COMPACTION] updates. Repeated inserts
+    /// of a key count as one update each in the insertion order.
+    fn compact_if_large(&mut self) {
+        let keys = self.base.key_count();
+        let pending = self.overlay.len().max(self.inserted.len());
+        if pending > MIN_COMPACTION.max(keys / 8) {
+            self.compact();
+        }
+    }
+
+    /// Return `Some(&Value)` if query is an exact match.
+    pub fn exact_match(&self, query: impl AsRef<[Label]>) -> Option<&Value> {
+        let query = query.as_ref();
+        match self.overlay.get(query) {
+            Some(value) => value.as_ref(),
+            None => self.base.exact_match(query),
+        }
+    }
+
+    /// Return true if `query` is a key, without allocating. See
+    /// [Trie::contains_key].
+    pub fn contains_key(&self, query: impl AsRef<[Label]>) -> bool {
+        let query = query.as_ref();
+        match self.overlay.get(query) {
+            Some(value) => value.is_some(),
+            None => self.base.contains_key(query),
+        }
+    }
+
+    /// Return `Some(&mut Value)` if query is an exact match.
+    pub fn exact_match_mut(&mut self, query: impl AsRef<[Label]>) -> Option<&mut Value> {
+        let query = query.as_ref();
+        match self.overlay.get_mut(query) {
+            Some(value) => value.as_mut(),
+            None => self.base.exact_match_mut(query),
+        }
+    }
+
+    /// Return the value of `key`, first inserting `f()` if `key` is absent.
+    ///
+    /// `key` is looked up once, in the overlay and then the trie underneath.
+    ///
+    /// # Panics
+    /// If `key` is empty, like [UpdatableTrie::insert].
+    ///
+    /// ```rust
+    /// use trie_rs::map::UpdatableTrie;
+    ///
+    /// let mut cache = UpdatableTrie::new();
+    /// *cache.get_or_insert_with("net.ipv4.ttl", || 0) += 64;
+    /// assert_eq!(*cache.get_or_insert_with("net.ipv4.ttl", || unreachable!()), 64);
+    /// ```
+    pub fn get_or_insert_with(
+        &mut self,
+        key: impl AsRef<[Label]>,
+        f: impl FnOnce() -> Value,
+    ) -> &mut Value {
+        let key = key.as_ref();
+        assert_non_empty(key);
+        let mut inserted = self
+            .base
+            .insertion_order
+            .is_some()
+            .then_some(&mut self.inserted);
+        match self.overlay.entry(key.to_vec()) {
+            // A removed key is absent, so `f` fills its tombstone.
+            btree_map::Entry::Occupied(entry) => {
+                let value = entry.into_mut();
+                if value.is_none() {
+                    if let Some(inserted) = &mut inserted {
+                        inserted.push(key.to_vec());
+                    }
+                }
+                value.get_or_insert_with(f)
+            }
+            btree_map::Entry::Vacant(entry) => match self.base.exact_match_mut(key) {
+                Some(value) => value,
+                None => {
+                    if let Some(inserted) = &mut inserted {
+                        inserted.push(key.to_vec());
+                    }
+                    entry.insert(Some(f())).as_mut().unwrap()
+                }
+            },
+        }
+    }
+
+    /// Return true if `query` is a prefix of a key other than itself.
+    ///
+    /// Keys are not built: the overlay entries below `query` are scanned, and
+    /// the trie underneath is only walked if some of its keys there were
+    /// removed.
+    pub fn is_prefix(&self, query: impl AsRef<[Label]>) -> bool {
+        let query = query.as_ref();
+        // Each removal below `query` hides one key of `base`.
+        let mut removed = 0;
+        let below = (Bound::Excluded(query), Bound::Unbounded);
+        for (key, value) in self.overlay.range::<[Label], _>(below) {
+            if !key.starts_with(query) {
+                break;
+            }
+            match value {
+                Some(_) => return true,
+                None => removed += 1,
+            }
+        }
+        if removed == 0 {
+            return self.base.is_prefix(query);
+        }
+        let Some(node) = self.base.node_num(query) else {
+            return false;
+        };
+        // Look for a key of `base` below `query` beyond the removed ones.
+        let mut left = removed + 1;
+        let mut stack: Vec<_> = self.base.children_node_nums(node).collect();
+        while let Some(node) = stack.pop() {
+            if self.base.is_terminal(node) {
+                left -= 1;
+                if left == 0 {
+                    return true;
+                }
+            }
+            stack.extend(self.base.children_node_nums(node));
+        }
+        false
+    }
+
+    /// Return all entries and their values that match `query`, in
+    /// lexicographic order.
+    pub fn predictive_search<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> UpdatableSearchIter<'_, Label, Value, C, M>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        UpdatableSearchIter::new(&self.base, &self.overlay, query.as_ref())
+    }
+
+    /// Return all entries and their values in lexicographic order.
+    pub fn iter<C, M>(&self) -> UpdatableSearchIter<'_, Label, Value, C, M>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        self.predictive_search([])
+    }
+
+    /// Return the number of pending inserts and removals.
+    pub fn overlay_len(&self) -> usize {
+        self.overlay.len()
+    }
+
+    /// Return the immutable trie underneath the overlay.
+    pub fn base(&self) -> &Trie<Label, Value> {
+        &self.base
+    }
+
+    /// Fold the overlay into a newly built trie, keeping insertion order and
+    /// label runs.
+    pub fn compact(&mut self) {
+        let inserted = std::mem::take(&mut self.inserted);
+        if self.overlay.is_empty() {
+            return;
+        }
+        let mut base = std::mem::replace(&mut self.base, TrieBuilder::new().build());
+        let had_runs = base.runs.is_some();
+        if self.overlay.values().all(Option::is_none) {
+            // Only removals: drop the values in place and prune the branches.
+            for (key, _) in std::mem::take(&mut self.overlay) {
+                if let Some(node) = base.node_num(&key) {
+                    base.values.take(node.0 as usize - 2);
+                }
+            }
+            self.base = base.rebuild();
+        } else {
+            self.base = Self::fold(base, std::mem::take(&mut self.overlay), inserted);
+        }
+        if had_runs {
+            self.base.build_label_runs();
+        }
+    }
+
+    /// Build a trie of the entries of `base` updated by `overlay`, pushing
+    /// them in the insertion order of `base` followed by the last insert of
+    /// each key in `inserted`, if `base` keeps insertion order.
+    fn fold(
+        base: Trie<Label, Value>,
+        mut overlay: BTreeMap<Vec<Label>, Option<Value>>,
+        inserted: Vec<Vec<Label>>,
+    ) -> Trie<Label, Value> {
+        let mut builder = TrieBuilder::new();
+        let order: Option<Vec<Vec<Label>>> = base.iter_insertion_order().map(|entries| {
+            entries
+                .map(|(key, _)| key)
+                .filter(|key: &Vec<Label>| !overlay.contains_key(key))
+                .collect()
+        });
+        let mut entries: BTreeMap<Vec<Label>, Option<Value>> =
+            base.into_entries().map(|(k, v)| (k, Some(v))).collect();
+        entries.append(&mut overlay);
+
+        let Some(mut order) = order else {
+            for (key, value) in entries {
+                if let Some(value) = value {
+                    builder.insert(key, value);
+                }
+            }
+            return builder.build();
+        };
+        builder.keep_insertion_order();
+        // Only the last insert of a key counts.
+        let mut seen = BTreeSet::new();
+        let mut last_inserts: Vec<Vec<Label>> = inserted
+            .into_iter()
+            .rev()
+            .filter(|key| seen.insert(key.clone()))
+            .collect();
+        last_inserts.reverse();
+        order.append(&mut last_inserts);
+        for key in order {
+            if let Some(Some(value)) = entries.remove(&key) {
+                builder.insert(key, value);
+            }
+        }
+        builder.build()
+    }
+
+    /// Fold the overlay in, then [Trie::shrink] the trie, to bound the memory
+    /// of a long-running service. Return the bytes reclaimed from the trie;
+    /// the overlay is freed too but not counted.
+    ///
+    /// The trie is laid out anew once: a trie just folded into has no
+    /// branches left without a key, so only its spare capacity is dropped.
+    pub fn shrink(&mut self) -> usize {
+        if self.overlay.is_empty() {
+            return self.base.shrink();
+        }
+        let before = self.base.heap_bytes();
+        self.compact();
+        self.base.shrink_to_fit();
+        before.saturating_sub(self.base.heap_bytes())
+    }
+
+    /// Fold the overlay in and return the resulting [Trie].
+    pub fn into_trie(mut self) -> Trie<Label, Value> {
+        self.compact();
+        self.base
+    }
+}
+
+impl<Label: Ord + Clone, Value> Trie<Label, Value> {
+    /// Consume the trie and return its entries in lexicographic order.
+    pub(crate) fn into_entries(mut self) -> impl Iterator<Item = (Vec<Label>, Value)> {
+        let mut entries = Vec::new();
+        let mut stack = vec![(LoudsNodeNum(1), Vec::new())];
+        while let Some((node, key)) = stack.pop() {
+            let children: Vec<_> = self.children_node_nums(node).collect();
+            for child in children.into_iter().rev() {
+                let mut child_key = key.clone();
+                child_key.push(self.label(child).clone());
+                stack.push((child, child_key));
+            }
+            if node.0 > 1 {
+                if let Some(value) = self.values.take((node.0 - 2) as usize) {
+                    entries.push((key, value));
+                }
+            }
+        }
+        entries.into_iter()
+    }
+}
+
+/// Iterates through the entries of an [UpdatableTrie] that match a query.
+///
+/// The overlay may hold values as `Overlay`, e.g. `Arc<Value>` for a
+/// [super::Snapshot].
+pub struct UpdatableSearchIter<'a, Label: Ord + Clone, Value, C, M, Overlay = Value> {
+    query: Vec<Label>,
+    base: Peekable<SearchIter<'a, Label, Value, Vec<Label>, Collect>>,
+    overlay: Peekable<btree_map::Range<'a, Vec<Label>, Option<Overlay>>>,
+    col: PhantomData<(C, M)>,
+}
+
+impl<'a, Label: Ord + Clone, Value, C, M, Overlay>
+    UpdatableSearchIter<'a, Label, Value, C, M, Overlay>
+where
+    C: TryFromIterator<Label, M>,
+{
+    pub(crate) fn new(
+        base: &'a Trie<Label, Value>,
+        overlay: &'a BTreeMap<Vec<Label>, Option<Overlay>>,
+        query: &[Label],
+    ) -> Self {
+        Self {
+            query: query.to_vec(),
+            base: base.predictive_search(query).peekable(),
+            overlay: overlay.range(query.to_vec()..).peekable(),
+            col: PhantomData,
+        }
+    }
+}
+
+impl<'a, Label: Ord + Clone, Value, C, M, Overlay> Iterator
+    for UpdatableSearchIter<'a, Label, Value, C, M, Overlay>
+where
+    C: TryFromIterator<Label, M>,
+    Overlay: Borrow<Value>,
+{
+    type Item = (C, &'a Value);
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let overlay = self
+                .overlay
+                .peek()
+                .filter(|(key, _)| key.starts_with(&self.query));
+            let order = match (self.base.peek(), overlay) {
+                (None, None) => return None,
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (Some((base_key, _)), Some((overlay_key, _))) => base_key.cmp(overlay_key),
+            };
+            let (key, value) = match order {
+                Ordering::Less => {
+                    let (key, value) = self.base.next().unwrap();
+                    (key, Some(value))
+                }
+                Ordering::Equal => {
+                    self.base.next();
+                    let (key, value) = self.overlay.next().unwrap();
+                    (key.clone(), value.as_ref().map(Borrow::borrow))
+                }
+                Ordering::Greater => {
+                    let (key, value) = self.overlay.next().unwrap();
+                    (key.clone(), value.as_ref().map(Borrow::borrow))
+                }
+            };
+            if let Some(value) = value {
+                return Some((
+                    key.into_iter().try_collect().expect("Could not collect"),
+                    value,
+                ));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod updatable_trie_tests {
+    use crate::map::{Trie, UpdatableTrie};
+
+    fn build_trie() -> UpdatableTrie<u8, u8> {
+        UpdatableTrie::from(Trie::from_iter([
+            ("a", 0),
+            ("app", 1),
+            ("apple", 2),
+            ("better", 3),
+            ("application", 4),
+        ]))
+    }
+
+    #[test]
+    fn insert_and_remove() {
+        let mut trie = build_trie();
+        trie.insert("apply", 5);
+        trie.insert("app", 10);
+        assert!(trie.remove("apple"));
+        assert!(!trie.remove("apple"));
+        assert!(!trie.remove("zoo"));
+        assert_eq!(trie.exact_match("apply"), Some(&5));
+        assert_eq!(trie.exact_match("app"), Some(&10));
+        assert_eq!(trie.exact_match("apple"), None);
+        *trie.exact_match_mut("a").unwrap() = 20;
+
+        let results: Vec<(String, &u8)> = trie.iter().collect();
+        let expected = [
+            ("a", 20),
+            ("app", 10),
+            ("application", 4),
+            ("apply", 5),
+            ("better", 3),
+        ];
+        let expected: Vec<(String, &u8)> =
+            expected.iter().map(|(k, v)| (k.to_string(), v)).collect();
+        assert_eq!(results, expected);
+
+        trie.compact();
+        assert_eq!(trie.overlay_len(), 0);
+        let compacted: Vec<(String, &u8)> = trie.iter().collect();
+        assert_eq!(compacted, expected);
+    }
+
+    #[test]
+    fn is_prefix_honors_removals() {
+        let mut trie = build_trie();
+        assert!(trie.is_prefix("bet"));
+        trie.remove("better");
+        assert!(!trie.is_prefix("bet"));
+        trie.insert("bets", 6);
+        assert!(trie.is_prefix("bet"));
+        assert!(!trie.is_prefix("bets"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot insert an empty key")]
+    fn rejects_empty_keys() {
+        let mut trie = build_trie();
+        trie.insert("", 0);
+        trie.compact();
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot insert an empty key")]
+    fn get_or_insert_with_rejects_empty_keys() {
+        let mut trie = build_trie();
+        trie.get_or_insert_with("", || 0);
+        trie.compact();
+    }
+
+    #[test]
+    fn is_prefix_counts_removals() {
+        let mut trie =
+            UpdatableTrie::from(Trie::from_iter([("a", 0), ("ab", 1), ("abc", 2), ("b", 3)]));
+        trie.remove("abc");
+        assert!(trie.is_prefix("a"));
+        assert!(!trie.is_prefix("ab"));
+        trie.remove("ab");
+        assert!(!trie.is_prefix("a"));
+        assert!(trie.is_prefix(""));
+        trie.remove("a");
+        trie.remove("b");
+        assert!(!trie.is_prefix(""));
+        trie.insert("abcd", 4);
+        assert!(trie.is_prefix("abc"));
+        assert!(!trie.is_prefix("abcd"));
+    }
+
+    #[test]
+    fn get_or_insert_with() {
+        let mut trie = build_trie();
+        *trie.get_or_insert_with("apple", || 10) += 1;
+        assert_eq!(trie.exact_match("apple"), Some(&3));
+        assert_eq!(trie.overlay_len(), 0);
+        assert_eq!(*trie.get_or_insert_with("apply", || 5), 5);
+        assert_eq!(*trie.get_or_insert_with("apply", || 6), 5);
+        trie.remove("better");
+        assert_eq!(*trie.get_or_insert_with("better", || 7), 7);
+        assert_eq!(trie.exact_match("better"), Some(&7));
+        assert_eq!(trie.overlay_len(), 2);
+    }
+
+    #[test]
+    fn remove_then_reinsert() {
+        let mut trie = build_trie();
+        trie.remove("app");
+        trie.insert("app", 7);
+        assert_eq!(trie.exact_match("app"), Some(&7));
+        let trie = trie.into_trie();
+        assert_eq!(trie.exact_match("app"), Some(&7));
+        assert_eq!(trie.exact_match("apple"), Some(&2));
+    }
+
+    #[test]
+    fn compact_removals_only() {
+        let mut trie = build_trie();
+        trie.remove("better");
+        trie.remove("apple");
+        trie.compact();
+        assert_eq!(trie.overlay_len(), 0);
+        assert!(!trie.base().is_prefix("b"));
+        let results: Vec<(String, &u8)> = trie.iter().collect();
+        let expected: Vec<(String, &u8)> = [("a", 0), ("app", 1), ("application", 4)]
+            .iter()
+            .map(|(k, v)| (k.to_string(), v))
+            .collect();
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    fn compaction_keeps_insertion_order_and_runs() {
+        use crate::map::TrieBuilder;
+
+        let mut builder = TrieBuilder::new();
+        builder.keep_insertion_order();
+        for (k, v) in [("better", 0), ("app", 1), ("apple", 2)] {
+            builder.push(k, v);
+        }
+        let mut base = builder.build();
+        base.build_label_runs();
+        let mut trie = UpdatableTrie::from(base);
+        trie.insert("zoo", 3);
+        trie.insert("app", 4);
+        trie.remove("better");
+        *trie.get_or_insert_with("ant", || 5) += 1;
+        trie.insert("zoo", 6);
+        trie.compact();
+        let order: Vec<(String, &u32)> = trie.base().iter_insertion_order().unwrap().collect();
+        let expected = [("apple", 2), ("app", 4), ("ant", 6), ("zoo", 6)];
+        let expected: Vec<(String, &u32)> =
+            expected.iter().map(|(k, v)| (k.to_string(), v)).collect();
+        assert_eq!(order, expected);
+        assert!(trie.base().runs.is_some());
+
+        // Removals alone, and compactions triggered by inserts.
+        trie.remove("app");
+        trie.compact();
+        assert!(trie.base().runs.is_some());
+        for i in 0..200u32 {
+            trie.insert(format!("k{}", i), i);
+        }
+        assert!(trie.overlay_len() < 200);
+        let order: Vec<String> = trie
+            .into_trie()
+            .iter_insertion_order()
+            .unwrap()
+            .map(|(k, _)| k)
+            .collect();
+        let mut expected: Vec<String> = vec!["apple".into(), "ant".into(), "zoo".into()];
+        expected.extend((0..200).map(|i| format!("k{}", i)));
+        assert_eq!(order, expected);
+    }
+
+    #[test]
+    fn matches_btree_map() {
+        use rand::{Rng, SeedableRng};
+        use std::collections::BTreeMap;
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1002);
+        let mut trie = UpdatableTrie::new();
+        let mut expected = BTreeMap::new();
+        let mut compactions = 0;
+        for i in 0..3000u32 {
+            let len = rng.gen_range(1, 6);
+            let key: Vec<u8> = (0..len).map(|_| rng.gen_range(b'a', b'e')).collect();
+            let pending = trie.overlay_len();
+            if rng.gen_range(0, 3) == 0 {
+                assert_eq!(trie.remove(&key), expected.remove(&key).is_some());
+            } else {
+                trie.insert(&key, i);
+                expected.insert(key, i);
+            }
+            if trie.overlay_len() == 0 && pending > 1 {
+                compactions += 1;
+            }
+            let keys = trie.base().key_count();
+            assert!(trie.overlay_len() <= 64.max(keys / 8));
+        }
+        // Folded in by amortized rebuilds, not one per update.
+        assert!((2..60).contains(&compactions), "{}", compactions);
+        let entries: Vec<(Vec<u8>, &u32)> = trie.iter().collect();
+        let wanted: Vec<(Vec<u8>, &u32)> = expected.iter().map(|(k, v)| (k.clone(), v)).collect();
+        assert_eq!(entries, wanted);
+    }
+}
@@ -0,0 +1,106 @@
+use super::{Trie, TrieBuilder};
+
+#[derive(Debug, Clone)]
+/// A [TrieBuilder] that checks every key with a validator before adding it,
+/// so malformed keys from untrusted input are rejected with the validator's
+/// error at push time. See [TrieBuilder::with_validator].
+pub struct ValidatingTrieBuilder<Label, Value, F> {
+    builder: TrieBuilder<Label, Value>,
+    validator: F,
+}
+
+impl<Label: Ord, Value> TrieBuilder<Label, Value> {
+    /// Check every key pushed from now on with `validator`, e.g. to limit key
+    /// length or forbid labels. Keys already pushed are not checked.
+    ///
+    /// ```rust
+    /// use trie_rs::map::TrieBuilder;
+    ///
+    /// let mut builder = TrieBuilder::new().with_validator(|key: &[u8]| {
+    ///     if key.len() > 8 {
+    ///         Err("too long")
+    ///     } else if key.contains(&b'\0') {
+    ///         Err("contains NUL")
+    ///     } else {
+    ///         Ok(())
+    ///     }
+    /// });
+    /// builder.push("session", 1).unwrap();
+    /// assert_eq!(builder.push("a\0b", 2), Err("contains NUL"));
+    /// assert_eq!(builder.push("sessions-v2", 3), Err("too long"));
+    /// let trie = builder.build();
+    /// assert_eq!(trie.iter::<String, _>().count(), 1);
+    /// ```
+    pub fn with_validator<F, E>(self, validator: F) -> ValidatingTrieBuilder<Label, Value, F>
+    where
+        F: Fn(&[Label]) -> Result<(), E>,
+    {
+        ValidatingTrieBuilder {
+            builder: self,
+            validator,
+        }
+    }
+}
+
+impl<Label: Ord, Value, F, E> ValidatingTrieBuilder<Label, Value, F>
+where
+    F: Fn(&[Label]) -> Result<(), E>,
+{
+    /// Add a cloneable entry and value if the validator accepts the entry.
+    pub fn push<Arr: AsRef<[Label]>>(&mut self, entry: Arr, value: Value) -> Result<(), E>
+    where
+        Label: Clone,
+    {
+        let entry = entry.as_ref();
+        (self.validator)(entry)?;
+        self.builder.push(entry, value);
+        Ok(())
+    }
+
+    /// Add an entry and value if the validator accepts the entry.
+    pub fn insert<Arr: IntoIterator<Item = Label>>(
+        &mut self,
+        entry: Arr,
+        value: Value,
+    ) -> Result<(), E> {
+        let entry: Vec<Label> = entry.into_iter().collect();
+        (self.validator)(&entry)?;
+        self.builder.insert(entry, value);
+        Ok(())
+    }
+
+    /// Return the builder without the validator.
+    pub fn into_inner(self) -> TrieBuilder<Label, Value> {
+        self.builder
+    }
+
+    /// Build a [Trie].
+    pub fn build(self) -> Trie<Label, Value> {
+        self.builder.build()
+    }
+}
+
+#[cfg(test)]
+mod validating_builder_tests {
+    use crate::map::TrieBuilder;
+
+    #[test]
+    fn rejects_invalid_keys() {
+        let mut builder = TrieBuilder::new().with_validator(|key: &[char]| {
+            match key.iter().position(|c| c.is_control()) {
+                Some(i) => Err(i),
+                None => Ok(()),
+            }
+        });
+        builder.insert("ok".chars(), 0).unwrap();
+        assert_eq!(builder.insert("b\tc".chars(), 1), Err(1));
+        assert_eq!(builder.push(['\n'], 2), Err(0));
+        builder.push(['o', 'k', '!'], 3).unwrap();
+
+        let mut builder = builder.into_inner();
+        builder.push(['\n'], 4);
+        let trie = builder.build();
+        let keys: Vec<String> = trie.iter().map(|(k, _)| k).collect();
+        assert_eq!(keys, ["\n", "ok", "ok!"]);
+    }
+}
@@ -0,0 +1,161 @@
+use serde::de::{self, MapAccess, SeqAccess, Visitor};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::marker::PhantomData;
+
+/// The version of the serialized layout of the tries and builders, written
+/// by [Versioned]. It is bumped whenever any of their serialized fields
+/// change, including those of `louds-rs`.
+pub const FORMAT_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// A trie or builder that serializes with [FORMAT_VERSION], under the crate
+/// feature "serde". Persist tries in one of these: data written by another
+/// format version fails to deserialize with a [FormatVersionMismatch] instead
+/// of loading a corrupt trie or failing somewhere inside it.
+///
+/// The tries serialize as a struct of `format_version` followed by `trie`.
+/// Deserializing checks the version before reading the trie. Data without a
+/// version, such as a bare trie written by trie-rs 0.4.2 or earlier, is
+/// reported as format version 0.
+///
+/// ```rust
+/// use trie_rs::map::{Trie, Versioned};
+///
+/// let trie = Trie::from_iter([("a", 0), ("app", 1)]);
+/// let json = serde_json::to_string(&Versioned(&trie)).unwrap();
+/// assert!(json.starts_with(r#"{"format_version":2,"trie":"#));
+///
+/// let Versioned(loaded): Versioned<Trie<u8, u8>> = serde_json::from_str(&json).unwrap();
+/// assert_eq!(loaded.exact_match("app"), Some(&1));
+///
+/// let old = json.replacen(":2,", ":1,", 1);
+/// let error = serde_json::from_str::<Versioned<Trie<u8, u8>>>(&old).unwrap_err();
+/// assert!(error.to_string().contains("format version 1"));
+/// ```
+pub struct Versioned<T>(pub T);
+
+impl<T> Versioned<T> {
+    /// Return the wrapped trie.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Error of deserializing a [Versioned] written with another
+/// [FORMAT_VERSION]. Serde formats report it through their own error type,
+/// whose message is this one's.
+pub struct FormatVersionMismatch {
+    /// The version of the serialized data.
+    pub found: u32,
+    /// The version this build of the crate reads, [FORMAT_VERSION].
+    pub expected: u32,
+}
+
+impl fmt::Display for FormatVersionMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "trie serialized with format version {}, but this version of trie-rs reads {}",
+            self.found, self.expected
+        )
+    }
+}
+
+impl std::error::Error for FormatVersionMismatch {}
+
+fn check<E: de::Error>(found: u32) -> Result<(), E> {
+    if found == FORMAT_VERSION {
+        Ok(())
+    } else {
+        Err(E::custom(FormatVersionMismatch {
+            found,
+            expected: FORMAT_VERSION,
+        }))
+    }
+}
+
+const FIELDS: &[&str] = &["format_version", "trie"];
+
+impl<T: Serialize> Serialize for Versioned<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Versioned", FIELDS.len())?;
+        state.serialize_field(FIELDS[0], &FORMAT_VERSION)?;
+        state.serialize_field(FIELDS[1], &self.0)?;
+        state.end()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "snake_case")]
+enum Field {
+    FormatVersion,
+    Trie,
+    #[serde(other)]
+    Other,
+}
+
+struct VersionedVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for VersionedVisitor<T> {
+    type Value = Versioned<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a versioned trie")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let version = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        check(version)?;
+        let trie = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        Ok(Versioned(trie))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut version = None;
+        let mut trie = None;
+        while let Some(field) = map.next_key()? {
+            match field {
+                Field::FormatVersion if version.is_some() => {
+                    return Err(de::Error::duplicate_field(FIELDS[0]));
+                }
+                Field::FormatVersion => {
+                    let found = map.next_value()?;
+                    check(found)?;
+                    version = Some(found);
+                }
+                // The trie of an unknown version may not even parse.
+                Field::Trie if version.is_none() => {
+                    return Err(de::Error::missing_field(FIELDS[0]));
+                }
+                Field::Trie if trie.is_some() => {
+                    return Err(de::Error::duplicate_field(FIELDS[1]));
+                }
+                Field::Trie => trie = Some(map.next_value()?),
+                // A bare trie, e.g. the `louds` field of one written before
+                // tries were versioned.
+                Field::Other if version.is_none() => check(0)?,
+                Field::Other => {
+                    map.next_value::<de::IgnoredAny>()?;
+                }
+            }
+        }
+        if version.is_none() {
+            return Err(de::Error::missing_field(FIELDS[0]));
+        }
+        trie.map(Versioned)
+            .ok_or_else(|| de::Error::missing_field(FIELDS[1]))
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Versioned<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_struct("Versioned", FIELDS, VersionedVisitor(PhantomData))
+    }
+}
@@ -0,0 +1,161 @@
+use super::{Trie, TrieBuilder};
+use crate::try_collect::Phrase;
+
+#[derive(Debug)]
+/// Queries a trie of word labels with phrases, which are split on whitespace.
+/// Keys are returned with their words joined by single spaces. Obtained from
+/// [Trie::by_words].
+pub struct WordQueries<'a, Value>(&'a Trie<String, Value>);
+
+impl<Value> Clone for WordQueries<'_, Value> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Value> Copy for WordQueries<'_, Value> {}
+
+fn split(phrase: &str) -> Vec<String> {
+    phrase.split_whitespace().map(String::from).collect()
+}
+
+impl<Value> TrieBuilder<String, Value> {
+    /// Add the words of `phrase`, split on whitespace, as an entry.
+    ///
+    /// ```rust
+    /// use trie_rs::map::TrieBuilder;
+    ///
+    /// let mut builder = TrieBuilder::new();
+    /// builder.push_words("new  york", 0);
+    /// builder.push_words("new york city", 1);
+    /// let trie = builder.build();
+    /// assert_eq!(trie.exact_match(["new", "york"].map(String::from)), Some(&0));
+    /// ```
+    pub fn push_words(&mut self, phrase: &str, value: Value) {
+        self.insert(split(phrase), value);
+    }
+}
+
+impl<Value> Trie<String, Value> {
+    /// Query this trie of word labels with phrases.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([("new york".split(' ').map(String::from).collect::<Vec<_>>(), 0)]);
+    /// let words = trie.by_words();
+    /// assert_eq!(words.exact_match("new\tyork"), Some(&0));
+    /// assert!(words.is_prefix("new"));
+    /// // Words are whole labels, so a partial word is not a prefix.
+    /// assert!(!words.is_prefix("ne"));
+    /// ```
+    pub fn by_words(&self) -> WordQueries<'_, Value> {
+        WordQueries(self)
+    }
+}
+
+impl<'a, Value> WordQueries<'a, Value> {
+    /// Return `Some(&Value)` if the words of `phrase` are an exact match.
+    pub fn exact_match(&self, phrase: &str) -> Option<&'a Value> {
+        self.0.exact_match(split(phrase))
+    }
+
+    /// Return true if the words of `phrase` are a prefix.
+    pub fn is_prefix(&self, phrase: &str) -> bool {
+        self.0.is_prefix(split(phrase))
+    }
+
+    /// Return all phrases starting with the words of `phrase`, and their
+    /// values.
+    pub fn predictive_search(
+        &self,
+        phrase: &str,
+    ) -> impl Iterator<Item = (String, &'a Value)> + 'a {
+        self.0
+            .predictive_search(split(phrase))
+            .map(|(key, value): (Phrase, _)| (key.0, value))
+    }
+
+    /// Return the phrases that are prefixes of `phrase` word by word, and
+    /// their values, e.g. to find the dictionary phrases starting a sentence.
+    ///
+    /// ```rust
+    /// use trie_rs::map::TrieBuilder;
+    ///
+    /// let mut builder = TrieBuilder::new();
+    /// builder.push_words("new", 0);
+    /// builder.push_words("new york", 1);
+    /// builder.push_words("new york times", 2);
+    /// let trie = builder.build();
+    /// let results: Vec<(String, &u8)> = trie.by_words().common_prefix_search("new york is big").collect();
+    /// assert_eq!(results, [("new".to_string(), &0), ("new york".to_string(), &1)]);
+    /// ```
+    pub fn common_prefix_search(
+        &self,
+        phrase: &str,
+    ) -> impl Iterator<Item = (String, &'a Value)> + 'a {
+        self.0
+            .common_prefix_search(split(phrase))
+            .map(|(key, value): (Phrase, _)| (key.0, value))
+    }
+
+    /// Return the longest shared prefix or terminal of the words of `phrase`.
+    pub fn longest_prefix(&self, phrase: &str) -> Option<String> {
+        self.0
+            .longest_prefix(split(phrase))
+            .map(|key: Phrase| key.0)
+    }
+
+    /// Return the underlying trie.
+    pub fn trie(&self) -> &'a Trie<String, Value> {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod word_tokens_tests {
+    use crate::map::{Trie, TrieBuilder};
+
+    fn build_trie() -> Trie<String, u8> {
+        let mut builder = TrieBuilder::new();
+        builder.push_words("to be", 0);
+        builder.push_words("to be or not to be", 1);
+        builder.push_words("  to   go ", 2);
+        builder.push_words("tomorrow", 3);
+        builder.build()
+    }
+
+    #[test]
+    fn phrases() {
+        let trie = build_trie();
+        let words = trie.by_words();
+        assert_eq!(words.exact_match("to go"), Some(&2));
+        assert_eq!(words.exact_match("to"), None);
+        assert!(words.is_prefix("to be or"));
+        let results: Vec<(String, &u8)> = words.predictive_search("to").collect();
+        assert_eq!(
+            results,
+            [
+                ("to be".to_string(), &0),
+                ("to be or not to be".to_string(), &1),
+                ("to go".to_string(), &2)
+            ]
+        );
+        assert_eq!(words.predictive_search("").count(), 4);
+        assert_eq!(
+            words.longest_prefix("to be or"),
+            Some("to be or not to be".to_string())
+        );
+        assert_eq!(words.longest_prefix("tom"), None);
+    }
+
+    #[test]
+    fn remapped() {
+        let trie = build_trie().remap_alphabet();
+        assert_eq!(trie.alphabet(), ["be", "go", "not", "or", "to", "tomorrow"]);
+        let results: Vec<(crate::try_collect::Phrase, &u8)> = trie
+            .common_prefix_search(["to", "be", "or"].map(String::from))
+            .collect();
+        assert_eq!(results[0].0.as_ref(), "to be");
+    }
+}
@@ -0,0 +1,197 @@
+//! Read-only access to the LOUDS layout of a trie
+//!
+//! # Motivation
+//!
+//! The search methods on [map::Trie] cover the common cases. Custom searches
+//! (automata, joins of two tries, ...) want to walk the structure directly.
+//! [RawTrie] exposes the node numbering and per-node data without exposing
+//! the private fields, so the layout can be relied on without depending on
+//! `pub(crate)` internals.
+//!
+//! # Invariants
+//!
+//! These hold for every trie built by this crate:
+//!
+//! - Nodes are identified by a [NodeId]. The root is `NodeId(1)` and has no
+//!   label and no value.
+//! - Nodes are numbered in breadth-first order, so the non-root nodes are
+//!   exactly `2..=node_count()`.
+//! - The children of a node have consecutive node numbers and are sorted by
+//!   their label in ascending order, so they may be binary searched.
+//! - A node is terminal, i.e. the end of a key, iff it has a value.
+//! - Every non-root node has a parent with a smaller node number.
+//!
+//! Passing a node number outside of `1..=node_count()` to any method is a
+//! logic error and panics.
+use crate::map;
+use louds_rs::LoudsNodeNum;
+use std::iter::FusedIterator;
+use std::ops::Range;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// The number of a node of a [RawTrie], in breadth-first order from 1 for
+/// the root. See the [module invariants](self#invariants).
+pub struct NodeId(pub u64);
+
+#[derive(Debug, Clone)]
+/// Consecutive nodes of a [RawTrie], such as the children of a node. See
+/// [RawTrie::children].
+pub struct NodeIds(Range<u64>);
+
+impl Iterator for NodeIds {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<NodeId> {
+        self.0.next().map(NodeId)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for NodeIds {
+    fn next_back(&mut self) -> Option<NodeId> {
+        self.0.next_back().map(NodeId)
+    }
+}
+
+impl ExactSizeIterator for NodeIds {}
+
+impl FusedIterator for NodeIds {}
+
+#[derive(Debug)]
+/// A read-only view of the nodes of a [map::Trie].
+pub struct RawTrie<'a, Label, Value> {
+    trie: &'a map::Trie<Label, Value>,
+}
+
+impl<Label, Value> Clone for RawTrie<'_, Label, Value> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Label, Value> Copy for RawTrie<'_, Label, Value> {}
+
+impl<'a, Label: Ord, Value> RawTrie<'a, Label, Value> {
+    pub(crate) fn new(trie: &'a map::Trie<Label, Value>) -> Self {
+        Self { trie }
+    }
+
+    /// Return the root node.
+    pub fn root(&self) -> NodeId {
+        NodeId(1)
+    }
+
+    /// Return the number of nodes, including the root.
+    pub fn node_count(&self) -> u64 {
+        self.trie.trie_labels.len() as u64 + 1
+    }
+
+    /// Return the children of `node`, in label order.
+    ///
+    /// ```rust
+    /// use trie_rs::map::Trie;
+    /// use trie_rs::raw::NodeId;
+    ///
+    /// let trie = Trie::from_iter([("ab", 0), ("ac", 1)]);
+    /// let raw = trie.raw();
+    /// let a = raw.children(raw.root()).next().unwrap();
+    /// assert_eq!(a, NodeId(2));
+    /// let labels: Vec<u8> = raw.children(a).map(|n| *raw.label(n)).collect();
+    /// assert_eq!(labels, b"bc");
+    /// ```
+    pub fn children(&self, node: NodeId) -> NodeIds {
+        let node = self.validate(node);
+        let mut iter = self.trie.children_node_nums(node);
+        let len = iter.len() as u64;
+        NodeIds(match iter.next() {
+            Some(first) => first.0..first.0 + len,
+            None => 0..0,
+        })
+    }
+
+    /// Return the parent of `node`, or `None` for the root.
+    pub fn parent(&self, node: NodeId) -> Option<NodeId> {
+        let node = self.validate(node);
+        self.trie.parent(node).map(|parent| NodeId(parent.0))
+    }
+
+    /// Return the label on the edge into `node`.
+    ///
+    /// # Panics
+    /// If `node` is the root.
+    pub fn label(&self, node: NodeId) -> &'a Label {
+        let node = self.validate(node);
+        assert!(node.0 > 1, "the root has no label");
+        self.trie.label(node)
+    }
+
+    /// Return the value of `node` if it is terminal.
+    pub fn value(&self, node: NodeId) -> Option<&'a Value> {
+        let node = self.validate(node);
+        self.trie.value(node)
+    }
+
+    /// Return the labels of all non-root nodes, ordered by node number.
+    pub fn labels(&self) -> impl ExactSizeIterator<Item = &'a Label> + 'a {
+        self.trie.trie_labels.iter()
+    }
+
+    /// Return the values of all non-root nodes, ordered by node number.
+    pub fn values(&self) -> impl ExactSizeIterator<Item = Option<&'a Value>> + 'a {
+        (0..self.trie.values.len()).map(|i| self.trie.values.get(i))
+    }
+
+    fn validate(&self, node: NodeId) -> LoudsNodeNum {
+        assert!(
+            node.0 >= 1 && node.0 <= self.node_count(),
+            "NodeId({}) does not exist in this trie",
+            node.0
+        );
+        LoudsNodeNum(node.0)
+    }
+}
+
+#[cfg(test)]
+mod raw_tests {
+    use crate::map::Trie;
+    use crate::raw::NodeId;
+
+    fn build_trie() -> Trie<u8, u8> {
+        Trie::from_iter([("a", 0), ("app", 1), ("apple", 2), ("better", 3)])
+    }
+
+    #[test]
+    fn walk_every_node() {
+        let trie = build_trie();
+        let raw = trie.raw();
+        let mut stack = vec![raw.root()];
+        let mut visited = 0;
+        while let Some(node) = stack.pop() {
+            visited += 1;
+            let children = raw.children(node);
+            let labels: Vec<u8> = children.clone().map(|n| *raw.label(n)).collect();
+            let mut sorted = labels.clone();
+            sorted.sort();
+            assert_eq!(labels, sorted);
+            for child in children.rev() {
+                assert_eq!(raw.parent(child), Some(node));
+                stack.push(child);
+            }
+        }
+        assert_eq!(visited, raw.node_count());
+        assert_eq!(raw.parent(raw.root()), None);
+        assert_eq!(raw.labels().len() as u64, raw.node_count() - 1);
+        assert_eq!(raw.values().flatten().count(), 4);
+    }
+
+    #[test]
+    #[should_panic]
+    fn out_of_range() {
+        let trie = build_trie();
+        let raw = trie.raw();
+        raw.value(NodeId(raw.node_count() + 1));
+    }
+}
@@ -0,0 +1,265 @@
+//! A plain [BTreeMap]-backed stand-in for [map::Trie](crate::map::Trie), under
+//! the crate feature "testing", to run alongside it in differential tests.
+//!
+//! [NaiveMap] has the same methods as [map::Trie](crate::map::Trie) for keys
+//! and searches, and as [map::UpdatableTrie](crate::map::UpdatableTrie) for
+//! updates, answered by scanning a [BTreeMap] instead of walking a trie, so
+//! the two can be driven with the same calls and compared.
+//!
+//! ```rust
+//! use trie_rs::map::Trie;
+//! use trie_rs::reference::NaiveMap;
+//!
+//! let entries = [("app", 0), ("apple", 1), ("app", 2), ("bet", 3)];
+//! let trie = Trie::from_iter(entries);
+//! let naive = NaiveMap::from_iter(entries);
+//! for query in ["", "a", "app", "apples", "c"] {
+//!     assert_eq!(trie.exact_match(query), naive.exact_match(query));
+//!     let a: Vec<(String, &u8)> = trie.predictive_search(query).collect();
+//!     let b: Vec<(String, &u8)> = naive.predictive_search(query).collect();
+//!     assert_eq!(a, b);
+//! }
+//! ```
+use crate::try_collect::{TryCollect, TryFromIterator};
+use std::collections::BTreeMap;
+use std::iter::FromIterator;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A map from sequences of `Label`s to `Value`s with the searches of
+/// [map::Trie](crate::map::Trie), kept as a [BTreeMap].
+pub struct NaiveMap<Label, Value> {
+    map: BTreeMap<Vec<Label>, Value>,
+}
+
+impl<Label, Value> Default for NaiveMap<Label, Value> {
+    fn default() -> Self {
+        Self {
+            map: BTreeMap::new(),
+        }
+    }
+}
+
+impl<Label: Ord + Clone, Value> NaiveMap<Label, Value> {
+    /// Return an empty map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the number of keys.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Return true if there are no keys.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Set the value of `key`, returning the old one. See
+    /// [map::UpdatableTrie::insert](crate::map::UpdatableTrie::insert).
+    ///
+    /// # Panics
+    /// If `key` is empty, as a trie's root holds no value.
+    pub fn insert(&mut self, key: impl AsRef<[Label]>, value: Value) -> Option<Value> {
+        let key = key.as_ref();
+        assert!(!key.is_empty(), "cannot insert the empty key");
+        self.map.insert(key.to_vec(), value)
+    }
+
+    /// Remove `key`, returning its value.
+    pub fn remove(&mut self, key: impl AsRef<[Label]>) -> Option<Value> {
+        self.map.remove(key.as_ref())
+    }
+
+    /// Return `Some(&Value)` if query is an exact match.
+    pub fn exact_match(&self, query: impl AsRef<[Label]>) -> Option<&Value> {
+        self.map.get(query.as_ref())
+    }
+
+    /// Return `Some(&mut Value)` if query is an exact match.
+    pub fn exact_match_mut(&mut self, query: impl AsRef<[Label]>) -> Option<&mut Value> {
+        self.map.get_mut(query.as_ref())
+    }
+
+    /// Return true if some key is longer than `query` and starts with it.
+    pub fn is_prefix(&self, query: impl AsRef<[Label]>) -> bool {
+        let query = query.as_ref();
+        self.below(query).any(|(key, _)| key.len() > query.len())
+    }
+
+    /// Return all entries and their values that match `query`.
+    pub fn predictive_search<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> std::vec::IntoIter<(C, &Value)>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        self.below(query.as_ref())
+            .map(|(key, value)| (collect(key), value))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    /// Return the postfixes and values of all entries that match `query`; the
+    /// query itself is not a postfix.
+    pub fn postfix_search<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> std::vec::IntoIter<(C, &Value)>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        let len = query.as_ref().len();
+        self.below(query.as_ref())
+            .filter(|(key, _)| key.len() > len)
+            .map(|(key, value)| (collect(&key[len..]), value))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    /// Return all entries in lexicographic order.
+    pub fn iter<C, M>(&self) -> impl Iterator<Item = (C, &Value)>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        self.map.iter().map(|(key, value)| (collect(key), value))
+    }
+
+    /// Return the keys that are prefixes of `query`, shortest first.
+    pub fn common_prefix_search<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> std::vec::IntoIter<(C, &Value)>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        let query = query.as_ref();
+        (1..=query.len())
+            .filter_map(|len| self.map.get_key_value(&query[..len]))
+            .map(|(key, value)| (collect(key), value))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    /// Return `query` extended for as long as every key starting with it
+    /// agrees on the next label and `query` is not itself a key, or `None` if
+    /// `query` is empty or no key starts with it. See
+    /// [map::Trie::longest_prefix](crate::map::Trie::longest_prefix).
+    pub fn longest_prefix<C, M>(&self, query: impl AsRef<[Label]>) -> Option<C>
+    where
+        C: TryFromIterator<Label, M>,
+    {
+        let query = query.as_ref();
+        if query.is_empty() {
+            return None;
+        }
+        // A key that ends on the way is the first one, so the first and last
+        // keys share exactly the labels to return.
+        let first = self.below(query).next()?.0;
+        let last = self.below(query).last()?.0;
+        let len = first.iter().zip(last).take_while(|(a, b)| a == b).count();
+        Some(collect(&first[..len]))
+    }
+
+    /// Return the entries whose keys start with `query`, in order.
+    fn below<'a, 'q>(
+        &'a self,
+        query: &'q [Label],
+    ) -> impl Iterator<Item = (&'a Vec<Label>, &'a Value)> + 'q
+    where
+        'a: 'q,
+    {
+        self.map
+            .range(query.to_vec()..)
+            .take_while(move |(key, _)| key.starts_with(query))
+    }
+}
+
+fn collect<Label: Clone, C: TryFromIterator<Label, M>, M>(labels: &[Label]) -> C {
+    labels
+        .iter()
+        .cloned()
+        .try_collect()
+        .expect("Could not collect")
+}
+
+impl<Label, Value, K> FromIterator<(K, Value)> for NaiveMap<Label, Value>
+where
+    K: AsRef<[Label]>,
+    Label: Ord + Clone,
+{
+    /// Insert the entries in order, so a later value of a key replaces an
+    /// earlier one, as with [TrieBuilder::push](crate::map::TrieBuilder::push).
+    fn from_iter<T: IntoIterator<Item = (K, Value)>>(iter: T) -> Self {
+        let mut map = Self::new();
+        for (key, value) in iter {
+            map.insert(key, value);
+        }
+        map
+    }
+}
+
+#[cfg(test)]
+mod reference_tests {
+    use super::NaiveMap;
+    use crate::map::{Trie, UpdatableTrie};
+    use crate::testing::KeyGen;
+    use crate::try_collect::Collect;
+
+    fn compare(entries: &[(Vec<char>, usize)], queries: &[Vec<char>]) {
+        let trie = Trie::from_iter(entries.iter().cloned());
+        let mut naive = NaiveMap::from_iter(entries.iter().cloned());
+        assert_eq!(trie.iter::<Vec<char>, _>().count(), naive.len());
+        let a: Vec<(String, &usize)> = trie.iter().collect();
+        let b: Vec<(String, &usize)> = naive.iter().collect();
+        assert_eq!(a, b);
+        for query in queries {
+            assert_eq!(trie.exact_match(query), naive.exact_match(query));
+            assert_eq!(trie.is_prefix(query), naive.is_prefix(query), "{:?}", query);
+            let a: Vec<(Vec<char>, &usize)> = trie.predictive_search::<_, Collect>(query).collect();
+            let b: Vec<(Vec<char>, &usize)> = naive.predictive_search(query).collect();
+            assert_eq!(a, b);
+            let a: Vec<(String, &usize)> = trie.postfix_search(query).collect();
+            let b: Vec<(String, &usize)> = naive.postfix_search(query).collect();
+            assert_eq!(a, b);
+            let a: Vec<(String, &usize)> = trie.common_prefix_search(query).collect();
+            let b: Vec<(String, &usize)> = naive.common_prefix_search(query).collect();
+            assert_eq!(a, b);
+            assert_eq!(
+                trie.longest_prefix::<String, _>(query),
+                naive.longest_prefix::<String, _>(query),
+                "{:?}",
+                query
+            );
+        }
+        let mut trie = UpdatableTrie::from(trie);
+        for (i, query) in queries.iter().enumerate().filter(|(_, q)| !q.is_empty()) {
+            match i % 3 {
+                0 => assert_eq!(trie.remove(query), naive.remove(query).is_some()),
+                _ => {
+                    trie.insert(query, i);
+                    naive.insert(query, i);
+                }
+            }
+        }
+        let a: Vec<(String, &usize)> = trie.iter().collect();
+        let b: Vec<(String, &usize)> = naive.iter().collect();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn agrees_with_trie() {
+        for seed in 0..100 {
+            let entries = KeyGen::new(seed, "abア🍎".chars()).max_len(5).entries(20);
+            let queries = KeyGen::new(seed + 1000, "abア🍎".chars())
+                .max_len(6)
+                .keys(20)
+                .into_iter()
+                .chain(entries.iter().map(|(key, _)| key.clone()))
+                .chain([Vec::new()])
+                .collect::<Vec<_>>();
+            compare(&entries, &queries);
+        }
+    }
+}
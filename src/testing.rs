@@ -0,0 +1,222 @@
+//! Generators of key sets and checks of a trie against a [BTreeMap], under the
+//! crate feature "testing", for property tests and fuzzing here and in
+//! downstream crates.
+//!
+//! Everything is seeded and dependency free, so a failing case can be replayed
+//! from its seed.
+//!
+//! ```rust
+//! use trie_rs::testing::{check_against_btree_map, KeyGen};
+//!
+//! for seed in 0..100 {
+//!     let entries = KeyGen::new(seed, *b"abc").max_len(6).entries(20);
+//!     check_against_btree_map(&entries);
+//! }
+//! ```
+use crate::map::{Trie, TrieBuilder};
+use crate::try_collect::Collect;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Debug;
+
+#[derive(Debug, Clone)]
+/// A seeded generator of keys over an alphabet.
+///
+/// Keys often extend or share a prefix with an earlier key, so the tries
+/// built from them branch and nest like real ones, and [KeyGen::entries]
+/// repeats keys so a later push has to replace an earlier value.
+pub struct KeyGen<Label> {
+    state: u64,
+    alphabet: Vec<Label>,
+    max_len: usize,
+    keys: Vec<Vec<Label>>,
+}
+
+impl<Label: Clone> KeyGen<Label> {
+    /// Return a generator of keys of 1 to 8 labels from `alphabet`.
+    ///
+    /// # Panics
+    /// If `alphabet` is empty.
+    pub fn new(seed: u64, alphabet: impl IntoIterator<Item = Label>) -> Self {
+        let alphabet: Vec<Label> = alphabet.into_iter().collect();
+        assert!(!alphabet.is_empty(), "empty alphabet");
+        Self {
+            state: seed,
+            alphabet,
+            max_len: 8,
+            keys: Vec::new(),
+        }
+    }
+
+    /// Generate keys of at most `max_len` labels, and at least one.
+    pub fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len.max(1);
+        self
+    }
+
+    /// Return a number below `n` (SplitMix64).
+    fn below(&mut self, n: usize) -> usize {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        (z % n as u64) as usize
+    }
+
+    /// Return the next key.
+    pub fn key(&mut self) -> Vec<Label> {
+        let len = 1 + self.below(self.max_len);
+        let kind = self.below(4);
+        let mut key = if kind < 3 && !self.keys.is_empty() {
+            let earlier = self.below(self.keys.len());
+            if kind == 0 {
+                // A repeat of an earlier key.
+                let key = self.keys[earlier].clone();
+                self.keys.push(key.clone());
+                return key;
+            }
+            // A prefix of an earlier key, extended below.
+            let keep = self.below(self.keys[earlier].len() + 1).min(len);
+            self.keys[earlier][..keep].to_vec()
+        } else {
+            Vec::new()
+        };
+        while key.len() < len && (key.is_empty() || self.below(3) != 0) {
+            let label = self.below(self.alphabet.len());
+            key.push(self.alphabet[label].clone());
+        }
+        self.keys.push(key.clone());
+        key
+    }
+
+    /// Return the next `count` keys.
+    pub fn keys(&mut self, count: usize) -> Vec<Vec<Label>> {
+        (0..count).map(|_| self.key()).collect()
+    }
+
+    /// Return the next `count` keys, each with its position as the value.
+    pub fn entries(&mut self, count: usize) -> Vec<(Vec<Label>, usize)> {
+        (0..count).map(|i| (self.key(), i)).collect()
+    }
+}
+
+/// Build a trie by pushing `entries` in order, and panic with the query and
+/// search if it answers differently from a [BTreeMap] they were inserted into
+/// in the same order.
+///
+/// Checked are [Trie::iter], and for every prefix of every key, and every key
+/// followed by a label, [Trie::exact_match], [Trie::is_prefix],
+/// [Trie::predictive_search], [Trie::postfix_search] and
+/// [Trie::common_prefix_search].
+pub fn check_against_btree_map<Label, Value>(entries: &[(Vec<Label>, Value)])
+where
+    Label: Ord + Clone + Debug,
+    Value: Clone + PartialEq + Debug,
+{
+    let mut builder = TrieBuilder::new();
+    let mut expected = BTreeMap::new();
+    for (key, value) in entries {
+        builder.push(key, value.clone());
+        expected.insert(key.clone(), value.clone());
+    }
+    let trie = builder.build();
+    check_trie(&trie, &expected);
+}
+
+/// Panic with the query and search if `trie` answers differently from
+/// `expected`. See [check_against_btree_map].
+pub fn check_trie<Label, Value>(trie: &Trie<Label, Value>, expected: &BTreeMap<Vec<Label>, Value>)
+where
+    Label: Ord + Clone + Debug,
+    Value: PartialEq + Debug,
+{
+    let actual: Vec<(Vec<Label>, &Value)> = trie.iter::<_, Collect>().collect();
+    let wanted: Vec<(Vec<Label>, &Value)> = expected.iter().map(|(k, v)| (k.clone(), v)).collect();
+    assert_eq!(actual, wanted, "iter");
+
+    let mut queries = BTreeSet::new();
+    for key in expected.keys() {
+        for len in 0..=key.len() {
+            queries.insert(key[..len].to_vec());
+        }
+        if let Some(first) = key.first() {
+            let mut longer = key.clone();
+            longer.push(first.clone());
+            queries.insert(longer);
+        }
+    }
+    for query in &queries {
+        let below: Vec<(&Vec<Label>, &Value)> = expected
+            .range(query.clone()..)
+            .take_while(|(key, _)| key.starts_with(query))
+            .collect();
+
+        assert_eq!(
+            trie.exact_match(query),
+            expected.get(query),
+            "exact_match of {:?}",
+            query
+        );
+        assert_eq!(
+            trie.is_prefix(query),
+            below.iter().any(|(key, _)| key.len() > query.len()),
+            "is_prefix of {:?}",
+            query
+        );
+        let actual: Vec<(Vec<Label>, &Value)> = trie.predictive_search(query).collect();
+        let wanted: Vec<(Vec<Label>, &Value)> =
+            below.iter().map(|(k, v)| ((*k).clone(), *v)).collect();
+        assert_eq!(actual, wanted, "predictive_search of {:?}", query);
+        let actual: Vec<(Vec<Label>, &Value)> = trie.postfix_search(query).collect();
+        // Postfixes are strict: the query itself is not one.
+        let wanted: Vec<(Vec<Label>, &Value)> = below
+            .iter()
+            .filter(|(k, _)| k.len() > query.len())
+            .map(|(k, v)| (k[query.len()..].to_vec(), *v))
+            .collect();
+        assert_eq!(actual, wanted, "postfix_search of {:?}", query);
+        let actual: Vec<(Vec<Label>, &Value)> = trie.common_prefix_search(query).collect();
+        let wanted: Vec<(Vec<Label>, &Value)> = (0..=query.len())
+            .filter_map(|len| {
+                expected
+                    .get_key_value(&query[..len])
+                    .map(|(k, v)| (k.clone(), v))
+            })
+            .collect();
+        assert_eq!(actual, wanted, "common_prefix_search of {:?}", query);
+    }
+}
+
+#[cfg(test)]
+mod testing_tests {
+    use super::{check_against_btree_map, KeyGen};
+
+    #[test]
+    fn generated_tries_match_btree_map() {
+        for seed in 0..200 {
+            let entries = KeyGen::new(seed, *b"ab").max_len(5).entries(30);
+            check_against_btree_map(&entries);
+            let entries = KeyGen::new(seed, "aあ🍎".chars()).entries(50);
+            check_against_btree_map(&entries);
+        }
+    }
+
+    #[test]
+    fn keys_repeat_and_share_prefixes() {
+        let keys = KeyGen::new(7, 0..10u8).max_len(4).keys(100);
+        assert_eq!(keys, KeyGen::new(7, 0..10u8).max_len(4).keys(100));
+        assert!(keys.iter().all(|key| (1..=4).contains(&key.len())));
+        let distinct: std::collections::BTreeSet<_> = keys.iter().collect();
+        assert!(distinct.len() < keys.len());
+        assert!(keys.iter().any(|key| keys
+            .iter()
+            .any(|other| other.len() > key.len() && other.starts_with(key))));
+    }
+
+    #[test]
+    #[should_panic(expected = "iter")]
+    fn reports_mismatches() {
+        let trie = crate::map::Trie::from_iter([("a", 0)]);
+        super::check_trie(&trie, &[(b"a".to_vec(), 1)].into_iter().collect());
+    }
+}
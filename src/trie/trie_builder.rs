@@ -29,10 +29,63 @@ impl<Label: Ord> TrieBuilder<Label> {
         self.0.insert(entry, ());
     }
 
+    /// Add a cloneable entry, or return [map::InvalidKey::Empty] if it is
+    /// empty.
+    pub fn try_push<Arr: AsRef<[Label]>>(&mut self, entry: Arr) -> Result<(), map::InvalidKey>
+    where
+        Label: Clone,
+    {
+        self.0.try_push(entry, ())
+    }
+
+    /// Add an entry, or return [map::InvalidKey::Empty] if it is empty.
+    pub fn try_insert<Arr: IntoIterator<Item = Label>>(
+        &mut self,
+        entry: Arr,
+    ) -> Result<(), map::InvalidKey> {
+        self.0.try_insert(entry, ())
+    }
+
+    /// Reject keys longer than `limit` labels from now on. See
+    /// [map::TrieBuilder::limit_key_len]; the built map converts into a [Trie]
+    /// with [Trie::from].
+    pub fn limit_key_len(self, limit: usize) -> map::KeyLenLimitedBuilder<Label, ()> {
+        self.0.limit_key_len(limit)
+    }
+
+    /// Push keys as they arrive, calling `progress` after every `batch_size`
+    /// keys. See [map::TrieBuilder::ingest].
+    pub fn ingest<Key, F>(
+        &mut self,
+        keys: impl IntoIterator<Item = Key>,
+        batch_size: usize,
+        progress: F,
+    ) -> Result<usize, map::IngestAborted>
+    where
+        Key: IntoIterator<Item = Label>,
+        F: FnMut(usize) -> std::ops::ControlFlow<()>,
+    {
+        self.0
+            .ingest(keys.into_iter().map(|key| (key, ())), batch_size, progress)
+    }
+
+    /// Remember the order entries are pushed in. See
+    /// [map::TrieBuilder::keep_insertion_order].
+    pub fn keep_insertion_order(&mut self) {
+        self.0.keep_insertion_order();
+    }
+
     /// Build a [Trie].
     pub fn build(self) -> Trie<Label> {
         Trie(self.0.build())
     }
+
+    /// Build a [Trie] and report on it. See
+    /// [map::TrieBuilder::build_with_report].
+    pub fn build_with_report(self) -> (Trie<Label>, map::BuildReport) {
+        let (trie, report) = self.0.build_with_report();
+        (Trie(trie), report)
+    }
 }
 
 impl<Label: Ord> Default for TrieBuilder<Label> {
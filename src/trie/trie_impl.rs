@@ -1,8 +1,12 @@
-use crate::inc_search::IncSearch;
-use crate::iter::{Keys, KeysExt, PostfixIter, PrefixIter, SearchIter};
+use crate::inc_search::{Answer, IncSearch};
+use crate::iter::{
+    FuzzyIter, GapIter, InsertionOrderIter, Keys, KeysExt, PostfixIter, PrefixIter, RangeIter,
+    SearchIter,
+};
 use crate::map;
 use crate::try_collect::TryFromIterator;
 use std::iter::FromIterator;
+use std::ops::RangeBounds;
 
 #[cfg(feature = "mem_dbg")]
 use mem_dbg::MemDbg;
@@ -11,6 +15,16 @@ use mem_dbg::MemDbg;
 #[cfg_attr(feature = "mem_dbg", derive(mem_dbg::MemDbg, mem_dbg::MemSize))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// A trie for sequences of the type `Label`.
+///
+/// It is a [map::Trie] with unit values, and converts to and from one without
+/// copying, see [Trie::as_map] and [map::Trie::as_set].
+///
+/// Besides its labels and LOUDS bits, it stores one terminal bit per node,
+/// the number of keys of each length that occurs, and the paths to the keys
+/// of a trie of at most four keys. Label runs and insertion order are only
+/// stored when asked for, with [Trie::build_label_runs] and
+/// [super::TrieBuilder::keep_insertion_order].
+#[repr(transparent)]
 pub struct Trie<Label>(pub map::Trie<Label, ()>);
 
 impl<Label: Ord> Trie<Label> {
@@ -36,6 +50,47 @@ impl<Label: Ord> Trie<Label> {
         self.0.exact_match(query).is_some()
     }
 
+    /// Return true if `query` is in the set, without allocating. See
+    /// [map::Trie::contains_key].
+    ///
+    /// ```rust
+    /// use trie_rs::Trie;
+    ///
+    /// let trie = Trie::from_iter(["app", "apple"]);
+    /// assert!(trie.contains("apple"));
+    /// assert!(!trie.contains("ap"));
+    /// ```
+    pub fn contains(&self, query: impl AsRef<[Label]>) -> bool {
+        self.0.contains_key(query)
+    }
+
+    /// Lay the set out anew and drop spare capacity, and return the bytes
+    /// reclaimed. See [map::Trie::shrink].
+    pub fn shrink(&mut self) -> usize
+    where
+        Label: Clone,
+    {
+        self.0.shrink()
+    }
+
+    /// Write the set to `writer` in a compact binary format. See
+    /// [map::Trie::serialize_into].
+    pub fn serialize_into<W: std::io::Write>(&self, writer: W) -> std::io::Result<()>
+    where
+        Label: Clone + map::BinaryCodec,
+    {
+        self.0.serialize_into(writer)
+    }
+
+    /// Read a set written by [Trie::serialize_into]. See
+    /// [map::Trie::deserialize_from].
+    pub fn deserialize_from<R: std::io::Read>(reader: R) -> std::io::Result<Self>
+    where
+        Label: Clone + map::BinaryCodec,
+    {
+        map::Trie::deserialize_from(reader).map(Trie)
+    }
+
     /// Return the common prefixes of `query`.
     ///
     /// # Arguments
@@ -66,6 +121,32 @@ impl<Label: Ord> Trie<Label> {
         self.0.common_prefix_search(query).keys()
     }
 
+    /// Return the common prefixes of `query` along with the offset into
+    /// `query` where each one ends.
+    ///
+    /// ```rust
+    /// use trie_rs::Trie;
+    ///
+    /// let trie = Trie::from_iter(["a", "app", "apple"]);
+    ///
+    /// let results: Vec<(String, usize)> = trie.common_prefix_search_with_offsets("applet").collect();
+    ///
+    /// assert_eq!(results, [("a".to_string(), 1), ("app".to_string(), 3), ("apple".to_string(), 5)]);
+    /// ```
+    pub fn common_prefix_search_with_offsets<'a, C, M>(
+        &'a self,
+        query: impl AsRef<[Label]>,
+    ) -> impl Iterator<Item = (C, usize)> + 'a
+    where
+        C: TryFromIterator<Label, M> + 'a,
+        M: 'a,
+        Label: Clone,
+    {
+        self.0
+            .common_prefix_search_with_offsets(query)
+            .map(|(key, offset, _)| (key, offset))
+    }
+
     /// Return all entries that match `query`.
     pub fn predictive_search<C, M>(
         &self,
@@ -78,6 +159,67 @@ impl<Label: Ord> Trie<Label> {
         self.0.predictive_search(query).keys()
     }
 
+    /// Store all entries that match `query` in `arena`, replacing its
+    /// previous ones. See [map::Trie::predictive_search_in].
+    pub fn predictive_search_in<'a>(
+        &'a self,
+        query: impl AsRef<[Label]>,
+        arena: &mut map::LabelArena<'a, Label, ()>,
+    ) where
+        Label: Clone,
+    {
+        self.0.predictive_search_in(query, arena)
+    }
+
+    /// Return up to `k` keys starting with `prefix`, ranked by how many keys
+    /// start with each of them, with those counts. See
+    /// [map::Trie::popular_completions].
+    pub fn popular_completions<C, M>(
+        &self,
+        prefix: impl AsRef<[Label]>,
+        k: usize,
+    ) -> Vec<(C, usize)>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        self.0
+            .popular_completions(prefix, k)
+            .into_iter()
+            .map(|(key, _, count)| (key, count))
+            .collect()
+    }
+
+    /// Return a key starting with `prefix` made by walking down from it at
+    /// random. See [map::Trie::random_walk].
+    pub fn random_walk<C, M>(
+        &self,
+        prefix: impl AsRef<[Label]>,
+        rng: impl FnMut(usize) -> usize,
+        max_len: usize,
+    ) -> Option<C>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        self.0.random_walk(prefix, rng, max_len)
+    }
+
+    /// Return a key starting with `prefix` at random, each equally likely.
+    /// See [map::Trie::random_walk_weighted].
+    pub fn random_walk_weighted<C, M>(
+        &self,
+        prefix: impl AsRef<[Label]>,
+        rng: impl FnMut(usize) -> usize,
+        max_len: usize,
+    ) -> Option<C>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        self.0.random_walk_weighted(prefix, rng, max_len)
+    }
+
     /// Return the postfixes of all entries that match `query`.
     ///
     /// # Arguments
@@ -111,12 +253,64 @@ impl<Label: Ord> Trie<Label> {
         self.0.postfix_search(query).keys()
     }
 
+    /// Return all keys within `max_edits` edits of `query`, with their edit
+    /// distance. See [map::Trie::fuzzy_search]. Items carry the unit value
+    /// like [map::Trie] items do, so [FuzzyIter::transpositions] is available.
+    ///
+    /// ```rust
+    /// use trie_rs::Trie;
+    ///
+    /// let trie = Trie::from_iter(["the", "then", "tea"]);
+    /// let results: Vec<(String, usize)> = trie
+    ///     .fuzzy_search("teh", 1)
+    ///     .transpositions()
+    ///     .map(|(key, distance, _)| (key, distance))
+    ///     .collect();
+    /// assert_eq!(results, [("tea".to_string(), 1), ("the".to_string(), 1)]);
+    /// ```
+    pub fn fuzzy_search<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+        max_edits: usize,
+    ) -> FuzzyIter<'_, Label, (), C, M>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        self.0.fuzzy_search(query, max_edits)
+    }
+
+    /// Return all keys that start with the labels of `query` in order, with
+    /// gaps of up to `max_gap` labels. See [map::Trie::gap_search]. Items
+    /// carry the unit value like [map::Trie] items do.
+    pub fn gap_search<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+        max_gap: usize,
+    ) -> GapIter<'_, Label, (), C, M>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        self.0.gap_search(query, max_gap)
+    }
+
+    /// Return every occurrence of a key in `text` with its span. See
+    /// [map::Trie::scan]. Items carry the unit value like [map::Trie] items
+    /// do.
+    pub fn scan<'a, 't>(
+        &'a self,
+        text: &'t (impl AsRef<[Label]> + ?Sized),
+    ) -> map::Scan<'a, 't, Label, ()> {
+        self.0.scan(text)
+    }
+
     /// Returns an iterator across all keys in the trie.
     ///
     /// # Examples
     /// In the following example we illustrate how to iterate over all keys in the trie.
-    /// Note that the order of the keys is not guaranteed, as they will be returned in
-    /// lexicographical order.
+    /// The keys are always returned in lexicographical order of their labels,
+    /// regardless of the order they were pushed in.
     ///
     /// ```rust
     /// use trie_rs::Trie;
@@ -136,6 +330,41 @@ impl<Label: Ord> Trie<Label> {
         self.postfix_search([])
     }
 
+    /// Return the keys within `range`, in lexicographic order. See
+    /// [map::Trie::range].
+    ///
+    /// ```rust
+    /// use std::ops::Bound::{Excluded, Unbounded};
+    /// use trie_rs::Trie;
+    ///
+    /// let trie = Trie::from_iter(["a", "b", "b\u{10FFFF}", "c"].map(|s| s.chars().collect::<Vec<_>>()));
+    /// let results: Vec<String> = trie.range((Excluded(&['b'][..]), Unbounded)).collect();
+    /// assert_eq!(results, ["b\u{10FFFF}", "c"]);
+    /// ```
+    pub fn range<C, M>(
+        &self,
+        range: impl RangeBounds<[Label]>,
+    ) -> Keys<RangeIter<'_, Label, (), C, M>>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        self.0.range(range).keys()
+    }
+
+    /// Returns an iterator across all keys in the order they were pushed, or
+    /// `None` unless the trie was built after
+    /// [TrieBuilder::keep_insertion_order](crate::TrieBuilder::keep_insertion_order).
+    pub fn iter_insertion_order<C, M>(
+        &self,
+    ) -> Option<Keys<InsertionOrderIter<'_, Label, (), C, M>>>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        self.0.iter_insertion_order().map(|iter| iter.keys())
+    }
+
     /// Create an incremental search. Useful for interactive applications. See
     /// [crate::inc_search] for details.
     pub fn inc_search(&self) -> IncSearch<'_, Label, ()> {
@@ -150,6 +379,38 @@ impl<Label: Ord> Trie<Label> {
         self.0.is_prefix(query)
     }
 
+    /// Return true if some key starts with `query`, including `query` itself.
+    /// See [map::Trie::has_completions].
+    pub fn has_completions(&self, query: impl AsRef<[Label]>) -> bool {
+        self.0.has_completions(query)
+    }
+
+    /// Return whether `query` is a key, a prefix, or both. See
+    /// [map::Trie::answer].
+    pub fn answer(&self, query: impl AsRef<[Label]>) -> Option<Answer> {
+        self.0.answer(query)
+    }
+
+    /// Return [Trie::answer] for each of `queries`. See [map::Trie::classify].
+    pub fn classify<'a, Q: AsRef<[Label]>>(
+        &'a self,
+        queries: impl IntoIterator<Item = Q> + 'a,
+    ) -> impl Iterator<Item = Option<Answer>> + 'a {
+        self.0.classify(queries)
+    }
+
+    /// Return true if every nonempty proper prefix of `query` is in the set.
+    /// See [map::Trie::all_prefixes_exist].
+    pub fn all_prefixes_exist(&self, query: impl AsRef<[Label]>) -> bool {
+        self.0.all_prefixes_exist(query)
+    }
+
+    /// Return [Trie::answer] for each nonempty prefix of `query`, in one
+    /// descent. See [map::Trie::classify_path].
+    pub fn classify_path(&self, query: impl AsRef<[Label]>) -> Vec<Option<Answer>> {
+        self.0.classify_path(query)
+    }
+
     /// Return the longest shared prefix of `query`.
     pub fn longest_prefix<C, M>(&self, query: impl AsRef<[Label]>) -> Option<C>
     where
@@ -158,6 +419,253 @@ impl<Label: Ord> Trie<Label> {
     {
         self.0.longest_prefix(query)
     }
+
+    /// Like [Trie::predictive_search], but never panics. See
+    /// [map::Trie::try_predictive_search].
+    pub fn try_predictive_search<'a, C, M>(
+        &'a self,
+        query: impl AsRef<[Label]>,
+    ) -> impl Iterator<Item = Result<C, C::Error>> + 'a
+    where
+        C: TryFromIterator<Label, M> + Clone + 'a,
+        M: 'a,
+        C::Error: Clone,
+        Label: Clone,
+    {
+        self.0.try_predictive_search(query).keys()
+    }
+
+    /// Like [Trie::postfix_search], but never panics. See
+    /// [map::Trie::try_postfix_search].
+    pub fn try_postfix_search<'a, C, M>(
+        &'a self,
+        query: impl AsRef<[Label]>,
+    ) -> impl Iterator<Item = Result<C, C::Error>> + 'a
+    where
+        C: TryFromIterator<Label, M> + 'a,
+        M: 'a,
+        Label: Clone,
+    {
+        self.0.try_postfix_search(query).keys()
+    }
+
+    /// Like [Trie::iter], but never panics. See [map::Trie::try_iter].
+    pub fn try_iter<'a, C, M>(&'a self) -> impl Iterator<Item = Result<C, C::Error>> + 'a
+    where
+        C: TryFromIterator<Label, M> + 'a,
+        M: 'a,
+        Label: Clone,
+    {
+        self.0.try_iter().keys()
+    }
+
+    /// Like [Trie::common_prefix_search], but never panics. See
+    /// [map::Trie::try_common_prefix_search].
+    pub fn try_common_prefix_search<'a, C, M>(
+        &'a self,
+        query: impl AsRef<[Label]>,
+    ) -> impl Iterator<Item = Result<C, C::Error>> + 'a
+    where
+        C: TryFromIterator<Label, M> + 'a,
+        M: 'a,
+        Label: Clone,
+    {
+        self.0.try_common_prefix_search(query).keys()
+    }
+
+    /// Like [Trie::longest_prefix], but never panics. See
+    /// [map::Trie::try_longest_prefix].
+    pub fn try_longest_prefix<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> Result<Option<C>, C::Error>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        self.0.try_longest_prefix(query)
+    }
+
+    /// Return the number of `Label`s matched from `query` and the labels
+    /// [Trie::longest_prefix] extended it by. See
+    /// [map::Trie::longest_prefix_with_extension].
+    pub fn longest_prefix_with_extension<C, M>(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> Option<(usize, C)>
+    where
+        C: TryFromIterator<Label, M>,
+        Label: Clone,
+    {
+        self.0.longest_prefix_with_extension(query)
+    }
+
+    /// Return the length of the longest key that is a prefix of `query`,
+    /// `query` included. See [map::Trie::longest_match].
+    pub fn longest_match(&self, query: impl AsRef<[Label]>) -> Option<usize> {
+        self.0.longest_match(query).map(|(len, _)| len)
+    }
+
+    /// Return the node reached by `query`. See [map::Trie::node].
+    pub fn node(&self, query: impl AsRef<[Label]>) -> Option<map::NodeRef<'_, Label, ()>> {
+        self.0.node(query)
+    }
+
+    /// Return the children of the node reached by `query` in label order.
+    pub fn children(&self, query: impl AsRef<[Label]>) -> map::ChildNodeRefs<'_, Label, ()> {
+        self.0.children(query)
+    }
+
+    /// Return the nodes of the keys that are prefixes of `query`. See
+    /// [map::Trie::prefixes_of_nodes].
+    pub fn prefixes_of_nodes<Q: AsRef<[Label]>>(
+        &self,
+        query: Q,
+    ) -> map::PrefixNodes<'_, Label, (), Q> {
+        self.0.prefixes_of_nodes(query)
+    }
+
+    /// Return the nodes of all keys starting with `query`. See
+    /// [map::Trie::predictive_search_nodes].
+    pub fn predictive_search_nodes(
+        &self,
+        query: impl AsRef<[Label]>,
+    ) -> impl Iterator<Item = map::NodeRef<'_, Label, ()>> + '_ {
+        self.0.predictive_search_nodes(query)
+    }
+
+    /// Return the nodes of all keys in lexicographic order. See
+    /// [map::Trie::iter_nodes].
+    pub fn iter_nodes(&self) -> impl Iterator<Item = map::NodeRef<'_, Label, ()>> + '_ {
+        self.0.iter_nodes()
+    }
+
+    /// Return true if every key of `queries` is in this trie. See
+    /// [map::Trie::contains_all].
+    pub fn contains_all(&self, queries: &Trie<Label>) -> bool {
+        self.0.contains_all(&queries.0)
+    }
+
+    /// Return true if any key of `queries` is in this trie. See
+    /// [map::Trie::contains_any].
+    pub fn contains_any(&self, queries: &Trie<Label>) -> bool {
+        self.0.contains_any(&queries.0)
+    }
+
+    /// Lay the trie out anew. See [map::Trie::rebuild].
+    pub fn rebuild(self) -> Self {
+        Trie(self.0.rebuild())
+    }
+
+    /// Report the size of the keys against the size of the trie storing them.
+    /// See [map::Trie::compression_report].
+    pub fn compression_report(&self) -> map::CompressionReport {
+        self.0.compression_report()
+    }
+
+    /// Count the identical subtrees. See [map::Trie::suffix_sharing].
+    pub fn suffix_sharing(&self) -> map::SuffixSharing {
+        self.0.suffix_sharing()
+    }
+
+    /// Return true if both tries have the same layout. See
+    /// [map::Trie::canonical_eq].
+    pub fn canonical_eq(&self, other: &Trie<Label>) -> bool {
+        self.0.canonical_eq(&other.0)
+    }
+
+    /// Return the length of the longest key. See [map::Trie::max_key_len].
+    pub fn max_key_len(&self) -> Option<usize> {
+        self.0.max_key_len()
+    }
+
+    /// Return the length of the shortest key. See [map::Trie::min_key_len].
+    pub fn min_key_len(&self) -> Option<usize> {
+        self.0.min_key_len()
+    }
+
+    /// Return the number of keys of each length. See
+    /// [map::Trie::key_len_histogram].
+    pub fn key_len_histogram(&self) -> Vec<usize> {
+        self.0.key_len_histogram()
+    }
+
+    /// Return true if both tries contain the same keys.
+    pub fn keys_eq(&self, other: &Trie<Label>) -> bool {
+        self.0.keys_eq(&other.0)
+    }
+
+    /// Store the labels of single-child chains contiguously. See
+    /// [map::Trie::build_label_runs].
+    pub fn build_label_runs(&mut self)
+    where
+        Label: Clone,
+    {
+        self.0.build_label_runs()
+    }
+}
+
+impl<Label> Trie<Label> {
+    /// Return this trie as a [map::Trie] with unit values.
+    pub fn as_map(&self) -> &map::Trie<Label, ()> {
+        &self.0
+    }
+
+    /// Turn this trie into a [map::Trie] with unit values.
+    pub fn into_map(self) -> map::Trie<Label, ()> {
+        self.0
+    }
+}
+
+impl<Label> map::Trie<Label, ()> {
+    /// Return this trie as a [Trie] of keys, for APIs that take one.
+    ///
+    /// ```rust
+    /// use trie_rs::map;
+    ///
+    /// fn count_keys(set: &trie_rs::Trie<u8>) -> usize {
+    ///     set.iter::<Vec<u8>, _>().count()
+    /// }
+    ///
+    /// let flags: map::Trie<u8, ()> = map::Trie::from_iter([("-v", ()), ("-q", ())]);
+    /// assert_eq!(count_keys(flags.as_set()), 2);
+    /// assert!(std::ptr::eq(flags.as_set().as_map(), &flags));
+    /// ```
+    pub fn as_set(&self) -> &Trie<Label> {
+        let map: *const map::Trie<Label, ()> = self;
+        // SAFETY: Trie is repr(transparent) over map::Trie<Label, ()>, so the
+        // two have the same layout, and the reference keeps `self` borrowed.
+        unsafe { &*(map as *const Trie<Label>) }
+    }
+
+    /// Turn this trie into a [Trie] of keys.
+    pub fn into_set(self) -> Trie<Label> {
+        Trie(self)
+    }
+}
+
+impl<Label> From<map::Trie<Label, ()>> for Trie<Label> {
+    fn from(trie: map::Trie<Label, ()>) -> Self {
+        Trie(trie)
+    }
+}
+
+impl<Label> From<Trie<Label>> for map::Trie<Label, ()> {
+    fn from(trie: Trie<Label>) -> Self {
+        trie.0
+    }
+}
+
+impl<Label> AsRef<map::Trie<Label, ()>> for Trie<Label> {
+    fn as_ref(&self) -> &map::Trie<Label, ()> {
+        &self.0
+    }
+}
+
+impl<Label> AsRef<Trie<Label>> for map::Trie<Label, ()> {
+    fn as_ref(&self) -> &Trie<Label> {
+        self.as_set()
+    }
 }
 
 impl<Label, C> FromIterator<C> for Trie<Label>
@@ -207,6 +715,24 @@ mod search_tests {
         assert!(trie.exact_match("application"));
     }
 
+    #[test]
+    fn set_and_map_views() {
+        let trie = build_trie();
+        let map: &crate::map::Trie<u8, ()> = trie.as_map();
+        assert_eq!(map.exact_match("apple"), Some(&()));
+        assert!(std::ptr::eq(map.as_set(), &trie));
+        let keys: Vec<String> = map.as_set().iter().collect();
+        let expected: Vec<String> = trie.iter().collect();
+        assert_eq!(keys, expected);
+
+        let map = trie.into_map();
+        let set: Trie<u8> = map.into_set();
+        assert!(set.exact_match("better"));
+        let map = crate::map::Trie::from(set);
+        let set = Trie::from(map);
+        assert!(set.is_prefix("app"));
+    }
+
     #[test]
     fn clone() {
         let trie = build_trie();
@@ -218,7 +744,7 @@ mod search_tests {
     fn print_debug() {
         let trie: Trie<u8> = ["a"].into_iter().collect();
         assert_eq!(format!("{:?}", trie),
-"Trie(Trie { louds: Louds { lbs: Fid { byte_vec: [160], bit_len: 5, chunks: Chunks { chunks: [Chunk { value: 2, blocks: Blocks { blocks: [Block { value: 1, length: 1 }, Block { value: 1, length: 1 }, Block { value: 2, length: 1 }, Block { value: 2, length: 1 }], blocks_cnt: 4 } }, Chunk { value: 2, blocks: Blocks { blocks: [Block { value: 0, length: 1 }], blocks_cnt: 1 } }], chunks_cnt: 2 }, table: PopcountTable { bit_length: 1, table: [0, 1] } } }, trie_labels: [TrieLabel { label: 97, value: Some(()) }] })"
+"Trie(Trie { louds: Louds { lbs: Fid { byte_vec: [160], bit_len: 5, chunks: Chunks { chunks: [Chunk { value: 2, blocks: Blocks { blocks: [Block { value: 1, length: 1 }, Block { value: 1, length: 1 }, Block { value: 2, length: 1 }, Block { value: 2, length: 1 }], blocks_cnt: 4 } }, Chunk { value: 2, blocks: Blocks { blocks: [Block { value: 0, length: 1 }], blocks_cnt: 1 } }], chunks_cnt: 2 }, table: PopcountTable { bit_length: 1, table: [0, 1] } } }, trie_labels: [97], values: NodeValues { slots: [], terminals: [1], zero_sized: [()], len: 1 }, runs: None, insertion_order: None, key_lens: [(1, 1)], tiny: Some(TinyPaths { paths: [[LoudsNodeNum(2)]] }) })"
         );
     }
 
@@ -230,7 +756,7 @@ mod search_tests {
         builder.push("a");
         builder.push("app");
         assert_eq!(format!("{:?}", builder),
-"TrieBuilder(TrieBuilder { naive_trie: Root(NaiveTrieRoot { children: [IntermOrLeaf(NaiveTrieIntermOrLeaf { children: [IntermOrLeaf(NaiveTrieIntermOrLeaf { children: [IntermOrLeaf(NaiveTrieIntermOrLeaf { children: [], label: 112, value: Some(()) })], label: 112, value: None })], label: 97, value: Some(()) })] }) })"
+"TrieBuilder(TrieBuilder { naive_trie: Root(NaiveTrieRoot { children: [IntermOrLeaf(NaiveTrieIntermOrLeaf { children: [IntermOrLeaf(NaiveTrieIntermOrLeaf { children: [IntermOrLeaf(NaiveTrieIntermOrLeaf { children: [], label: 112, value: Some(()), seq: 1 })], label: 112, value: None, seq: 0 })], label: 97, value: Some(()), seq: 0 })], pushes: 2, last_path: [0, 0, 0] }), keep_insertion_order: false })"
         );
     }
 
@@ -99,3 +99,164 @@ impl TryFromIterator<u8, StringCollect> for String {
         String::from_utf8(iter.into_iter().collect())
     }
 }
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+/// A [String] collected from word labels joined with single spaces, e.g. the
+/// keys of a phrase trie over `String` labels.
+///
+/// ```
+/// use trie_rs::try_collect::Phrase;
+/// use trie_rs::map::Trie;
+///
+/// let trie = Trie::from_iter([(["new", "york"].map(String::from), 0)]);
+/// let phrases: Vec<(Phrase, &u8)> = trie.iter().collect();
+/// assert_eq!(phrases[0].0.as_ref(), "new york");
+/// ```
+pub struct Phrase(pub String);
+
+impl<S: AsRef<str>> FromIterator<S> for Phrase {
+    fn from_iter<T: IntoIterator<Item = S>>(iter: T) -> Self {
+        let mut phrase = String::new();
+        for (i, word) in iter.into_iter().enumerate() {
+            if i > 0 {
+                phrase.push(' ');
+            }
+            phrase.push_str(word.as_ref());
+        }
+        Phrase(phrase)
+    }
+}
+
+impl From<Phrase> for String {
+    fn from(s: Phrase) -> Self {
+        s.0
+    }
+}
+
+impl AsRef<str> for Phrase {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone)]
+/// Marker type for the `Result` [TryFromIterator] implementation.
+#[doc(hidden)]
+pub struct ResultCollect<M>(std::marker::PhantomData<M>);
+
+/// Collecting into `Result<C, C::Error>` never fails; the error is returned
+/// as a value instead. Search iterators collecting into it yield failed
+/// collections as `Err` items rather than panicking.
+///
+/// ```
+/// use trie_rs::Trie;
+///
+/// let trie = Trie::from_iter([&b"ok"[..], &[0xff]]);
+/// let keys: Vec<Result<String, _>> = trie.iter().collect();
+/// assert!(keys[0].is_ok());
+/// assert_eq!(keys[1].as_ref().unwrap_err().as_bytes(), [0xff]);
+/// ```
+impl<A, M, C> TryFromIterator<A, ResultCollect<M>> for Result<C, C::Error>
+where
+    C: TryFromIterator<A, M>,
+{
+    type Error = std::convert::Infallible;
+    fn try_from_iter<T>(iter: T) -> Result<Self, Self::Error>
+    where
+        Self: Sized,
+        T: IntoIterator<Item = A>,
+    {
+        Ok(C::try_from_iter(iter))
+    }
+}
+
+/// Collections that can stand in for a failed collection.
+pub trait Lossy: Sized {
+    /// Error of the failed collection.
+    type Error;
+    /// Return the collection, or a lossy replacement for it on error.
+    fn lossy(result: Result<Self, Self::Error>) -> Self;
+}
+
+impl Lossy for String {
+    type Error = std::string::FromUtf8Error;
+    /// Replace invalid UTF-8 with U+FFFD.
+    fn lossy(result: Result<Self, Self::Error>) -> Self {
+        result.unwrap_or_else(|e| String::from_utf8_lossy(e.as_bytes()).into_owned())
+    }
+}
+
+/// Items of search iterators that hold a `Result` collection.
+pub trait LossyItem {
+    /// The item with its collection replaced.
+    type Output;
+    /// Replace a failed collection with a lossy one.
+    fn lossy(self) -> Self::Output;
+}
+
+impl<C: Lossy> LossyItem for Result<C, C::Error> {
+    type Output = C;
+    fn lossy(self) -> C {
+        C::lossy(self)
+    }
+}
+
+impl<C: Lossy, V> LossyItem for (Result<C, C::Error>, V) {
+    type Output = (C, V);
+    fn lossy(self) -> (C, V) {
+        (C::lossy(self.0), self.1)
+    }
+}
+
+/// Add [LossyIterator::lossy] to iterators of [LossyItem]s.
+pub trait LossyIterator: Iterator {
+    /// Replace failed collections with lossy ones.
+    ///
+    /// ```
+    /// use trie_rs::try_collect::LossyIterator;
+    /// use trie_rs::map::Trie;
+    ///
+    /// let trie = Trie::from_iter([(&b"ok"[..], 0), (&[b'a', 0xff], 1)]);
+    /// let entries: Vec<(String, &i32)> = trie
+    ///     .iter::<Result<String, _>, _>()
+    ///     .lossy()
+    ///     .collect();
+    /// assert_eq!(entries, [("a\u{fffd}".to_string(), &1), ("ok".to_string(), &0)]);
+    ///
+    /// // Keys alone too, e.g. a postfix starting between the bytes of 'é'.
+    /// let set = trie_rs::Trie::from_iter(["café"]);
+    /// let postfixes: Vec<String> = set
+    ///     .postfix_search::<Result<String, _>, _>(b"caf\xc3")
+    ///     .lossy()
+    ///     .collect();
+    /// assert_eq!(postfixes, ["\u{fffd}"]);
+    /// ```
+    fn lossy(self) -> LossyIter<Self>
+    where
+        Self: Sized,
+        Self::Item: LossyItem,
+    {
+        LossyIter(self)
+    }
+}
+
+impl<I: Iterator> LossyIterator for I {}
+
+#[derive(Debug, Clone)]
+/// Iterator returned by [LossyIterator::lossy].
+pub struct LossyIter<I>(pub(crate) I);
+
+impl<I> Iterator for LossyIter<I>
+where
+    I: Iterator,
+    I::Item: LossyItem,
+{
+    type Item = <I::Item as LossyItem>::Output;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(LossyItem::lossy)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
@@ -0,0 +1,168 @@
+//! JavaScript bindings under the crate feature "wasm", for autocomplete in the
+//! browser without a wrapper crate.
+//!
+//! Build with e.g. `wasm-pack build --features wasm --profile wasm-release`,
+//! which optimizes for size. From JavaScript:
+//!
+//! ```js
+//! const trie = new WasmTrie(new TextEncoder().encode("app\napple\nbanana"));
+//! trie.contains("app");               // true
+//! trie.predictiveSearch("app", 10);   // ["app", "apple"]
+//! const search = trie.incSearch();
+//! search.push("ap");                  // true
+//! search.completions(10);             // ["app", "apple"]
+//! ```
+use crate::inc_search::{IncSearch, Position};
+use crate::map::TrieBuilder;
+use crate::try_collect::LossyIterator;
+use crate::Trie;
+use std::rc::Rc;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// A trie of UTF-8 keys.
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct WasmTrie(Rc<Trie<u8>>);
+
+fn completions(trie: &Trie<u8>, prefix: &[u8], limit: usize) -> Vec<String> {
+    trie.predictive_search::<Result<String, _>, _>(prefix)
+        .lossy()
+        .take(limit)
+        .collect()
+}
+
+#[wasm_bindgen]
+impl WasmTrie {
+    /// Build a trie from the bytes of a `Uint8Array` holding keys separated by
+    /// `'\n'`. Empty keys are skipped.
+    #[wasm_bindgen(constructor)]
+    pub fn new(keys: &[u8]) -> WasmTrie {
+        let mut builder = TrieBuilder::new();
+        for key in keys.split(|b| *b == b'\n').filter(|key| !key.is_empty()) {
+            builder.push(key, ());
+        }
+        WasmTrie(Rc::new(Trie(builder.build())))
+    }
+
+    /// Return true if `key` is in the trie.
+    pub fn contains(&self, key: &str) -> bool {
+        self.0.contains(key)
+    }
+
+    /// Return true if `query` is a prefix of some key.
+    #[wasm_bindgen(js_name = isPrefix)]
+    pub fn is_prefix(&self, query: &str) -> bool {
+        self.0.is_prefix(query)
+    }
+
+    /// Return up to `limit` keys starting with `query`, in order.
+    #[wasm_bindgen(js_name = predictiveSearch)]
+    pub fn predictive_search(&self, query: &str, limit: usize) -> Vec<String> {
+        completions(&self.0, query.as_bytes(), limit)
+    }
+
+    /// Return the keys that are prefixes of `query`. A key may end within a
+    /// character of `query`, so invalid UTF-8 is replaced by U+FFFD.
+    #[wasm_bindgen(js_name = commonPrefixSearch)]
+    pub fn common_prefix_search(&self, query: &str) -> Vec<String> {
+        self.0
+            .common_prefix_search::<Result<String, _>, _>(query)
+            .lossy()
+            .collect()
+    }
+
+    /// Start an incremental search at the empty query.
+    #[wasm_bindgen(js_name = incSearch)]
+    pub fn inc_search(&self) -> WasmIncSearch {
+        WasmIncSearch {
+            position: IncSearch::new(&self.0 .0).into(),
+            trie: Rc::clone(&self.0),
+        }
+    }
+}
+
+/// An incremental search on a [WasmTrie], typed into one piece at a time.
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct WasmIncSearch {
+    trie: Rc<Trie<u8>>,
+    position: Position,
+}
+
+impl WasmIncSearch {
+    fn inc_search(&self) -> IncSearch<'_, u8, ()> {
+        IncSearch::resume(&self.trie.0, self.position)
+    }
+}
+
+#[wasm_bindgen]
+impl WasmIncSearch {
+    /// Append `text` to the query. Returns false and leaves the query as it
+    /// was if no key continues with it.
+    pub fn push(&mut self, text: &str) -> bool {
+        let mut inc_search = self.inc_search();
+        if inc_search.query_until(text).is_err() {
+            return false;
+        }
+        self.position = inc_search.into();
+        true
+    }
+
+    /// Return true if the query is a key.
+    #[wasm_bindgen(js_name = isMatch)]
+    pub fn is_match(&self) -> bool {
+        self.inc_search().value().is_some()
+    }
+
+    /// Return the query so far.
+    pub fn query(&self) -> String {
+        self.inc_search().prefix()
+    }
+
+    /// Return up to `limit` keys starting with the query, in order.
+    pub fn completions(&self, limit: usize) -> Vec<String> {
+        let prefix: Vec<u8> = self.inc_search().prefix();
+        completions(&self.trie, &prefix, limit)
+    }
+
+    /// Go back to the empty query.
+    pub fn reset(&mut self) {
+        let mut inc_search = self.inc_search();
+        inc_search.reset();
+        self.position = inc_search.into();
+    }
+}
+
+#[cfg(test)]
+mod wasm_tests {
+    use super::WasmTrie;
+
+    #[test]
+    fn queries() {
+        let trie = WasmTrie::new("app\napple\n\nbanana\nアップル".as_bytes());
+        assert!(trie.contains("app"));
+        assert!(!trie.contains("ap"));
+        assert!(trie.is_prefix("アッ"));
+        assert_eq!(trie.predictive_search("a", 10), ["app", "apple"]);
+        assert_eq!(trie.predictive_search("", 1), ["app"]);
+        assert_eq!(trie.common_prefix_search("apples"), ["app", "apple"]);
+
+        let mut search = trie.inc_search();
+        assert!(search.push("ap"));
+        assert!(!search.is_match());
+        assert!(!search.push("x"));
+        assert!(search.push("p"));
+        assert!(search.is_match());
+        assert_eq!(search.query(), "app");
+        assert_eq!(search.completions(10), ["app", "apple"]);
+        search.reset();
+        assert_eq!(search.completions(10).len(), 4);
+    }
+
+    #[test]
+    fn keys_ending_within_a_character() {
+        // "あ" is e3 81 82 in UTF-8.
+        let trie = WasmTrie::new(b"\xe3\nx");
+        assert_eq!(trie.common_prefix_search("あ"), ["\u{fffd}"]);
+    }
+}
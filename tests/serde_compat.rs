@@ -0,0 +1,138 @@
+//! Tries serialized by earlier releases must keep loading, or fail with a
+//! format version error. The `_v0` fixtures under `tests/fixtures` are bare
+//! tries written by trie-rs 0.4.2, before tries were versioned. The others
+//! were written when their [FORMAT_VERSION] was introduced; if serializing
+//! the same tries today writes anything else, the layout changed and
+//! [FORMAT_VERSION] must be bumped, with a new fixture added next to the old
+//! ones.
+//!
+//! Set `TRIE_RS_WRITE_FIXTURES=1` to write the fixtures of the current
+//! version.
+#![cfg(feature = "serde")]
+
+use trie_rs::map::{self, FormatVersionMismatch, Versioned, FORMAT_VERSION};
+use trie_rs::Trie;
+
+fn map_trie() -> map::Trie<u8, u32> {
+    map::Trie::from_iter([
+        ("a", 0),
+        ("app", 1),
+        ("apple", 2),
+        ("better", 3),
+        ("application", 4),
+        ("アップル🍎", 5),
+    ])
+}
+
+fn set_trie() -> Trie<char> {
+    let keys = ["a", "app", "apple", "better", "application", "アップル🍎"];
+    Trie::from_iter(keys.map(|key| key.chars().collect::<Vec<char>>()))
+}
+
+fn fixture(name: &str, json: String) -> String {
+    let path = format!(
+        "{}/tests/fixtures/{}_v{}.json",
+        env!("CARGO_MANIFEST_DIR"),
+        name,
+        FORMAT_VERSION
+    );
+    if std::env::var_os("TRIE_RS_WRITE_FIXTURES").is_some() {
+        std::fs::write(&path, json + "\n").unwrap();
+    }
+    std::fs::read_to_string(&path).unwrap()
+}
+
+#[test]
+fn fixtures_load() {
+    let json = serde_json::to_string(&Versioned(map_trie())).unwrap();
+    let stored = fixture("map_trie", json.clone());
+    assert_eq!(stored.trim_end(), json, "the layout changed");
+    let Versioned(trie): Versioned<map::Trie<u8, u32>> = serde_json::from_str(&stored).unwrap();
+    let original = map_trie();
+    let entries: Vec<(String, &u32)> = trie.iter().collect();
+    assert_eq!(entries, original.iter().collect::<Vec<(String, &u32)>>());
+    assert_eq!(trie.exact_match("アップル🍎"), Some(&5));
+
+    let json = serde_json::to_string(&Versioned(set_trie())).unwrap();
+    let stored = fixture("set_trie", json.clone());
+    assert_eq!(stored.trim_end(), json, "the layout changed");
+    let Versioned(trie): Versioned<Trie<char>> = serde_json::from_str(&stored).unwrap();
+    let keys: Vec<String> = trie.iter().collect();
+    assert_eq!(keys, set_trie().iter().collect::<Vec<String>>());
+}
+
+#[test]
+fn release_0_4_2_fails_loudly() {
+    let expected = FormatVersionMismatch {
+        found: 0,
+        expected: FORMAT_VERSION,
+    }
+    .to_string();
+    let dir = format!("{}/tests/fixtures", env!("CARGO_MANIFEST_DIR"));
+    let stored = std::fs::read_to_string(format!("{}/map_trie_v0.json", dir)).unwrap();
+    let error = serde_json::from_str::<Versioned<map::Trie<u8, u32>>>(&stored).unwrap_err();
+    assert!(error.to_string().contains(&expected), "{}", error);
+    // The layout changed, so the bare trie does not load either.
+    assert!(serde_json::from_str::<map::Trie<u8, u32>>(&stored).is_err());
+
+    let stored = std::fs::read_to_string(format!("{}/set_trie_v0.json", dir)).unwrap();
+    let error = serde_json::from_str::<Versioned<Trie<char>>>(&stored).unwrap_err();
+    assert!(error.to_string().contains(&expected), "{}", error);
+}
+
+#[test]
+fn format_version_1_fails_loudly() {
+    // Version 1 stored a count for every key length up to the longest.
+    let expected = FormatVersionMismatch {
+        found: 1,
+        expected: FORMAT_VERSION,
+    }
+    .to_string();
+    let dir = format!("{}/tests/fixtures", env!("CARGO_MANIFEST_DIR"));
+    let stored = std::fs::read_to_string(format!("{}/map_trie_v1.json", dir)).unwrap();
+    let error = serde_json::from_str::<Versioned<map::Trie<u8, u32>>>(&stored).unwrap_err();
+    assert!(error.to_string().contains(&expected), "{}", error);
+
+    let stored = std::fs::read_to_string(format!("{}/set_trie_v1.json", dir)).unwrap();
+    let error = serde_json::from_str::<Versioned<Trie<char>>>(&stored).unwrap_err();
+    assert!(error.to_string().contains(&expected), "{}", error);
+}
+
+#[test]
+fn other_versions_fail_loudly() {
+    let json = serde_json::to_string(&Versioned(map_trie())).unwrap();
+    let prefix = format!(r#"{{"format_version":{},"#, FORMAT_VERSION);
+    assert!(json.starts_with(&prefix));
+
+    let newer = json.replacen(&prefix, r#"{"format_version":4294967295,"#, 1);
+    let error = serde_json::from_str::<Versioned<map::Trie<u8, u32>>>(&newer).unwrap_err();
+    assert!(
+        error.to_string().contains(&format!(
+            "trie serialized with format version 4294967295, but this version of trie-rs reads {}",
+            FORMAT_VERSION
+        )),
+        "{}",
+        error
+    );
+
+    // Checked before the trie is read, even if it would not parse.
+    let garbled = newer.replacen(r#""trie":{"#, r#""trie":{"garbage":[],"#, 1);
+    let error = serde_json::from_str::<Versioned<map::Trie<u8, u32>>>(&garbled).unwrap_err();
+    assert!(
+        error.to_string().contains("format version 4294967295"),
+        "{}",
+        error
+    );
+
+    // Unversioned data and a trie before its version are rejected too.
+    let bare = serde_json::to_string(&map_trie()).unwrap();
+    let error = serde_json::from_str::<Versioned<map::Trie<u8, u32>>>(&bare).unwrap_err();
+    assert!(error.to_string().contains("format version 0"), "{}", error);
+    let reordered = format!(r#"{{"trie":{},"format_version":{}}}"#, bare, FORMAT_VERSION);
+    let error = serde_json::from_str::<Versioned<map::Trie<u8, u32>>>(&reordered).unwrap_err();
+    assert!(
+        error.to_string().contains("missing field `format_version`"),
+        "{}",
+        error
+    );
+}